@@ -0,0 +1,294 @@
+//! Anthropic Messages APIのクライアント（`anthropic` feature）
+
+use crate::cache::ResponseCache;
+use crate::limits;
+use crate::llm::{self, LlmClient, Message};
+use crate::transcript::{Call, Transcript};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+pub const DEFAULT_MODEL: &str = "claude-sonnet-4-5";
+
+const MESSAGES_URL: &str = "https://api.anthropic.com/v1/messages";
+
+const API_VERSION: &str = "2023-06-01";
+
+// 応答の最大トークン数（Messages APIでは指定が必須）
+const MAX_TOKENS: u32 = 8192;
+
+#[derive(Debug, Serialize)]
+struct MessagesRequest<'a> {
+    model: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<String>,
+    messages: Vec<&'a Message>,
+    max_tokens: u32,
+    temperature: f32,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessagesResponse {
+    content: Vec<ContentBlock>,
+    #[serde(default)]
+    usage: Option<Usage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContentBlock {
+    #[serde(default)]
+    text: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Usage {
+    input_tokens: u64,
+    output_tokens: u64,
+}
+
+// ストリーミング時のイベント（`content_block_delta`のテキストのみ使う）
+#[derive(Debug, Deserialize)]
+struct StreamEvent {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    delta: Option<StreamDelta>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamDelta {
+    #[serde(default)]
+    text: Option<String>,
+}
+
+/// Anthropic Messages APIのクライアント
+#[derive(Debug, Clone)]
+pub struct AnthropicClient {
+    http: reqwest::Client,
+    api_key: String,
+    model: String,
+    temperature: f32,
+    max_output_tokens: Option<u32>,
+    cache: Option<ResponseCache>,
+    transcript: Option<Transcript>,
+}
+
+impl AnthropicClient {
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            http: limits::llm_http_client(),
+            api_key: api_key.into(),
+            model: DEFAULT_MODEL.to_string(),
+            temperature: llm::DEFAULT_TEMPERATURE,
+            max_output_tokens: None,
+            cache: None,
+            transcript: None,
+        }
+    }
+
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = model.into();
+        self
+    }
+
+    pub fn with_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = temperature;
+        self
+    }
+
+    /// 応答の最大トークン数（未指定の場合は`MAX_TOKENS`）
+    pub fn with_max_output_tokens(mut self, max_output_tokens: u32) -> Self {
+        self.max_output_tokens = Some(max_output_tokens);
+        self
+    }
+
+    fn cache_key(&self, messages: &[Message]) -> String {
+        ResponseCache::key(
+            &llm::cache_model(&self.model, self.temperature, self.max_output_tokens),
+            messages,
+        )
+    }
+
+    /// 応答をディスクにキャッシュし、同じモデル・メッセージへの再送信を省く
+    pub fn with_cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// 呼び出しごとのプロンプト・リクエスト・応答を記録する
+    pub fn with_transcript(mut self, transcript: Transcript) -> Self {
+        self.transcript = Some(transcript);
+        self
+    }
+
+    // 呼び出しを記録（--save-transcript指定時のみ）
+    fn record(
+        &self,
+        messages: &[Message],
+        request: Option<&MessagesRequest<'_>>,
+        response: &serde_json::Value,
+        started: Option<Instant>,
+    ) -> Result<()> {
+        let Some(transcript) = &self.transcript else {
+            return Ok(());
+        };
+        let request = request.map(serde_json::to_value).transpose()?;
+        transcript.record(Call {
+            provider: "anthropic",
+            model: &self.model,
+            messages,
+            request: request.as_ref(),
+            response,
+            started,
+        })
+    }
+
+    fn cached(&self, messages: &[Message]) -> Option<String> {
+        let key = self.cache_key(messages);
+        let cached = self.cache.as_ref()?.get(&key)?;
+        tracing::info!(model = %self.model, %key, "キャッシュ済みの応答を使用");
+        Some(cached)
+    }
+
+    fn store(&self, messages: &[Message], content: &str) -> Result<()> {
+        match &self.cache {
+            Some(cache) => cache.put(&self.cache_key(messages), content),
+            None => Ok(()),
+        }
+    }
+
+    // システムメッセージは会話とは別の`system`として送る
+    fn build_request<'a>(&'a self, messages: &'a [Message], stream: bool) -> MessagesRequest<'a> {
+        let (system, conversation): (Vec<&Message>, Vec<&Message>) =
+            messages.iter().partition(|m| m.role == "system");
+        let system = (!system.is_empty()).then(|| {
+            system
+                .iter()
+                .map(|m| m.content.as_str())
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        });
+        MessagesRequest {
+            model: &self.model,
+            system,
+            messages: conversation,
+            max_tokens: self.max_output_tokens.unwrap_or(MAX_TOKENS),
+            temperature: self.temperature,
+            stream,
+        }
+    }
+
+    async fn send(&self, request: &MessagesRequest<'_>) -> Result<reqwest::Response> {
+        let response = self
+            .http
+            .post(MESSAGES_URL)
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", API_VERSION)
+            .json(request)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            anyhow::bail!("Anthropic API error: {}", error_text);
+        }
+        Ok(response)
+    }
+}
+
+impl LlmClient for AnthropicClient {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn complete(&self, messages: &[Message]) -> Result<String> {
+        if let Some(cached) = self.cached(messages) {
+            self.record(
+                messages,
+                None,
+                &serde_json::json!({ "content": cached }),
+                None,
+            )?;
+            return Ok(cached);
+        }
+        llm::ensure_online(&self.model)?;
+
+        let started = Instant::now();
+        let request = self.build_request(messages, false);
+        let body = self.send(&request).await?.text().await?;
+        self.record(
+            messages,
+            Some(&request),
+            &serde_json::from_str(&body)?,
+            Some(started),
+        )?;
+        let response: MessagesResponse = serde_json::from_str(&body)?;
+        let usage = response.usage.as_ref();
+        tracing::info!(
+            model = %self.model,
+            messages = messages.len(),
+            prompt_tokens = usage.map(|u| u.input_tokens),
+            completion_tokens = usage.map(|u| u.output_tokens),
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "Anthropic message"
+        );
+        let content: String = response.content.into_iter().map(|b| b.text).collect();
+        if content.is_empty() {
+            anyhow::bail!("No response from Anthropic");
+        }
+
+        self.store(messages, &content)?;
+        Ok(content)
+    }
+
+    async fn stream(
+        &self,
+        messages: &[Message],
+        mut on_token: impl FnMut(&str) + Send,
+    ) -> Result<String> {
+        if let Some(cached) = self.cached(messages) {
+            self.record(
+                messages,
+                None,
+                &serde_json::json!({ "content": cached }),
+                None,
+            )?;
+            on_token(&cached);
+            return Ok(cached);
+        }
+        llm::ensure_online(&self.model)?;
+
+        let started = Instant::now();
+        let request = self.build_request(messages, true);
+        let response = self.send(&request).await?;
+        let mut content = String::new();
+        llm::read_sse(response, |data| {
+            let event: StreamEvent = serde_json::from_str(data)?;
+            if event.kind == "content_block_delta"
+                && let Some(text) = event.delta.and_then(|d| d.text)
+            {
+                on_token(&text);
+                content.push_str(&text);
+            }
+            Ok(())
+        })
+        .await?;
+        tracing::info!(
+            model = %self.model,
+            messages = messages.len(),
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "Anthropic message (stream)"
+        );
+        self.record(
+            messages,
+            Some(&request),
+            &serde_json::json!({ "content": content }),
+            Some(started),
+        )?;
+
+        self.store(messages, &content)?;
+        Ok(content)
+    }
+}
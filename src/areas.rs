@@ -0,0 +1,126 @@
+//! モノレポの変更ファイルのパスによる領域別の内訳（`--monorepo`・設定ファイルの`[monorepo]`）
+//!
+//! 大きなリポジトリへの貢献が1つのリポジトリとしてまとめて数えられないよう、変更ファイルの
+//! パスの先頭のディレクトリ（既定は2階層、例: `services/payments`・`infra/terraform`）を
+//! 領域として、PR数と変更行数を集計します。変更ファイルの取得が必要です。
+
+use crate::github::PullRequest;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 領域とみなすディレクトリの既定の階層数
+pub const DEFAULT_DEPTH: usize = 2;
+
+/// リポジトリ直下のファイルの領域名
+pub const ROOT: &str = "(ルート)";
+
+/// 設定ファイルの`[monorepo]`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct MonorepoConfig {
+    /// 領域別に集計するリポジトリ（`owner/repo`、`--monorepo`に追加される）
+    pub repositories: Vec<String>,
+    /// 領域とみなすディレクトリの階層数
+    pub depth: usize,
+}
+
+impl Default for MonorepoConfig {
+    fn default() -> Self {
+        Self {
+            repositories: Vec::new(),
+            depth: DEFAULT_DEPTH,
+        }
+    }
+}
+
+impl MonorepoConfig {
+    pub fn is_empty(&self) -> bool {
+        self.repositories.is_empty()
+    }
+
+    pub fn contains(&self, repository: &str) -> bool {
+        self.repositories
+            .iter()
+            .any(|r| r.eq_ignore_ascii_case(repository))
+    }
+
+    /// `pr`の変更ファイルが属する領域（重複なし、名前順、モノレポ以外は空）
+    pub fn areas<'a>(&self, pr: &'a PullRequest) -> Vec<&'a str> {
+        if !self.contains(&pr.repository.name_with_owner) {
+            return Vec::new();
+        }
+        let mut areas: Vec<&str> = pr
+            .files
+            .iter()
+            .map(|file| area(&file.path, self.depth))
+            .collect();
+        areas.sort();
+        areas.dedup();
+        areas
+    }
+}
+
+/// `path`の先頭から`depth`階層までのディレクトリ（直下のファイルは[`ROOT`]）
+pub fn area(path: &str, depth: usize) -> &str {
+    let Some((dir, _)) = path.rsplit_once('/') else {
+        return ROOT;
+    };
+    let end = dir
+        .match_indices('/')
+        .nth(depth.max(1) - 1)
+        .map_or(dir.len(), |(i, _)| i);
+    &dir[..end]
+}
+
+/// モノレポの領域ごとのPR数と変更行数
+#[derive(Debug, Clone, Serialize)]
+pub struct AreaStats {
+    pub repository: String,
+    pub area: String,
+    /// 領域内のファイルを変更したPR数（複数の領域にまたがるPRはそれぞれで数える）
+    pub prs: usize,
+    /// 領域内の変更行数（追加+削除）
+    pub lines: usize,
+}
+
+impl AreaStats {
+    /// 表示用の名前（例: `acme/mono:services/payments`）
+    pub fn label(&self) -> String {
+        format!("{}:{}", self.repository, self.area)
+    }
+}
+
+/// `config`のリポジトリのPRを領域ごとに集計（PR数・変更行数の多い順）
+pub fn breakdown(prs: &[PullRequest], config: &MonorepoConfig) -> Vec<AreaStats> {
+    let mut stats: HashMap<(&str, &str), (usize, usize)> = HashMap::new();
+    for pr in prs {
+        let repository = pr.repository.name_with_owner.as_str();
+        for area in config.areas(pr) {
+            stats.entry((repository, area)).or_default().0 += 1;
+        }
+        if !config.contains(repository) {
+            continue;
+        }
+        for file in &pr.files {
+            let key = (repository, area(&file.path, config.depth));
+            stats.entry(key).or_default().1 += file.additions + file.deletions;
+        }
+    }
+
+    let mut sorted: Vec<AreaStats> = stats
+        .into_iter()
+        .map(|((repository, area), (prs, lines))| AreaStats {
+            repository: repository.to_string(),
+            area: area.to_string(),
+            prs,
+            lines,
+        })
+        .collect();
+    sorted.sort_by(|a, b| {
+        b.prs
+            .cmp(&a.prs)
+            .then_with(|| b.lines.cmp(&a.lines))
+            .then_with(|| a.label().cmp(&b.label()))
+    });
+    sorted
+}
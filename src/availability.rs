@@ -0,0 +1,63 @@
+//! 対象者の在籍期間と稼働率（設定ファイルの`[people.<login>]`）
+//!
+//! 期間の途中で参加・異動した人やパートタイムの人を、期間全体をフルタイムで働いた人と
+//! 同じ件数の基準で評価しないよう、評価期間のうち実際に稼働できた割合を求めて
+//! プロンプトに含めます。`batch submit`で複数人を生成する場合も一人ずつ適用します。
+
+use crate::github::SearchQuery;
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+/// 設定ファイルの`[people.<login>]`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PersonConfig {
+    /// 稼働率（0より大きく1以下、例: 週3日のパートタイムは0.6）
+    pub capacity: Option<f32>,
+    /// 参加日（評価期間の途中で参加・異動してきた場合）
+    pub joined: Option<NaiveDate>,
+    /// 離任日（評価期間の途中で異動・休職した場合）
+    pub left: Option<NaiveDate>,
+}
+
+/// 評価期間のうち稼働できた期間と割合
+#[derive(Debug, Clone, PartialEq)]
+pub struct Availability {
+    /// 評価期間の日数
+    pub period_days: i64,
+    /// 在籍していた期間（開始日, 終了日）
+    pub active_since: NaiveDate,
+    pub active_until: NaiveDate,
+    /// 稼働率（0より大きく1以下）
+    pub capacity: f32,
+}
+
+impl Availability {
+    /// 評価期間と設定から求める（期間全体をフルタイムで稼働した場合や、期間を解釈できない場合は`None`）
+    pub fn compute(query: &SearchQuery, person: &PersonConfig) -> Option<Self> {
+        let since = NaiveDate::parse_from_str(&query.since, "%Y-%m-%d").ok()?;
+        let until = NaiveDate::parse_from_str(&query.until, "%Y-%m-%d").ok()?;
+        let active_since = person.joined.map_or(since, |joined| joined.max(since));
+        let active_until = person.left.map_or(until, |left| left.min(until));
+        let capacity = person.capacity.unwrap_or(1.0).clamp(0.01, 1.0);
+        if active_since == since && active_until == until && capacity >= 1.0 {
+            return None;
+        }
+        Some(Self {
+            period_days: (until - since).num_days() + 1,
+            active_since,
+            active_until: active_until.max(active_since),
+            capacity,
+        })
+    }
+
+    /// 在籍していた日数
+    pub fn active_days(&self) -> i64 {
+        (self.active_until - self.active_since).num_days() + 1
+    }
+
+    /// 評価期間全体をフルタイムで稼働した場合に対する割合（0〜1）
+    pub fn ratio(&self) -> f32 {
+        self.active_days() as f32 / self.period_days.max(1) as f32 * self.capacity
+    }
+}
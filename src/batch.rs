@@ -0,0 +1,287 @@
+//! OpenAI Batch APIによる一括生成（`easyhyoka batch submit/status/collect`）
+//!
+//! 複数の対象者のプロンプトを1つのJSONLにまとめて送信し、24時間以内に処理される代わりに
+//! 通常の半額で生成します。送信したバッチのIDと対象者の対応は
+//! `~/.local/share/easyhyoka/batches/`などに保存し、後から状態の確認と結果の回収を行います。
+
+use crate::github::SearchQuery;
+use crate::limits;
+use crate::llm::{LlmClient, Message, OpenAiClient};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const FILES_URL: &str = "https://api.openai.com/v1/files";
+
+const BATCHES_URL: &str = "https://api.openai.com/v1/batches";
+
+const ENDPOINT: &str = "/v1/chat/completions";
+
+// multipart/form-dataの区切り
+const BOUNDARY: &str = "easyhyoka-batch-boundary";
+
+/// バッチで生成する1件（1人分）のリクエスト
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub query: SearchQuery,
+    pub system_prompt: String,
+    pub prompt: String,
+}
+
+/// 送信済みのバッチと、リクエストごとの対象
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Job {
+    pub batch_id: String,
+    /// 送信日時（RFC 3339）
+    pub submitted_at: String,
+    pub model: String,
+    pub items: Vec<JobItem>,
+}
+
+impl Job {
+    pub fn save(&self, dir: &Path) -> Result<PathBuf> {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("バッチの保存先を作成できません: {}", dir.display()))?;
+        let path = dir.join(format!("{}.json", self.batch_id));
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("バッチを保存できません: {}", path.display()))?;
+        Ok(path)
+    }
+
+    pub fn load(dir: &Path, batch_id: &str) -> Result<Self> {
+        let path = dir.join(format!("{batch_id}.json"));
+        let json = std::fs::read_to_string(&path).with_context(|| {
+            format!(
+                "送信したバッチが見つかりません: {batch_id}（easyhyoka batch statusで確認できます）"
+            )
+        })?;
+        serde_json::from_str(&json)
+            .with_context(|| format!("バッチの形式が不正です: {}", path.display()))
+    }
+
+    /// 保存済みのジョブ（送信の古い順）
+    pub fn list(dir: &Path) -> Result<Vec<Self>> {
+        let Ok(read_dir) = std::fs::read_dir(dir) else {
+            return Ok(Vec::new());
+        };
+        let mut jobs = Vec::new();
+        for path in read_dir.filter_map(|e| e.ok().map(|e| e.path())) {
+            if path.extension().is_some_and(|ext| ext == "json") {
+                let json = std::fs::read_to_string(&path)?;
+                jobs.push(
+                    serde_json::from_str::<Self>(&json)
+                        .with_context(|| format!("バッチの形式が不正です: {}", path.display()))?,
+                );
+            }
+        }
+        jobs.sort_by(|a, b| a.submitted_at.cmp(&b.submitted_at));
+        Ok(jobs)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobItem {
+    /// バッチ内でリクエストを識別するID
+    pub custom_id: String,
+    pub query: SearchQuery,
+}
+
+/// バッチの処理状況
+#[derive(Debug, Clone, Deserialize)]
+pub struct Status {
+    pub id: String,
+    /// `validating`・`in_progress`・`completed`・`failed`・`expired`・`cancelled`など
+    pub status: String,
+    #[serde(default)]
+    pub output_file_id: Option<String>,
+    #[serde(default)]
+    pub error_file_id: Option<String>,
+    #[serde(default)]
+    pub request_counts: RequestCounts,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RequestCounts {
+    pub total: usize,
+    pub completed: usize,
+    pub failed: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct UploadedFile {
+    id: String,
+}
+
+// 出力ファイルの1行
+#[derive(Debug, Deserialize)]
+struct OutputLine {
+    custom_id: String,
+    #[serde(default)]
+    response: Option<OutputResponse>,
+    #[serde(default)]
+    error: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OutputResponse {
+    status_code: u16,
+    body: serde_json::Value,
+}
+
+/// Batch APIのクライアント
+#[derive(Debug, Clone)]
+pub struct BatchClient {
+    http: reqwest::Client,
+    api_key: String,
+}
+
+impl BatchClient {
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            http: limits::http_client(),
+            api_key: api_key.into(),
+        }
+    }
+
+    /// `requests`を1つのバッチとして送信し、保存用のジョブを返す
+    ///
+    /// モデル・温度・最大トークン数・推論の深さは`chat`の設定で、通常の生成と同じ本文を送ります。
+    pub async fn submit(&self, chat: &OpenAiClient, requests: &[Request]) -> Result<Job> {
+        let mut jsonl = String::new();
+        let mut items = Vec::with_capacity(requests.len());
+        for (i, request) in requests.iter().enumerate() {
+            let custom_id = format!("{}-{}", i + 1, request.query.author);
+            let line = serde_json::json!({
+                "custom_id": custom_id,
+                "method": "POST",
+                "url": ENDPOINT,
+                "body": chat.request_body(&[
+                    Message::system(&request.system_prompt),
+                    Message::user(&request.prompt),
+                ])?,
+            });
+            jsonl.push_str(&format!("{line}\n"));
+            items.push(JobItem {
+                custom_id,
+                query: request.query.clone(),
+            });
+        }
+
+        let body = format!(
+            "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"purpose\"\r\n\r\nbatch\r\n--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"easyhyoka-batch.jsonl\"\r\nContent-Type: application/jsonl\r\n\r\n{jsonl}\r\n--{BOUNDARY}--\r\n"
+        );
+        let response = self
+            .http
+            .post(FILES_URL)
+            .bearer_auth(&self.api_key)
+            .header(
+                "Content-Type",
+                format!("multipart/form-data; boundary={BOUNDARY}"),
+            )
+            .body(body)
+            .send()
+            .await?;
+        let file: UploadedFile = check(response).await?.json().await?;
+
+        let response = self
+            .http
+            .post(BATCHES_URL)
+            .bearer_auth(&self.api_key)
+            .json(&serde_json::json!({
+                "input_file_id": file.id,
+                "endpoint": ENDPOINT,
+                "completion_window": "24h",
+            }))
+            .send()
+            .await?;
+        let status: Status = check(response).await?.json().await?;
+        tracing::info!(batch_id = %status.id, requests = items.len(), "OpenAI batch submitted");
+
+        Ok(Job {
+            batch_id: status.id,
+            submitted_at: chrono::Local::now().to_rfc3339(),
+            model: chat.model().to_string(),
+            items,
+        })
+    }
+
+    pub async fn status(&self, batch_id: &str) -> Result<Status> {
+        let response = self
+            .http
+            .get(format!("{BATCHES_URL}/{batch_id}"))
+            .bearer_auth(&self.api_key)
+            .send()
+            .await?;
+        Ok(check(response).await?.json().await?)
+    }
+
+    /// 完了したバッチの結果を、ジョブのリクエスト順に（対象, サマリーまたはエラー）で返す
+    pub async fn collect(&self, job: &Job) -> Result<Vec<(JobItem, Result<String>)>> {
+        let status = self.status(&job.batch_id).await?;
+        if status.status != "completed" {
+            anyhow::bail!(
+                "バッチ{}はまだ完了していません（状態: {}）",
+                job.batch_id,
+                status.status
+            );
+        }
+        let mut lines = Vec::new();
+        for file_id in [&status.output_file_id, &status.error_file_id]
+            .into_iter()
+            .flatten()
+        {
+            let response = self
+                .http
+                .get(format!("{FILES_URL}/{file_id}/content"))
+                .bearer_auth(&self.api_key)
+                .send()
+                .await?;
+            let content = check(response).await?.text().await?;
+            for line in content.lines().filter(|l| !l.trim().is_empty()) {
+                lines.push(serde_json::from_str::<OutputLine>(line)?);
+            }
+        }
+
+        Ok(job
+            .items
+            .iter()
+            .map(|item| {
+                let result = match lines.iter().find(|l| l.custom_id == item.custom_id) {
+                    Some(line) => summary(line),
+                    None => Err(anyhow::anyhow!("バッチの結果に含まれていません")),
+                };
+                (item.clone(), result)
+            })
+            .collect())
+    }
+}
+
+// 出力ファイルの1行からサマリー本文を取り出す
+fn summary(line: &OutputLine) -> Result<String> {
+    if let Some(error) = &line.error
+        && !error.is_null()
+    {
+        anyhow::bail!("OpenAI API error: {error}");
+    }
+    let response = line.response.as_ref().context("応答がありません")?;
+    if response.status_code != 200 {
+        anyhow::bail!("OpenAI API error: {}", response.body);
+    }
+    response.body["choices"][0]["message"]["content"]
+        .as_str()
+        .map(str::to_string)
+        .context("No response from OpenAI")
+}
+
+async fn check(response: reqwest::Response) -> Result<reqwest::Response> {
+    if !response.status().is_success() {
+        let error_text = response.text().await?;
+        anyhow::bail!("OpenAI API error: {}", error_text);
+    }
+    Ok(response)
+}
+
+/// 送信したジョブの保存先（`~/.local/share/easyhyoka/batches`など）
+pub fn default_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("easyhyoka").join("batches"))
+}
@@ -0,0 +1,322 @@
+//! プロンプトのトークン数の上限（`--max-prompt-tokens`）に合わせた活動データの削減
+//!
+//! 上限を超える場合は、指定された順（既定は本文→コメント→古いPR/Issue）にデータを削り、
+//! 削った件数を[`Truncated`]として返します。トークン数は[`estimate_tokens`]の概算です。
+
+use crate::github::{Issue, PullRequest, SearchQuery};
+use crate::llm::estimate_tokens;
+use crate::report::{self, PromptOptions};
+use anyhow::Result;
+use std::collections::HashSet;
+
+/// データの削り方
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Truncation {
+    /// PR/Issueの本文を省く（古いものから）
+    Bodies,
+    /// コメントとレビューの本文を省く（古いPR/Issueから）
+    Comments,
+    /// 古いPR/Issueそのものを除外する
+    Oldest,
+}
+
+/// 既定の削る順
+pub const DEFAULT_STRATEGIES: [Truncation; 3] =
+    [Truncation::Bodies, Truncation::Comments, Truncation::Oldest];
+
+// JSONのキーや区切りなど、PR/Issue 1件あたりの本文以外のトークン数の目安
+const ITEM_OVERHEAD_TOKENS: usize = 40;
+
+/// 上限に合わせて削ったデータの件数
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Truncated {
+    /// 本文を省いたPR/Issue
+    pub bodies: usize,
+    /// 省いたコメント・レビュー
+    pub comments: usize,
+    /// 除外したPR
+    pub prs: usize,
+    /// 除外したIssue
+    pub issues: usize,
+}
+
+impl Truncated {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// 「本文 3件、古いPR 2件」のような説明
+    pub fn describe(&self) -> String {
+        [
+            ("本文", self.bodies),
+            ("コメント・レビュー", self.comments),
+            ("古いPR", self.prs),
+            ("古いIssue", self.issues),
+        ]
+        .into_iter()
+        .filter(|(_, count)| *count > 0)
+        .map(|(label, count)| format!("{label} {count}件"))
+        .collect::<Vec<_>>()
+        .join("、")
+    }
+}
+
+// 作成日時の古い順のPR/Issue
+enum Item {
+    Pr(usize),
+    Issue(usize),
+}
+
+fn oldest_first(prs: &[PullRequest], issues: &[Issue]) -> Vec<Item> {
+    let mut items: Vec<(&str, Item)> = prs
+        .iter()
+        .enumerate()
+        .map(|(i, pr)| (pr.created_at.as_str(), Item::Pr(i)))
+        .chain(
+            issues
+                .iter()
+                .enumerate()
+                .map(|(i, issue)| (issue.created_at.as_str(), Item::Issue(i))),
+        )
+        .collect();
+    items.sort_by_key(|(created_at, _)| *created_at);
+    items.into_iter().map(|(_, item)| item).collect()
+}
+
+/// プロンプトが`max_tokens`に収まるまで、`strategies`の順にデータを削る
+///
+/// すべての方法で削っても収まらない場合は、削れるだけ削った状態で返します。
+pub fn fit(
+    max_tokens: usize,
+    strategies: &[Truncation],
+    query: &SearchQuery,
+    prs: &mut Vec<PullRequest>,
+    issues: &mut Vec<Issue>,
+    options: &PromptOptions,
+) -> Result<Truncated> {
+    let mut truncated = Truncated::default();
+    for strategy in strategies {
+        loop {
+            let tokens = prompt_tokens(query, prs, issues, options)?;
+            let excess = tokens.saturating_sub(max_tokens);
+            if excess == 0 {
+                return Ok(truncated);
+            }
+            let removed = match strategy {
+                Truncation::Bodies => drop_bodies(prs, issues, excess, &mut truncated),
+                Truncation::Comments => drop_comments(prs, issues, excess, &mut truncated),
+                Truncation::Oldest => drop_oldest(prs, issues, excess, &mut truncated),
+            };
+            if !removed {
+                break;
+            }
+        }
+    }
+    Ok(truncated)
+}
+
+/// 現在のデータで構築したプロンプトのトークン数（概算）
+pub fn prompt_tokens(
+    query: &SearchQuery,
+    prs: &[PullRequest],
+    issues: &[Issue],
+    options: &PromptOptions,
+) -> Result<usize> {
+    let prompt = report::build_prompt(query, prs, issues, options)?;
+    Ok(estimate_tokens(options.tone.system_prompt()) + estimate_tokens(&prompt))
+}
+
+// 古いPR/Issueから本文を省き、`excess`トークン分を減らす
+fn drop_bodies(
+    prs: &mut [PullRequest],
+    issues: &mut [Issue],
+    excess: usize,
+    truncated: &mut Truncated,
+) -> bool {
+    let mut freed = 0;
+    for item in oldest_first(prs, issues) {
+        if freed >= excess {
+            break;
+        }
+        let body = match item {
+            Item::Pr(i) => &mut prs[i].body,
+            Item::Issue(i) => &mut issues[i].body,
+        };
+        if let Some(text) = body.take().filter(|text| !text.is_empty()) {
+            freed += estimate_tokens(&text);
+            truncated.bodies += 1;
+        }
+    }
+    freed > 0
+}
+
+// 古いPR/Issueからコメントとレビューの本文を省き、`excess`トークン分を減らす
+fn drop_comments(
+    prs: &mut [PullRequest],
+    issues: &mut [Issue],
+    excess: usize,
+    truncated: &mut Truncated,
+) -> bool {
+    let mut freed = 0;
+    for item in oldest_first(prs, issues) {
+        if freed >= excess {
+            break;
+        }
+        let comments = match item {
+            Item::Pr(i) => {
+                let pr = &mut prs[i];
+                for review in pr.reviews.iter_mut().filter(|r| !r.body.is_empty()) {
+                    freed += estimate_tokens(&std::mem::take(&mut review.body));
+                    truncated.comments += 1;
+                }
+                &mut pr.comments
+            }
+            Item::Issue(i) => &mut issues[i].comments,
+        };
+        for comment in comments.drain(..) {
+            freed += estimate_tokens(&comment.body) + ITEM_OVERHEAD_TOKENS / 4;
+            truncated.comments += 1;
+        }
+    }
+    freed > 0
+}
+
+// 古いPR/Issueから除外し、`excess`トークン分を減らす
+fn drop_oldest(
+    prs: &mut Vec<PullRequest>,
+    issues: &mut Vec<Issue>,
+    excess: usize,
+    truncated: &mut Truncated,
+) -> bool {
+    let mut freed = 0;
+    let mut dropped_prs = HashSet::new();
+    let mut dropped_issues = HashSet::new();
+    for item in oldest_first(prs, issues) {
+        if freed >= excess {
+            break;
+        }
+        let (title, body, comments) = match item {
+            Item::Pr(i) => {
+                dropped_prs.insert(i);
+                (&prs[i].title, &prs[i].body, &prs[i].comments)
+            }
+            Item::Issue(i) => {
+                dropped_issues.insert(i);
+                (&issues[i].title, &issues[i].body, &issues[i].comments)
+            }
+        };
+        freed += ITEM_OVERHEAD_TOKENS
+            + estimate_tokens(title)
+            + body.as_deref().map_or(0, estimate_tokens)
+            + comments
+                .iter()
+                .map(|c| estimate_tokens(&c.body))
+                .sum::<usize>();
+    }
+
+    truncated.prs += dropped_prs.len();
+    truncated.issues += dropped_issues.len();
+    let mut index = 0;
+    prs.retain(|_| {
+        index += 1;
+        !dropped_prs.contains(&(index - 1))
+    });
+    let mut index = 0;
+    issues.retain(|_| {
+        index += 1;
+        !dropped_issues.contains(&(index - 1))
+    });
+    freed > 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query() -> SearchQuery {
+        serde_json::from_value(serde_json::json!({
+            "author": "alice",
+            "since": "2025-01-01",
+            "until": "2025-01-31",
+        }))
+        .unwrap()
+    }
+
+    fn pr(number: u32, created_at: &str, body: &str) -> PullRequest {
+        serde_json::from_value(serde_json::json!({
+            "number": number,
+            "title": format!("PR {number}"),
+            "body": body,
+            "createdAt": created_at,
+            "state": "merged",
+            "url": format!("https://github.com/acme/x/pull/{number}"),
+            "repository": { "nameWithOwner": "acme/x" },
+        }))
+        .unwrap()
+    }
+
+    fn prs() -> Vec<PullRequest> {
+        let body = "変更内容の説明。".repeat(200);
+        vec![
+            pr(2, "2025-01-20T00:00:00Z", &body),
+            pr(1, "2025-01-10T00:00:00Z", &body),
+        ]
+    }
+
+    #[test]
+    fn fit_keeps_data_under_the_limit() {
+        let options = PromptOptions::default();
+        let mut prs = prs();
+        let tokens = prompt_tokens(&query(), &prs, &[], &options).unwrap();
+        let truncated = fit(
+            tokens,
+            &DEFAULT_STRATEGIES,
+            &query(),
+            &mut prs,
+            &mut Vec::new(),
+            &options,
+        )
+        .unwrap();
+        assert!(truncated.is_empty());
+        assert!(prs.iter().all(|pr| pr.body.is_some()));
+    }
+
+    #[test]
+    fn fit_drops_bodies_of_oldest_items_first() {
+        let options = PromptOptions::default();
+        let mut prs = prs();
+        let tokens = prompt_tokens(&query(), &prs, &[], &options).unwrap();
+        let truncated = fit(
+            tokens - 10,
+            &[Truncation::Bodies],
+            &query(),
+            &mut prs,
+            &mut Vec::new(),
+            &options,
+        )
+        .unwrap();
+        assert_eq!(truncated.bodies, 1);
+        assert_eq!(truncated.describe(), "本文 1件");
+        assert!(prs[0].body.is_some());
+        assert!(prs[1].body.is_none());
+    }
+
+    #[test]
+    fn fit_drops_oldest_items() {
+        let options = PromptOptions::default();
+        let mut prs = prs();
+        let tokens = prompt_tokens(&query(), &prs, &[], &options).unwrap();
+        let truncated = fit(
+            tokens - 10,
+            &[Truncation::Oldest],
+            &query(),
+            &mut prs,
+            &mut Vec::new(),
+            &options,
+        )
+        .unwrap();
+        assert_eq!(truncated.prs, 1);
+        assert_eq!(prs.len(), 1);
+        assert_eq!(prs[0].number, 2);
+    }
+}
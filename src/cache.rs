@@ -0,0 +1,69 @@
+//! LLMの応答のディスクキャッシュ
+//!
+//! モデル名と送信するメッセージのハッシュをキーに応答本文を保存し、
+//! 同じデータで再実行したときに生成し直さずに済むようにします（`--no-cache`で無効化）。
+
+use crate::llm::Message;
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+/// 応答本文をファイルとして保存するキャッシュ
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    dir: PathBuf,
+}
+
+impl ResponseCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// デフォルトのキャッシュディレクトリ（`~/.cache/easyhyoka/llm`など）
+    pub fn default_dir() -> Option<PathBuf> {
+        dirs::cache_dir().map(|dir| dir.join("easyhyoka").join("llm"))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// モデル名とメッセージからキャッシュのキー（SHA-256の16進表記）を求める
+    pub fn key(model: &str, messages: &[Message]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(model.as_bytes());
+        for message in messages {
+            // 区切りを入れて、内容の境界が違うだけのメッセージ列を区別する
+            hasher.update([0]);
+            hasher.update(message.role.as_bytes());
+            hasher.update([0]);
+            hasher.update(message.content.as_bytes());
+        }
+        hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect()
+    }
+
+    /// キャッシュ済みの応答（なければ`None`）
+    pub fn get(&self, key: &str) -> Option<String> {
+        std::fs::read_to_string(self.path(key)).ok()
+    }
+
+    pub fn put(&self, key: &str, content: &str) -> Result<()> {
+        std::fs::create_dir_all(&self.dir).with_context(|| {
+            format!(
+                "キャッシュディレクトリを作成できません: {}",
+                self.dir.display()
+            )
+        })?;
+        let path = self.path(key);
+        std::fs::write(&path, content)
+            .with_context(|| format!("キャッシュを書き込めません: {}", path.display()))
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.txt"))
+    }
+}
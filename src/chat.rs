@@ -0,0 +1,102 @@
+//! `--chat` モード: サマリー生成後に追加の質問や修正依頼を対話的に行う
+
+use anyhow::Result;
+use easy_hyoka::llm::{LlmClient, Message};
+use std::io::{BufRead, Write};
+
+const HELP: &str = "質問や修正依頼を入力してください（例: 「リポジトリXの節を詳しく」「インフラの取り組みをもっと目立たせて」）
+  /use          直前の応答を最終的なサマリーとして採用
+  /save <path>  直前の応答をファイルに保存
+  /help         このヘルプを表示
+  /quit         終了（空行のEnterでは何もしません）";
+
+/// 対話セッション（データセットと生成済みサマリーを文脈として保持）
+pub struct ChatSession<'a, C: LlmClient> {
+    client: &'a C,
+    messages: Vec<Message>,
+}
+
+impl<'a, C: LlmClient> ChatSession<'a, C> {
+    /// サマリー生成時のプロンプトと応答を履歴の起点にする
+    pub fn new(client: &'a C, system_prompt: &str, prompt: &str, summary: &str) -> Self {
+        Self {
+            client,
+            messages: vec![
+                Message::system(format!(
+                    "{system_prompt}\n\n生成済みのサマリーに対して、ユーザーから質問や修正依頼が届きます。修正依頼の場合は修正後のサマリー全文を、質問の場合は活動データに基づく回答を返してください。"
+                )),
+                Message::user(prompt),
+                Message::assistant(summary),
+            ],
+        }
+    }
+
+    /// 標準入力から質問を読み、`/quit`または入力終了までやり取りを続ける
+    ///
+    /// `/use`で採用された応答があればそれを、なければ`summary`をそのまま返します。
+    pub async fn run(mut self, summary: String) -> Result<String> {
+        let mut adopted = summary;
+        let mut last_reply = adopted.clone();
+
+        println!("\n{HELP}");
+        let stdin = std::io::stdin();
+        loop {
+            print!("\n> ");
+            std::io::stdout().flush()?;
+
+            let mut line = String::new();
+            if stdin.lock().read_line(&mut line)? == 0 {
+                break;
+            }
+            let input = line.trim();
+
+            match input.split_once(' ').unwrap_or((input, "")) {
+                ("", _) => continue,
+                ("/quit" | "/exit", _) => break,
+                ("/help", _) => println!("{HELP}"),
+                ("/use", _) => {
+                    adopted = last_reply.clone();
+                    println!("直前の応答をサマリーとして採用しました");
+                }
+                ("/save", path) if !path.trim().is_empty() => {
+                    match std::fs::write(path.trim(), &last_reply) {
+                        Ok(()) => println!("保存しました: {}", path.trim()),
+                        Err(e) => eprintln!("保存に失敗しました: {}: {e}", path.trim()),
+                    }
+                }
+                ("/save", _) => println!("保存先のパスを指定してください（/save <path>）"),
+                _ => match self.ask(input).await {
+                    Ok(reply) => last_reply = reply,
+                    Err(e) => eprintln!("応答の生成に失敗しました: {e}"),
+                },
+            }
+        }
+
+        Ok(adopted)
+    }
+
+    // 応答は生成されたそばから表示する
+    async fn ask(&mut self, question: &str) -> Result<String> {
+        self.messages.push(Message::user(question));
+        println!();
+        let reply = self
+            .client
+            .stream(&self.messages, |token| {
+                print!("{token}");
+                let _ = std::io::stdout().flush();
+            })
+            .await;
+        println!();
+        match reply {
+            Ok(reply) => {
+                self.messages.push(Message::assistant(&reply));
+                Ok(reply)
+            }
+            Err(e) => {
+                // 失敗した質問は履歴に残さない
+                self.messages.pop();
+                Err(e)
+            }
+        }
+    }
+}
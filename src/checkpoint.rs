@@ -0,0 +1,116 @@
+//! 取得途中の状態の保存（中断した取得の再開用）
+//!
+//! 検索結果と、PRごとの追加取得（レビュー・変更ファイルなど）の進み具合を
+//! 検索条件ごとのファイルに保存し、再実行時に続きから取得できるようにします。
+
+use crate::github::{
+    Community, Discussion, Issue, Membership, Mentions, PullRequest, Release, SearchQuery,
+    TriageItem,
+};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// 検索条件ごとの取得途中の状態
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Checkpoint {
+    #[serde(skip)]
+    path: Option<PathBuf>,
+    /// 検索済みのPR（追加取得の結果を含む）
+    pub prs: Option<Vec<PullRequest>>,
+    pub issues: Option<Vec<Issue>>,
+    pub discussions: Option<Vec<Discussion>>,
+    pub releases: Option<Vec<Release>>,
+    pub community: Option<Community>,
+    pub membership: Option<Membership>,
+    pub triage: Option<Vec<TriageItem>>,
+    pub mentions: Option<Mentions>,
+    /// 追加取得の処理ごとの、取得済みのPR数
+    pub steps: BTreeMap<String, usize>,
+}
+
+impl Checkpoint {
+    /// `query`の保存済みの状態を読み込む（なければ空の状態）
+    ///
+    /// 保存先のディレクトリがない環境では、保存しない空の状態を返します。
+    pub fn load(query: &SearchQuery) -> Self {
+        let Some(path) = default_dir().map(|dir| dir.join(format!("{}.json", key(query)))) else {
+            return Self::default();
+        };
+        let checkpoint = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|json| serde_json::from_str::<Self>(&json).ok())
+            .unwrap_or_default();
+        Self {
+            path: Some(path),
+            ..checkpoint
+        }
+    }
+
+    /// 保存済みの取得結果を破棄（保存先はそのまま）
+    pub fn reset(&mut self) {
+        *self = Self {
+            path: self.path.take(),
+            ..Self::default()
+        };
+    }
+
+    /// 保存済みの取得結果があるか
+    pub fn is_resumed(&self) -> bool {
+        self.prs.is_some()
+    }
+
+    /// 追加取得の処理`step`で取得済みのPR数
+    pub fn progress(&self, step: &str) -> usize {
+        self.steps.get(step).copied().unwrap_or(0)
+    }
+
+    /// 処理`step`の進み具合と、その時点のPRを保存
+    pub fn record(&mut self, step: &str, done: usize, prs: &[PullRequest]) -> Result<()> {
+        self.steps.insert(step.to_string(), done);
+        self.prs = Some(prs.to_vec());
+        self.save()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).with_context(|| {
+                format!("再開用のディレクトリを作成できません: {}", dir.display())
+            })?;
+        }
+        std::fs::write(path, serde_json::to_string(self)?)
+            .with_context(|| format!("取得途中の状態を保存できません: {}", path.display()))
+    }
+
+    /// 取得の完了後に保存済みの状態を削除
+    pub fn remove(&self) {
+        if let Some(path) = &self.path {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// 保存先のディレクトリ（`~/.cache/easyhyoka/checkpoints`など）
+pub fn default_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join("easyhyoka").join("checkpoints"))
+}
+
+// 検索条件のハッシュ（SHA-256の先頭16文字）
+fn key(query: &SearchQuery) -> String {
+    // 期間の呼び名は取得する内容に影響しないため含めない
+    let query = SearchQuery {
+        label: None,
+        ..query.clone()
+    };
+    let json = serde_json::to_string(&query).unwrap_or_default();
+    Sha256::digest(json.as_bytes())
+        .iter()
+        .take(8)
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
@@ -0,0 +1,47 @@
+//! システムのクリップボードへのコピー（`--copy`）
+//!
+//! OSごとのコマンド（macOSの`pbcopy`、Windowsの`clip`、Linuxの`wl-copy`・`xclip`・`xsel`）に
+//! 標準入力で渡します。最初に実行できたコマンドを使います。
+
+use anyhow::Result;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+// 試す順のコマンドと引数
+const COMMANDS: &[(&str, &[&str])] = if cfg!(target_os = "macos") {
+    &[("pbcopy", &[])]
+} else if cfg!(windows) {
+    &[("clip", &[])]
+} else {
+    &[
+        ("wl-copy", &[]),
+        ("xclip", &["-selection", "clipboard"]),
+        ("xsel", &["--clipboard", "--input"]),
+    ]
+};
+
+/// `text`をクリップボードにコピー
+pub fn copy(text: &str) -> Result<()> {
+    for (program, args) in COMMANDS {
+        let Ok(mut child) = Command::new(program)
+            .args(*args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            continue;
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes())?;
+        }
+        if child.wait()?.success() {
+            return Ok(());
+        }
+    }
+    let names: Vec<&str> = COMMANDS.iter().map(|(program, _)| *program).collect();
+    anyhow::bail!(
+        "クリップボードにコピーできません（{}のいずれかが必要です）",
+        names.join("・")
+    )
+}
@@ -0,0 +1,173 @@
+//! 埋め込みベクトルによるPRのクラスタリング
+//!
+//! PRのタイトル・descriptionを埋め込み、k-meansでまとめたクラスタに安価なモデルでラベルを付けます。
+//! 関連するPRを一つのプロジェクトとして扱えるよう、クラスタ構成をプロンプトに含めます（`--cluster`）。
+
+use crate::github::PullRequest;
+use crate::llm::{self, LlmClient, OpenAiClient};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+// 埋め込みに使うdescriptionの最大文字数
+const MAX_BODY_CHARS: usize = 2000;
+
+const KMEANS_ITERATIONS: usize = 50;
+
+/// 関連するPRのまとまり（プロジェクト候補）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cluster {
+    pub label: String,
+    pub pr_urls: Vec<String>,
+}
+
+/// PRを埋め込み・クラスタリングし、ラベル付きのクラスタを返す（大きい順）
+///
+/// `k`を省略した場合はPR数から決めます。
+pub async fn cluster_prs(
+    client: &OpenAiClient,
+    prs: &[PullRequest],
+    k: Option<usize>,
+) -> Result<Vec<Cluster>> {
+    if prs.len() < 2 {
+        return Ok(Vec::new());
+    }
+
+    let inputs: Vec<String> = prs.iter().map(embedding_input).collect();
+    let vectors = client.embed(&inputs).await?;
+
+    let k = k
+        .unwrap_or_else(|| default_k(prs.len()))
+        .clamp(1, prs.len());
+    let assignments = kmeans(&vectors, k);
+
+    let mut groups: Vec<Vec<&PullRequest>> = vec![Vec::new(); k];
+    for (pr, cluster) in prs.iter().zip(assignments) {
+        groups[cluster].push(pr);
+    }
+    groups.retain(|g| !g.is_empty());
+    groups.sort_by_key(|g| std::cmp::Reverse(g.len()));
+
+    let labels = label_clusters(client, &groups).await;
+    Ok(groups
+        .into_iter()
+        .enumerate()
+        .map(|(i, group)| Cluster {
+            label: labels
+                .get(i)
+                .cloned()
+                .unwrap_or_else(|| format!("クラスタ{}", i + 1)),
+            pr_urls: group.iter().map(|pr| pr.url.clone()).collect(),
+        })
+        .collect())
+}
+
+fn embedding_input(pr: &PullRequest) -> String {
+    let body: String = pr
+        .body
+        .as_deref()
+        .unwrap_or("")
+        .chars()
+        .take(MAX_BODY_CHARS)
+        .collect();
+    format!("{}\n{}", pr.title, body)
+}
+
+// PR数の平方根程度（2〜12）
+fn default_k(n: usize) -> usize {
+    ((n as f64 / 2.0).sqrt().round() as usize).clamp(2, 12)
+}
+
+/// k-meansで各ベクトルのクラスタ番号を求める
+///
+/// 初期中心は最遠点法で決めるため、同じ入力なら結果は毎回同じになります。
+pub fn kmeans(vectors: &[Vec<f32>], k: usize) -> Vec<usize> {
+    if vectors.is_empty() || k == 0 {
+        return Vec::new();
+    }
+
+    let mut centroids = vec![vectors[0].clone()];
+    while centroids.len() < k {
+        let farthest = vectors
+            .iter()
+            .max_by(|a, b| {
+                nearest(a, &centroids)
+                    .1
+                    .total_cmp(&nearest(b, &centroids).1)
+            })
+            .expect("vectors is not empty");
+        centroids.push(farthest.clone());
+    }
+
+    let mut assignments = vec![0; vectors.len()];
+    for _ in 0..KMEANS_ITERATIONS {
+        let next: Vec<usize> = vectors.iter().map(|v| nearest(v, &centroids).0).collect();
+        let converged = next == assignments;
+        assignments = next;
+
+        for (c, centroid) in centroids.iter_mut().enumerate() {
+            let members: Vec<&Vec<f32>> = vectors
+                .iter()
+                .zip(&assignments)
+                .filter(|(_, a)| **a == c)
+                .map(|(v, _)| v)
+                .collect();
+            if members.is_empty() {
+                continue;
+            }
+            for (d, value) in centroid.iter_mut().enumerate() {
+                *value = members.iter().map(|m| m[d]).sum::<f32>() / members.len() as f32;
+            }
+        }
+
+        if converged {
+            break;
+        }
+    }
+    assignments
+}
+
+// 最も近い中心の番号と距離（二乗）
+fn nearest(vector: &[f32], centroids: &[Vec<f32>]) -> (usize, f32) {
+    centroids
+        .iter()
+        .map(|c| {
+            vector
+                .iter()
+                .zip(c)
+                .map(|(a, b)| (a - b).powi(2))
+                .sum::<f32>()
+        })
+        .enumerate()
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap_or((0, 0.0))
+}
+
+// 各クラスタのPRタイトルから、短いプロジェクト名を付ける（失敗時は空）
+async fn label_clusters(client: &OpenAiClient, groups: &[Vec<&PullRequest>]) -> Vec<String> {
+    let mut prompt = String::from(
+        "以下はGitHubのPull Requestをクラスタリングした結果です。各クラスタに、含まれるPRの共通するテーマを表す短いプロジェクト名（日本語、20文字以内）を付けてください。\n\n",
+    );
+    for (i, group) in groups.iter().enumerate() {
+        prompt.push_str(&format!("## クラスタ{}\n", i + 1));
+        for pr in group.iter().take(20) {
+            prompt.push_str(&format!(
+                "- {} ({})\n",
+                pr.title, pr.repository.name_with_owner
+            ));
+        }
+        prompt.push('\n');
+    }
+    prompt.push_str(&format!(
+        "出力はクラスタ順のプロジェクト名{}件のJSON配列のみとしてください（例: [\"決済基盤の改善\", \"ドキュメント整備\"]）。\n",
+        groups.len()
+    ));
+
+    let labeler = client.clone().with_model(llm::LIGHT_MODEL);
+    let Ok(response) = labeler
+        .chat("あなたはPRの分類を手伝うアシスタントです。", &prompt)
+        .await
+    else {
+        return Vec::new();
+    };
+    serde_json::from_str(llm::strip_code_fence(&response)).unwrap_or_default()
+}
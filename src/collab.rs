@@ -0,0 +1,121 @@
+//! コメント・レビュー・共同コミットから見た協業相手の集計
+
+use crate::github::{CommentAuthor, Issue, PullRequest};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::LazyLock;
+
+/// 協業相手ごとのやり取りの回数
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Collaborator {
+    pub login: String,
+    /// 自分のPR/Issueへのコメント
+    pub comments: usize,
+    /// 自分のPRへのレビュー
+    pub reviews_received: usize,
+    /// 相手のPRへの自分のレビュー
+    pub reviews_given: usize,
+    /// Co-authored-byで共同作成したPR
+    pub co_authored: usize,
+    /// やり取りのあったリポジトリ
+    pub repositories: BTreeSet<String>,
+}
+
+impl Collaborator {
+    pub fn total(&self) -> usize {
+        self.comments + self.reviews_received + self.reviews_given + self.co_authored
+    }
+}
+
+/// `author`の協業相手をやり取りの多い順に集計（本人とbotは除く）
+pub fn network(prs: &[PullRequest], issues: &[Issue], author: &str) -> Vec<Collaborator> {
+    let mut collaborators: HashMap<String, Collaborator> = HashMap::new();
+    let mut record = |login: &str, repo: &str, update: fn(&mut Collaborator)| {
+        if login == author || is_bot(login) {
+            return;
+        }
+        let entry = collaborators
+            .entry(login.to_string())
+            .or_insert_with(|| Collaborator {
+                login: login.to_string(),
+                ..Default::default()
+            });
+        update(entry);
+        entry.repositories.insert(repo.to_string());
+    };
+
+    for pr in prs {
+        let repo = pr.repository.name_with_owner.as_str();
+        if is_own(pr.author.as_ref(), author) {
+            for comment in &pr.comments {
+                if let Some(a) = &comment.author {
+                    record(&a.login, repo, |c| c.comments += 1);
+                }
+            }
+            for review in &pr.reviews {
+                if let Some(a) = &review.author {
+                    record(&a.login, repo, |c| c.reviews_received += 1);
+                }
+            }
+            for login in co_authors(pr.body.as_deref().unwrap_or("")) {
+                record(&login, repo, |c| c.co_authored += 1);
+            }
+        } else if let Some(pr_author) = &pr.author {
+            for _ in &pr.authored_reviews {
+                record(&pr_author.login, repo, |c| c.reviews_given += 1);
+            }
+        }
+    }
+    for issue in issues {
+        if !is_own(issue.author.as_ref(), author) {
+            continue;
+        }
+        for comment in &issue.comments {
+            if let Some(a) = &comment.author {
+                record(&a.login, &issue.repository.name_with_owner, |c| {
+                    c.comments += 1
+                });
+            }
+        }
+    }
+
+    let mut sorted: Vec<Collaborator> = collaborators.into_values().collect();
+    sorted.sort_by(|a, b| {
+        b.total()
+            .cmp(&a.total())
+            .then_with(|| a.login.cmp(&b.login))
+    });
+    sorted
+}
+
+// 作成者の情報がない場合は本人のものとみなす（`--role author`の検索結果）
+pub(crate) fn is_own(item_author: Option<&CommentAuthor>, author: &str) -> bool {
+    item_author.is_none_or(|a| a.login == author)
+}
+
+pub(crate) fn is_bot(login: &str) -> bool {
+    login.ends_with("[bot]") || login.ends_with("-bot") || login == "Unknown"
+}
+
+/// PR本文の`Co-authored-by:`トレーラーから共同作成者を取り出す
+///
+/// GitHubのnoreplyアドレス（`12345+login@users.noreply.github.com`）ならログイン名、それ以外は名前を返します。
+pub fn co_authors(text: &str) -> Vec<String> {
+    static PATTERN: LazyLock<regex::Regex> = LazyLock::new(|| {
+        regex::Regex::new(r"(?mi)^co-authored-by:\s*(.+?)\s*<([^>]+)>")
+            .expect("co-author pattern should be valid")
+    });
+
+    let mut logins = Vec::new();
+    for caps in PATTERN.captures_iter(text) {
+        let email = &caps[2];
+        let login = email
+            .strip_suffix("@users.noreply.github.com")
+            .map(|local| local.rsplit('+').next().unwrap_or(local).to_string())
+            .unwrap_or_else(|| caps[1].to_string());
+        if !logins.contains(&login) {
+            logins.push(login);
+        }
+    }
+    logins
+}
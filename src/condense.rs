@@ -0,0 +1,116 @@
+//! 長いPR/Issue本文の事前の短縮（`--summarize-bodies`）
+//!
+//! 指定した文字数を超える本文を、メインのプロンプトを構築する前に短くします。
+//! 安価なモデルで1件ずつ要約するか、先頭から行単位で切り詰めます。
+
+use crate::github::{Issue, PullRequest};
+use crate::llm::{self, LlmClient, OpenAiClient};
+use anyhow::Result;
+
+/// 本文の短くし方
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Condense {
+    /// 安価なモデルで要約する（失敗した本文は切り詰める）
+    #[default]
+    Llm,
+    /// 先頭から行単位で切り詰める
+    Extract,
+}
+
+const SYSTEM_PROMPT: &str =
+    "あなたはGitHubのPR/Issueの本文を、評価資料の材料として簡潔にまとめるアシスタントです。";
+
+/// 本文が`max_chars`文字を超えるPR/Issueの数
+pub fn count_long(prs: &[PullRequest], issues: &[Issue], max_chars: usize) -> usize {
+    prs.iter()
+        .map(|pr| &pr.body)
+        .chain(issues.iter().map(|issue| &issue.body))
+        .filter(|body| is_long(body, max_chars))
+        .count()
+}
+
+fn is_long(body: &Option<String>, max_chars: usize) -> bool {
+    body.as_deref()
+        .is_some_and(|b| b.chars().count() > max_chars)
+}
+
+/// 本文を先頭から行単位で`max_chars`文字までに切り詰める
+///
+/// 1行目だけで超える場合は文字単位で切ります。
+pub fn truncate_lines(body: &str, max_chars: usize) -> String {
+    let mut kept = String::new();
+    let mut chars = 0;
+    for line in body.lines() {
+        let len = line.chars().count() + 1;
+        if chars + len > max_chars {
+            break;
+        }
+        kept.push_str(line);
+        kept.push('\n');
+        chars += len;
+    }
+    if kept.is_empty() {
+        kept = body.chars().take(max_chars).collect();
+        kept.push('\n');
+    }
+    kept.push_str("…（以下省略）");
+    kept
+}
+
+/// 本文が`max_chars`文字を超えるPR/Issueを`condense`の方法で短くし、短くした件数を返す
+///
+/// 1件ごとに`on_progress`を呼びます。
+pub async fn condense_long(
+    client: Option<&OpenAiClient>,
+    condense: Condense,
+    prs: &mut [PullRequest],
+    issues: &mut [Issue],
+    max_chars: usize,
+    mut on_progress: impl FnMut(),
+) -> Result<usize> {
+    let summarizer = match condense {
+        Condense::Llm => client.map(|c| c.clone().with_model(llm::LIGHT_MODEL)),
+        Condense::Extract => None,
+    };
+    let bodies = prs
+        .iter_mut()
+        .map(|pr| ("PR", &pr.title, &mut pr.body))
+        .chain(
+            issues
+                .iter_mut()
+                .map(|issue| ("Issue", &issue.title, &mut issue.body)),
+        )
+        .filter(|(_, _, body)| is_long(body, max_chars));
+
+    let mut condensed = 0;
+    for (kind, title, body) in bodies {
+        let Some(text) = body.as_deref() else {
+            continue;
+        };
+        let summary = match &summarizer {
+            Some(summarizer) => summarize(summarizer, kind, title, text, max_chars)
+                .await
+                .ok()
+                .filter(|s| !s.trim().is_empty())
+                .map(|s| format!("（本文の要約）\n{}", s.trim())),
+            None => None,
+        };
+        *body = Some(summary.unwrap_or_else(|| truncate_lines(text, max_chars)));
+        condensed += 1;
+        on_progress();
+    }
+    Ok(condensed)
+}
+
+async fn summarize(
+    client: &OpenAiClient,
+    kind: &str,
+    title: &str,
+    body: &str,
+    max_chars: usize,
+) -> Result<String> {
+    let prompt = format!(
+        "以下はGitHubの{kind}「{title}」の本文です。目的・変更内容・影響・検証方法など評価に役立つ情報を残し、{max_chars}文字以内の日本語の箇条書きに要約してください。テンプレートの定型文や未記入のチェックリストは省き、要約のみを出力してください。\n\n{body}"
+    );
+    client.chat(SYSTEM_PROMPT, &prompt).await
+}
@@ -0,0 +1,83 @@
+//! 設定ファイル（TOML）の読み込み
+//!
+//! `--config`で指定されたファイル、または`~/.config/easyhyoka/config.toml`を読み込みます。
+//! CLI引数で同じ項目が指定された場合はCLI引数が優先されます。
+
+use crate::areas::MonorepoConfig;
+use crate::availability::PersonConfig;
+use crate::confluence::ConfluenceConfig;
+use crate::email::EmailConfig;
+use crate::gaps::Gap;
+use crate::gdocs::GoogleDocsConfig;
+use crate::incident::IncidentRules;
+use crate::llm::{ModelSpec, ReasoningEffort};
+use crate::network::HttpConfig;
+use crate::strip::StripRules;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// サマリーを投稿するSlackのIncoming Webhook URL
+    pub slack_webhook: Option<String>,
+    /// PRの重要度スコアに使う重みファイル（TOML）のパス
+    pub weights: Option<PathBuf>,
+    /// 障害対応とみなすラベル・タイトルのルール（省略時は組み込みのルール）
+    pub incident: Option<IncidentRules>,
+    /// プロンプトのトークン数の上限（`--max-prompt-tokens`の既定値）
+    pub max_prompt_tokens: Option<usize>,
+    /// サマリーの生成の温度（`--temperature`の既定値）
+    pub temperature: Option<f32>,
+    /// サマリーの生成で出力する最大トークン数（`--max-output-tokens`の既定値）
+    pub max_output_tokens: Option<u32>,
+    /// 推論モデルの推論の深さ（`--reasoning-effort`の既定値）
+    pub reasoning_effort: Option<ReasoningEffort>,
+    /// サマリーの生成に使うモデルを試す順に並べたもの（エラー時は次のモデルで再試行、`--provider`より優先）
+    pub models: Vec<ModelSpec>,
+    /// `--email`でレポートを送る方法
+    pub email: Option<EmailConfig>,
+    /// `--google-docs`の作成先
+    pub google_docs: GoogleDocsConfig,
+    /// `--confluence-space`の公開先のサイト
+    pub confluence: ConfluenceConfig,
+    /// 本文・コメントから取り除くノイズ（指定時は`--strip`なしでも適用）
+    pub strip: Option<StripRules>,
+    /// HTTPリクエストのプロキシとTLSの設定
+    pub http: HttpConfig,
+    /// 対象者ごとの在籍期間と稼働率（キーはGitHubのユーザー名）
+    pub people: HashMap<String, PersonConfig>,
+    /// 活動のない期間（`--gaps`に追加される、例: `"2025-03-01..2025-03-21:育休"`）
+    pub gaps: Vec<Gap>,
+    /// 変更ファイルのパスで領域別に集計するモノレポ
+    pub monorepo: MonorepoConfig,
+    /// リポジトリ（`owner/name`）ごとの重要度の倍率（重みファイルの`[repositories]`に追加・上書き）
+    pub repo_weight: HashMap<String, f64>,
+    /// 会計年度の開始月（1〜12、`--quarter`・`--half`の期間の計算に使う、省略時は1月）
+    pub fiscal_year_start: Option<u32>,
+}
+
+impl Config {
+    /// 設定ファイルを読み込む（`path`未指定でデフォルトの場所にもない場合は空の設定）
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => match default_path() {
+                Some(path) if path.exists() => path,
+                _ => return Ok(Self::default()),
+            },
+        };
+
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("設定ファイルを読み込めません: {}", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("設定ファイルの形式が不正です: {}", path.display()))
+    }
+}
+
+/// デフォルトの設定ファイルの場所
+pub fn default_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("easyhyoka").join("config.toml"))
+}
@@ -0,0 +1,130 @@
+//! Confluenceへのページの公開（`--confluence-space`、設定ファイルの`[confluence]`）
+//!
+//! スペース内に同じタイトルのページがあれば版を上げて更新し、なければ親ページの下に作成します。
+//! 認証はユーザー名（メールアドレス）と`CONFLUENCE_API_TOKEN`環境変数のAPIトークンによるBasic認証です。
+
+use crate::limits;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+/// Confluenceの接続先（設定ファイルの`[confluence]`）
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ConfluenceConfig {
+    /// サイトのURL（例: `https://example.atlassian.net/wiki`）
+    pub base_url: Option<String>,
+    /// APIトークンの発行者のメールアドレス
+    pub username: Option<String>,
+    /// 親ページのID（`--confluence-parent`の既定値）
+    pub parent: Option<String>,
+}
+
+/// 公開先
+#[derive(Debug, Clone)]
+pub struct Destination<'a> {
+    pub space: &'a str,
+    pub parent: Option<&'a str>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResult {
+    results: Vec<Page>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Page {
+    id: String,
+    #[serde(default)]
+    version: Option<Version>,
+    #[serde(rename = "_links", default)]
+    links: Links,
+}
+
+#[derive(Debug, Deserialize)]
+struct Version {
+    number: u64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Links {
+    #[serde(default)]
+    webui: Option<String>,
+}
+
+/// `title`のページを`html`（ストレージ形式）で作成または更新し、ページのURLを返す
+pub async fn publish(
+    config: &ConfluenceConfig,
+    destination: &Destination<'_>,
+    title: &str,
+    html: &str,
+) -> Result<String> {
+    let base_url = config
+        .base_url
+        .as_deref()
+        .context("Confluenceへの公開には設定ファイルの[confluence]にbase_urlが必要です")?
+        .trim_end_matches('/');
+    let username = config
+        .username
+        .as_deref()
+        .context("Confluenceへの公開には設定ファイルの[confluence]にusernameが必要です")?;
+    let token = std::env::var("CONFLUENCE_API_TOKEN")
+        .context("Confluenceへの公開にはCONFLUENCE_API_TOKEN環境変数が必要です")?;
+    let client = limits::http_client();
+    let content_url = format!("{base_url}/rest/api/content");
+
+    let response = client
+        .get(&content_url)
+        .basic_auth(username, Some(&token))
+        .query(&[
+            ("spaceKey", destination.space),
+            ("title", title),
+            ("expand", "version"),
+        ])
+        .send()
+        .await?;
+    let existing = check(response)
+        .await?
+        .json::<SearchResult>()
+        .await?
+        .results
+        .into_iter()
+        .next();
+
+    let mut payload = serde_json::json!({
+        "type": "page",
+        "title": title,
+        "space": { "key": destination.space },
+        "body": { "storage": { "value": html, "representation": "storage" } },
+    });
+    let request = match &existing {
+        Some(page) => {
+            let number = page.version.as_ref().map_or(1, |v| v.number + 1);
+            payload["version"] = serde_json::json!({ "number": number });
+            client.put(format!("{content_url}/{}", page.id))
+        }
+        None => {
+            if let Some(parent) = destination.parent {
+                payload["ancestors"] = serde_json::json!([{ "id": parent }]);
+            }
+            client.post(&content_url)
+        }
+    };
+    let response = request
+        .basic_auth(username, Some(&token))
+        .json(&payload)
+        .send()
+        .await?;
+    let page: Page = check(response).await?.json().await?;
+    Ok(match page.links.webui {
+        Some(path) => format!("{base_url}{path}"),
+        None => format!("{base_url}/pages/viewpage.action?pageId={}", page.id),
+    })
+}
+
+async fn check(response: reqwest::Response) -> Result<reqwest::Response> {
+    if !response.status().is_success() {
+        let error_text = response.text().await?;
+        anyhow::bail!("Confluence API error: {}", error_text);
+    }
+    Ok(response)
+}
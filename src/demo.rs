@@ -0,0 +1,256 @@
+//! 合成データによるデモ（`--demo`）
+//!
+//! GitHubの認証やAPIキーを用意する前にツールを試せるよう、架空のOrganizationでの
+//! それらしいPR/Issueを期間内に散らして生成します。APIキーがない場合は、LLMの代わりに
+//! 用意済みのサマリーを返す[`DemoClient`]で最後まで実行できます。
+
+use crate::github::{Activity, Community, Issue, Membership, Mentions, PullRequest, SearchQuery};
+use crate::llm::{LlmClient, Message};
+use anyhow::Result;
+use chrono::NaiveDate;
+
+/// デモデータのOrganization
+pub const OWNER: &str = "example-org";
+
+/// `--author`を指定しない場合のデモの対象ユーザー
+pub const AUTHOR: &str = "demo-user";
+
+// (リポジトリ, 番号, タイトル, 本文, 状態, コメント数)
+const PRS: &[(&str, u32, &str, &str, &str, usize)] = &[
+    (
+        "api",
+        101,
+        "注文APIのN+1クエリを解消",
+        "注文一覧の取得で明細ごとにクエリが発行されていたため、JOINでまとめて取得するようにしました。p95のレスポンスタイムが820msから140msに改善しています。",
+        "merged",
+        6,
+    ),
+    (
+        "web",
+        214,
+        "決済画面のフォームバリデーションを共通化",
+        "画面ごとに書かれていたバリデーションを共通のスキーマに寄せました。エラーメッセージの文言も統一しています。",
+        "merged",
+        3,
+    ),
+    (
+        "api",
+        118,
+        "Webhookの再送処理を追加",
+        "外部決済サービスからのWebhookの処理に失敗した場合、指数バックオフで最大5回まで再送するようにしました。Closes #97",
+        "merged",
+        8,
+    ),
+    (
+        "infra",
+        42,
+        "ステージング環境のデプロイをGitHub Actionsに移行",
+        "手作業で行っていたステージングへのデプロイをワークフローにしました。mainへのマージで自動的にデプロイされます。",
+        "merged",
+        2,
+    ),
+    (
+        "web",
+        231,
+        "商品検索のサジェストを追加",
+        "入力中の文字列から商品名の候補を表示します。デバウンスでAPIの呼び出し回数を抑えています。",
+        "merged",
+        4,
+    ),
+    (
+        "api",
+        125,
+        "監査ログの保存期間を設定可能に",
+        "これまで固定の90日だった監査ログの保存期間を、テナントごとに設定できるようにしました。",
+        "merged",
+        1,
+    ),
+    (
+        "web",
+        240,
+        "ダークモード対応（WIP）",
+        "デザイントークンを見直し、配色をテーマで切り替えられるようにしています。",
+        "open",
+        0,
+    ),
+    (
+        "docs",
+        12,
+        "新メンバー向けのセットアップ手順を更新",
+        "ローカル環境の構築手順をDocker Composeに合わせて書き直しました。",
+        "merged",
+        1,
+    ),
+];
+
+// (リポジトリ, 番号, タイトル, 本文, 状態, コメント数)
+const ISSUES: &[(&str, u32, &str, &str, &str, usize)] = &[
+    (
+        "api",
+        97,
+        "決済完了のWebhookが取りこぼされることがある",
+        "決済サービス側の一時的なエラーで、注文のステータスが更新されないままになるケースがあります。",
+        "closed",
+        5,
+    ),
+    (
+        "web",
+        205,
+        "モバイルで決済ボタンが隠れる",
+        "iOSのSafariで、キーボードを開くと決済ボタンが画面外に出てしまいます。",
+        "closed",
+        2,
+    ),
+    (
+        "infra",
+        38,
+        "CIの実行時間を短縮したい",
+        "依存関係のインストールに毎回4分かかっているため、キャッシュを検討します。",
+        "open",
+        3,
+    ),
+];
+
+/// `query`の期間に散らした合成データ（オーナーは[`OWNER`]に置き換える）
+pub fn activity(mut query: SearchQuery) -> Activity {
+    query.owner = Some(OWNER.to_string());
+    let dates = spread(&query.since, &query.until, PRS.len() + ISSUES.len());
+    let author = query.author.clone();
+
+    let prs = PRS
+        .iter()
+        .zip(&dates)
+        .map(|(item, date)| pull_request(&author, item, date))
+        .collect();
+    let issues = ISSUES
+        .iter()
+        .zip(dates.iter().skip(PRS.len()))
+        .map(|(item, date)| issue(&author, item, date))
+        .collect();
+
+    Activity {
+        query,
+        prs,
+        issues,
+        discussions: Vec::new(),
+        releases: Vec::new(),
+        community: Community::default(),
+        membership: Membership::default(),
+        triage: Vec::new(),
+        mentions: Mentions::default(),
+    }
+}
+
+fn pull_request(
+    author: &str,
+    &(repo, number, title, body, state, comments): &(&str, u32, &str, &str, &str, usize),
+    date: &NaiveDate,
+) -> PullRequest {
+    let closed_at = (state != "open").then(|| timestamp(date, 3));
+    let mut pr = serde_json::json!({
+        "number": number,
+        "title": title,
+        "body": body,
+        "createdAt": timestamp(date, 0),
+        "closedAt": closed_at,
+        "state": state,
+        "url": format!("https://github.com/{OWNER}/{repo}/pull/{number}"),
+        "repository": { "nameWithOwner": format!("{OWNER}/{repo}") },
+        "author": { "login": author },
+        "commentsCount": comments,
+    });
+    // 議論の多いPRにはレビューとコメントも付ける
+    if comments >= 6 {
+        pr["comments"] = serde_json::json!([
+            {
+                "author": { "login": "reviewer-a" },
+                "body": "既存の注文データでの動作も確認できました。ありがとうございます！",
+                "createdAt": timestamp(date, 1),
+            },
+        ]);
+        pr["reviews"] = serde_json::json!([
+            {
+                "author": { "login": "reviewer-a" },
+                "state": "APPROVED",
+                "body": "LGTM",
+                "submittedAt": timestamp(date, 2),
+            },
+        ]);
+    }
+    serde_json::from_value(pr).expect("demo pull request should match the schema")
+}
+
+fn issue(
+    author: &str,
+    &(repo, number, title, body, state, comments): &(&str, u32, &str, &str, &str, usize),
+    date: &NaiveDate,
+) -> Issue {
+    let closed_at = (state != "open").then(|| timestamp(date, 5));
+    serde_json::from_value(serde_json::json!({
+        "number": number,
+        "title": title,
+        "body": body,
+        "createdAt": timestamp(date, 0),
+        "closedAt": closed_at,
+        "state": state,
+        "url": format!("https://github.com/{OWNER}/{repo}/issues/{number}"),
+        "repository": { "nameWithOwner": format!("{OWNER}/{repo}") },
+        "author": { "login": author },
+        "commentsCount": comments,
+    }))
+    .expect("demo issue should match the schema")
+}
+
+// 期間内に等間隔に並べた`count`個の日付（期間を解釈できない場合は今日から遡る）
+fn spread(since: &str, until: &str, count: usize) -> Vec<NaiveDate> {
+    let until = NaiveDate::parse_from_str(until, "%Y-%m-%d")
+        .unwrap_or_else(|_| chrono::Local::now().date_naive());
+    let since = NaiveDate::parse_from_str(since, "%Y-%m-%d")
+        .unwrap_or(until - chrono::Duration::days(180))
+        .min(until);
+    let days = (until - since).num_days();
+    (0..count)
+        .map(|i| since + chrono::Duration::days(days * i as i64 / count.max(1) as i64))
+        .collect()
+}
+
+fn timestamp(date: &NaiveDate, days_later: i64) -> String {
+    format!("{}T10:00:00Z", *date + chrono::Duration::days(days_later))
+}
+
+/// LLMの代わりに用意済みのサマリーを返すクライアント
+#[derive(Debug, Clone, Default)]
+pub struct DemoClient;
+
+impl LlmClient for DemoClient {
+    fn model(&self) -> &str {
+        "demo"
+    }
+
+    async fn complete(&self, _messages: &[Message]) -> Result<String> {
+        Ok(SUMMARY.replace("{owner}", OWNER))
+    }
+}
+
+// 合成データに対応する用意済みのサマリー
+const SUMMARY: &str = "\
+# 実績サマリー（デモ）
+
+このサマリーは`--demo`用に用意したもので、LLMは呼び出していません。\
+APIキーを設定して`--demo`を実行すると、同じ合成データから実際に生成できます。
+
+## 主な成果
+
+- **注文APIの性能改善**: 注文一覧のN+1クエリを解消し、p95のレスポンスタイムを820msから140msに短縮しました（https://github.com/{owner}/api/pull/101）
+- **決済の信頼性向上**: Webhookの取りこぼし（https://github.com/{owner}/api/issues/97）を報告し、指数バックオフによる再送処理で解決しました（https://github.com/{owner}/api/pull/118）
+- **デプロイの自動化**: ステージング環境へのデプロイをGitHub Actionsに移行し、手作業をなくしました（https://github.com/{owner}/infra/pull/42）
+
+## 開発体験・品質への貢献
+
+- 決済画面のバリデーションを共通化し、エラーメッセージを統一しました（https://github.com/{owner}/web/pull/214）
+- 新メンバー向けのセットアップ手順をDocker Composeに合わせて更新しました（https://github.com/{owner}/docs/pull/12）
+
+## 今後の課題
+
+- ダークモード対応（https://github.com/{owner}/web/pull/240）とCIの実行時間の短縮（https://github.com/{owner}/infra/issues/38）が進行中です
+";
@@ -0,0 +1,122 @@
+//! レポートのメール送信（`--email`、設定ファイルの`[email]`）
+//!
+//! SMTP（STARTTLS）またはSendGridのWeb APIで、Markdownのサマリーと統計付きのHTMLを
+//! multipart/alternativeの1通として送ります。パスワードやAPIキーは環境変数から読み込みます。
+
+use crate::limits;
+use anyhow::{Context, Result};
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Tokio1Executor};
+use serde::Deserialize;
+
+const SENDGRID_URL: &str = "https://api.sendgrid.com/v3/mail/send";
+
+/// メールの送信方法
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmailProvider {
+    /// SMTP（パスワードは`SMTP_PASSWORD`環境変数）
+    #[default]
+    Smtp,
+    /// SendGridのWeb API（APIキーは`SENDGRID_API_KEY`環境変数）
+    Sendgrid,
+}
+
+/// メール送信の設定（設定ファイルの`[email]`）
+#[derive(Debug, Clone, Deserialize)]
+pub struct EmailConfig {
+    /// 送信元（例: `easyhyoka <bot@example.com>`）
+    pub from: String,
+    #[serde(default)]
+    pub provider: EmailProvider,
+    /// SMTPサーバーのホスト名（`provider = "smtp"`の場合は必須）
+    pub smtp_host: Option<String>,
+    /// SMTPサーバーのポート（省略時は587）
+    pub smtp_port: Option<u16>,
+    /// SMTPのユーザー名（省略時は認証しない）
+    pub smtp_username: Option<String>,
+}
+
+/// 送信するレポート
+#[derive(Debug, Clone, Copy)]
+pub struct Report<'a> {
+    pub subject: &'a str,
+    /// テキスト版（Markdownのサマリー）
+    pub text: &'a str,
+    /// HTML版（統計グラフ付きのレポート）
+    pub html: &'a str,
+}
+
+/// `report`を`to`の宛先に送る
+pub async fn send_report(config: &EmailConfig, to: &[String], report: Report<'_>) -> Result<()> {
+    match config.provider {
+        EmailProvider::Smtp => send_smtp(config, to, report).await,
+        EmailProvider::Sendgrid => send_sendgrid(config, to, report).await,
+    }
+}
+
+async fn send_smtp(config: &EmailConfig, to: &[String], report: Report<'_>) -> Result<()> {
+    let host = config
+        .smtp_host
+        .as_deref()
+        .context("SMTPで送信するには[email]のsmtp_hostが必要です")?;
+    let mut builder = lettre::Message::builder()
+        .from(parse_mailbox(&config.from)?)
+        .subject(report.subject);
+    for address in to {
+        builder = builder.to(parse_mailbox(address)?);
+    }
+    let message = builder.multipart(MultiPart::alternative_plain_html(
+        report.text.to_string(),
+        report.html.to_string(),
+    ))?;
+
+    let mut transport = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)?
+        .port(config.smtp_port.unwrap_or(587))
+        .timeout(limits::timeout());
+    if let Some(username) = &config.smtp_username {
+        let password = std::env::var("SMTP_PASSWORD")
+            .context("SMTPの認証にはSMTP_PASSWORD環境変数が必要です")?;
+        transport = transport.credentials(Credentials::new(username.clone(), password));
+    }
+    transport.build().send(message).await?;
+    Ok(())
+}
+
+async fn send_sendgrid(config: &EmailConfig, to: &[String], report: Report<'_>) -> Result<()> {
+    let api_key = std::env::var("SENDGRID_API_KEY")
+        .context("SendGridで送信するにはSENDGRID_API_KEY環境変数が必要です")?;
+    let address = |mailbox: Mailbox| serde_json::json!({ "email": mailbox.email.to_string(), "name": mailbox.name });
+    let recipients = to
+        .iter()
+        .map(|a| parse_mailbox(a).map(address))
+        .collect::<Result<Vec<_>>>()?;
+    let payload = serde_json::json!({
+        "personalizations": [{ "to": recipients }],
+        "from": address(parse_mailbox(&config.from)?),
+        "subject": report.subject,
+        "content": [
+            { "type": "text/plain", "value": report.text },
+            { "type": "text/html", "value": report.html },
+        ],
+    });
+
+    let response = limits::http_client()
+        .post(SENDGRID_URL)
+        .bearer_auth(api_key)
+        .json(&payload)
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        let error_text = response.text().await?;
+        anyhow::bail!("SendGrid API error: {}", error_text);
+    }
+    Ok(())
+}
+
+fn parse_mailbox(address: &str) -> Result<Mailbox> {
+    address
+        .parse()
+        .with_context(|| format!("メールアドレスの形式が不正です: {address}"))
+}
@@ -0,0 +1,45 @@
+//! 失敗の種類とプロセスの終了コード
+//!
+//! エラーに`anyhow::Context::context`で[`Failure`]を付けておくと、
+//! ラッパースクリプトが終了コードで失敗の原因を判別できます。
+
+use std::fmt;
+
+/// 失敗の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Failure {
+    /// 引数・設定ファイル・環境変数・入力ファイルの誤り
+    Config,
+    /// `gh`やGitHub APIからの取得の失敗
+    GitHub,
+    /// OpenAI APIの呼び出しの失敗
+    Llm,
+}
+
+impl Failure {
+    /// プロセスの終了コード（1は分類されていないエラー、2はclapの引数エラー）
+    pub fn exit_code(self) -> u8 {
+        match self {
+            Failure::Config => 3,
+            Failure::GitHub => 4,
+            Failure::Llm => 5,
+        }
+    }
+
+    /// エラーに付けられた失敗の種類（外側のものを優先）
+    pub fn of(error: &anyhow::Error) -> Option<Self> {
+        error.downcast_ref::<Self>().copied()
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Failure::Config => "設定エラー",
+            Failure::GitHub => "GitHubからの取得に失敗しました",
+            Failure::Llm => "OpenAI APIの呼び出しに失敗しました",
+        })
+    }
+}
+
+impl std::error::Error for Failure {}
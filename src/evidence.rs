@@ -0,0 +1,127 @@
+//! サマリーの見出しごとの根拠となるPR/Issueの付録（`--evidence`）
+//!
+//! 生成済みのサマリーから見出しを抜き出し、見出しごとに根拠となるPR/IssueのURLを
+//! JSONで答えさせて、評価者が記述をすぐに確かめられる一覧として末尾に付けます。
+//! 活動データに含まれないURLは付録に載せません。
+
+use crate::github::{Issue, PullRequest};
+use crate::llm;
+use crate::report::ResumeLanguage;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// 見出しと、その記述の根拠となるPR/IssueのURL
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Evidence {
+    pub section: String,
+    #[serde(default)]
+    pub urls: Vec<String>,
+}
+
+/// サマリーのMarkdownの見出し（`#`〜`###`、重複なし、出現順）
+pub fn sections(summary: &str) -> Vec<String> {
+    let mut sections: Vec<String> = Vec::new();
+    let mut in_code = false;
+    for line in summary.lines().map(str::trim) {
+        if line.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        let level = line.chars().take_while(|&c| c == '#').count();
+        if in_code || !(1..=3).contains(&level) {
+            continue;
+        }
+        let Some(title) = line[level..].strip_prefix(' ').map(str::trim) else {
+            continue;
+        };
+        if !title.is_empty() && !sections.iter().any(|s| s == title) {
+            sections.push(title.to_string());
+        }
+    }
+    sections
+}
+
+/// LLMの応答（`[{"section": "...", "urls": ["..."]}]`）を解釈する
+///
+/// `sections`にない見出しと活動データにないURLは除き、除いたURLを2つ目の値として返します。
+pub fn parse(
+    response: &str,
+    sections: &[String],
+    prs: &[PullRequest],
+    issues: &[Issue],
+) -> Result<(Vec<Evidence>, Vec<String>)> {
+    let answered: Vec<Evidence> = serde_json::from_str(llm::strip_code_fence(response))
+        .context("根拠の一覧の応答をJSONとして解釈できません")?;
+    let titles = titles(prs, issues);
+
+    let mut unknown: Vec<String> = Vec::new();
+    let mut evidence: Vec<Evidence> = Vec::new();
+    for section in sections {
+        let mut urls: Vec<String> = Vec::new();
+        for url in answered
+            .iter()
+            .filter(|e| e.section.trim() == section)
+            .flat_map(|e| &e.urls)
+        {
+            if !titles.contains_key(url.as_str()) {
+                if !unknown.contains(url) {
+                    unknown.push(url.clone());
+                }
+            } else if !urls.contains(url) {
+                urls.push(url.clone());
+            }
+        }
+        if !urls.is_empty() {
+            evidence.push(Evidence {
+                section: section.clone(),
+                urls,
+            });
+        }
+    }
+    Ok((evidence, unknown))
+}
+
+/// 付録のMarkdown（根拠のある見出しがない場合は空文字列）
+pub fn appendix(
+    evidence: &[Evidence],
+    prs: &[PullRequest],
+    issues: &[Issue],
+    language: ResumeLanguage,
+) -> String {
+    if evidence.is_empty() {
+        return String::new();
+    }
+    let titles = titles(prs, issues);
+    let mut appendix = match language {
+        ResumeLanguage::Japanese => "## 付録: 根拠となるPR/Issue\n".to_string(),
+        ResumeLanguage::English => "## Appendix: Supporting PRs/Issues\n".to_string(),
+    };
+    for e in evidence {
+        appendix.push_str(&format!("\n### {}\n\n", e.section));
+        for url in &e.urls {
+            match titles.get(url.as_str()).copied().flatten() {
+                Some(title) => appendix.push_str(&format!("- [{title}]({url})\n")),
+                None => appendix.push_str(&format!("- {url}\n")),
+            }
+        }
+    }
+    appendix
+}
+
+// 活動データに含まれるPR/IssueのURLとタイトル（解決したIssueを含む、後のものが優先）
+fn titles<'a>(prs: &'a [PullRequest], issues: &'a [Issue]) -> HashMap<&'a str, Option<&'a str>> {
+    prs.iter()
+        .flat_map(|pr| &pr.closing_issues)
+        .map(|issue| (issue.url.as_str(), issue.title.as_deref()))
+        .chain(
+            prs.iter()
+                .map(|pr| (pr.url.as_str(), Some(pr.title.as_str()))),
+        )
+        .chain(
+            issues
+                .iter()
+                .map(|issue| (issue.url.as_str(), Some(issue.title.as_str()))),
+        )
+        .collect()
+}
@@ -0,0 +1,124 @@
+//! 取得した活動データのファイル出力
+
+use crate::github::{Comment, Issue, PullRequest, SearchQuery};
+use crate::report::{self, PromptOptions};
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::path::Path;
+
+const ITEM_COLUMNS: [&str; 7] = [
+    "repository",
+    "number",
+    "title",
+    "state",
+    "created_at",
+    "url",
+    "body",
+];
+
+/// `dir`に prs.csv / issues.csv / comments.csv を書き出す
+pub fn write_csv(dir: &Path, prs: &[PullRequest], issues: &[Issue]) -> Result<()> {
+    std::fs::create_dir_all(dir)?;
+
+    let mut writer = csv::Writer::from_path(dir.join("prs.csv"))?;
+    writer.write_record(ITEM_COLUMNS)?;
+    for pr in prs {
+        writer.write_record([
+            pr.repository.name_with_owner.as_str(),
+            &pr.number.to_string(),
+            &pr.title,
+            &pr.state,
+            &pr.created_at,
+            &pr.url,
+            pr.body.as_deref().unwrap_or(""),
+        ])?;
+    }
+    writer.flush()?;
+
+    let mut writer = csv::Writer::from_path(dir.join("issues.csv"))?;
+    writer.write_record(ITEM_COLUMNS)?;
+    for issue in issues {
+        writer.write_record([
+            issue.repository.name_with_owner.as_str(),
+            &issue.number.to_string(),
+            &issue.title,
+            &issue.state,
+            &issue.created_at,
+            &issue.url,
+            issue.body.as_deref().unwrap_or(""),
+        ])?;
+    }
+    writer.flush()?;
+
+    let mut writer = csv::Writer::from_path(dir.join("comments.csv"))?;
+    writer.write_record([
+        "kind",
+        "repository",
+        "number",
+        "parent_url",
+        "user",
+        "created_at",
+        "body",
+    ])?;
+    let pr_comments = prs.iter().flat_map(|pr| {
+        pr.comments
+            .iter()
+            .map(move |c| ("pr", &pr.repository.name_with_owner, pr.number, &pr.url, c))
+    });
+    let issue_comments = issues.iter().flat_map(|issue| {
+        issue.comments.iter().map(move |c| {
+            (
+                "issue",
+                &issue.repository.name_with_owner,
+                issue.number,
+                &issue.url,
+                c,
+            )
+        })
+    });
+    for (kind, repo, number, url, comment) in pr_comments.chain(issue_comments) {
+        writer.write_record([
+            kind,
+            repo.as_str(),
+            &number.to_string(),
+            url.as_str(),
+            comment_user(comment),
+            &comment.created_at,
+            &comment.body,
+        ])?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// プロンプトに含めるものと同じ1件ずつのJSONを、JSON Lines形式で`path`に書き出す
+///
+/// 各行には種類（`type`: pr/issue/release/discussion/oss_pr/gist）が付きます。
+/// 書き出した件数を返します。
+pub fn write_jsonl(
+    path: &Path,
+    query: &SearchQuery,
+    prs: &[PullRequest],
+    issues: &[Issue],
+    options: &PromptOptions,
+) -> Result<usize> {
+    let items = report::activity_items(query, prs, issues, options);
+    let file = std::fs::File::create(path)
+        .with_context(|| format!("JSONLを書き出せません: {}", path.display()))?;
+    let mut writer = std::io::BufWriter::new(file);
+    for item in &items {
+        serde_json::to_writer(&mut writer, item)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()?;
+    Ok(items.len())
+}
+
+fn comment_user(comment: &Comment) -> &str {
+    comment
+        .author
+        .as_ref()
+        .map(|a| a.login.as_str())
+        .unwrap_or("Unknown")
+}
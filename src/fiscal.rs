@@ -0,0 +1,148 @@
+//! 会計年度の四半期・半期による期間の指定（`--quarter`・`--half`、設定ファイルの`fiscal_year_start`）
+//!
+//! 年度の開始月を設定しておくと、`2025Q1`や`2025H2`から開始日・終了日を求めます。
+//! 年度は開始月の年で数えます（4月始まりの場合、`2025Q1`は2025-04-01〜2025-06-30、
+//! `2025Q4`は2026-01-01〜2026-03-31）。
+
+use anyhow::Context;
+use chrono::{Months, NaiveDate};
+use std::fmt;
+use std::str::FromStr;
+
+/// 会計年度の四半期または半期
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FiscalPeriod {
+    /// `year`年度の第`n`四半期（1〜4）
+    Quarter { year: i32, n: u32 },
+    /// `year`年度の上期（1）・下期（2）
+    Half { year: i32, n: u32 },
+}
+
+impl FiscalPeriod {
+    /// 年度が`start_month`月（1〜12）に始まる場合の開始日と終了日
+    pub fn range(&self, start_month: u32) -> anyhow::Result<(NaiveDate, NaiveDate)> {
+        anyhow::ensure!(
+            (1..=12).contains(&start_month),
+            "年度の開始月は1〜12で指定してください: {start_month}"
+        );
+        let (year, months, index) = match *self {
+            Self::Quarter { year, n } => (year, 3, n - 1),
+            Self::Half { year, n } => (year, 6, n - 1),
+        };
+        let fiscal_start = NaiveDate::from_ymd_opt(year, start_month, 1)
+            .with_context(|| format!("年度を解釈できません: {self}"))?;
+        let since = fiscal_start + Months::new(months * index);
+        let until = (since + Months::new(months))
+            .pred_opt()
+            .with_context(|| format!("期間を解釈できません: {self}"))?;
+        Ok((since, until))
+    }
+
+    /// `--quarter`の値（`2025Q1`の形式）
+    pub fn parse_quarter(value: &str) -> anyhow::Result<Self> {
+        match value.parse()? {
+            period @ Self::Quarter { .. } => Ok(period),
+            Self::Half { .. } => anyhow::bail!("四半期は 2025Q1 の形式で指定してください: {value}"),
+        }
+    }
+
+    /// `--half`の値（`2025H1`の形式）
+    pub fn parse_half(value: &str) -> anyhow::Result<Self> {
+        match value.parse()? {
+            period @ Self::Half { .. } => Ok(period),
+            Self::Quarter { .. } => {
+                anyhow::bail!("半期は 2025H1 の形式で指定してください: {value}")
+            }
+        }
+    }
+}
+
+/// `2025Q1`・`2025H2`（大文字小文字と`2025-Q1`のような区切りを許容）
+impl FromStr for FiscalPeriod {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> anyhow::Result<Self> {
+        let normalized = value.trim().to_uppercase().replace(['-', '_', ' '], "");
+        let invalid = || format!("期間は 2025Q1 または 2025H1 の形式で指定してください: {value}");
+        let split = normalized.find(['Q', 'H']).with_context(invalid)?;
+        let (year, rest) = normalized.split_at(split);
+        let year: i32 = year.parse().ok().with_context(invalid)?;
+        let n: u32 = rest[1..].parse().ok().with_context(invalid)?;
+        match &rest[..1] {
+            "Q" if (1..=4).contains(&n) => Ok(Self::Quarter { year, n }),
+            "H" if (1..=2).contains(&n) => Ok(Self::Half { year, n }),
+            _ => anyhow::bail!(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for FiscalPeriod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Quarter { year, n } => write!(f, "{year}Q{n}"),
+            Self::Half { year, n } => write!(f, "{year}H{n}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn from_str_accepts_case_and_separators() {
+        for value in ["2025Q1", "2025q1", "2025-Q1", "2025_q1", " 2025 Q1 "] {
+            assert_eq!(
+                value.parse::<FiscalPeriod>().unwrap(),
+                FiscalPeriod::Quarter { year: 2025, n: 1 }
+            );
+        }
+        assert_eq!(
+            "2025H2".parse::<FiscalPeriod>().unwrap(),
+            FiscalPeriod::Half { year: 2025, n: 2 }
+        );
+    }
+
+    #[test]
+    fn from_str_rejects_out_of_range_periods() {
+        for value in ["2025Q0", "2025Q5", "2025H3", "Q1", "2025", "2025X1"] {
+            assert!(value.parse::<FiscalPeriod>().is_err(), "{value}");
+        }
+    }
+
+    #[test]
+    fn parse_quarter_and_half_reject_the_other_kind() {
+        assert!(FiscalPeriod::parse_quarter("2025H1").is_err());
+        assert!(FiscalPeriod::parse_half("2025Q1").is_err());
+    }
+
+    #[test]
+    fn range_counts_the_year_from_the_start_month() {
+        let q1 = FiscalPeriod::Quarter { year: 2025, n: 1 };
+        assert_eq!(q1.range(4).unwrap(), (date(2025, 4, 1), date(2025, 6, 30)));
+        let q4 = FiscalPeriod::Quarter { year: 2025, n: 4 };
+        assert_eq!(q4.range(4).unwrap(), (date(2026, 1, 1), date(2026, 3, 31)));
+        let h2 = FiscalPeriod::Half { year: 2025, n: 2 };
+        assert_eq!(h2.range(1).unwrap(), (date(2025, 7, 1), date(2025, 12, 31)));
+    }
+
+    #[test]
+    fn range_rejects_invalid_start_month() {
+        assert!(
+            FiscalPeriod::Quarter { year: 2025, n: 1 }
+                .range(13)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for value in ["2025Q3", "2026H1"] {
+            assert_eq!(value.parse::<FiscalPeriod>().unwrap().to_string(), value);
+        }
+    }
+}
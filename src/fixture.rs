@@ -0,0 +1,127 @@
+//! 外部呼び出しの記録と再生（`--record`・`--replay`）
+//!
+//! `--record`では`gh`の呼び出しごとの出力をディレクトリに保存し、`--replay`では`gh`を実行せずに
+//! 保存した出力を返します。LLMの応答は同じディレクトリの`llm/`を応答のキャッシュとして使います。
+//! ネットワークや認証情報のない環境での再現可能な結合テストやデモに使います。
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Output};
+use std::sync::OnceLock;
+
+static MODE: OnceLock<Mode> = OnceLock::new();
+
+/// 記録・再生のモード
+#[derive(Debug, Clone)]
+pub enum Mode {
+    /// 実際に呼び出し、出力をディレクトリに保存する
+    Record(PathBuf),
+    /// 呼び出さずに、保存した出力を返す
+    Replay(PathBuf),
+}
+
+impl Mode {
+    pub fn dir(&self) -> &Path {
+        match self {
+            Mode::Record(dir) | Mode::Replay(dir) => dir,
+        }
+    }
+
+    /// LLMの応答を保存するディレクトリ
+    pub fn llm_dir(&self) -> PathBuf {
+        self.dir().join("llm")
+    }
+}
+
+// 保存する`gh`の1回分の出力
+#[derive(Debug, Serialize, Deserialize)]
+struct Recorded {
+    args: Vec<String>,
+    status: i32,
+    stdout: String,
+    stderr: String,
+}
+
+/// プロセス全体のモードを設定（起動時に一度だけ）
+pub fn set_mode(mode: Mode) {
+    let _ = MODE.set(mode);
+}
+
+pub fn mode() -> Option<&'static Mode> {
+    MODE.get()
+}
+
+pub fn is_replay() -> bool {
+    matches!(mode(), Some(Mode::Replay(_)))
+}
+
+/// `gh`を`args`で呼び出す（`run`が実際の呼び出し、モードに応じて記録・再生する）
+pub fn gh(args: &[&str], run: impl FnOnce() -> io::Result<Output>) -> io::Result<Output> {
+    let Some(mode) = mode() else {
+        return run();
+    };
+    let path = mode.dir().join("gh").join(format!("{}.json", key(args)));
+    match mode {
+        Mode::Replay(_) => {
+            let json = std::fs::read_to_string(&path).map_err(|e| {
+                io::Error::new(
+                    e.kind(),
+                    format!(
+                        "gh {}の記録がありません: {}",
+                        args.join(" "),
+                        path.display()
+                    ),
+                )
+            })?;
+            let recorded: Recorded = serde_json::from_str(&json)?;
+            Ok(Output {
+                status: exit_status(recorded.status),
+                stdout: recorded.stdout.into_bytes(),
+                stderr: recorded.stderr.into_bytes(),
+            })
+        }
+        Mode::Record(_) => {
+            let output = run()?;
+            let recorded = Recorded {
+                args: args.iter().map(|a| a.to_string()).collect(),
+                status: output.status.code().unwrap_or(-1),
+                stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+                stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            };
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::write(&path, serde_json::to_string_pretty(&recorded)?)?;
+            Ok(output)
+        }
+    }
+}
+
+// 引数のハッシュ（SHA-256の先頭16文字）
+fn key(args: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    for arg in args {
+        hasher.update(arg.as_bytes());
+        hasher.update([0]);
+    }
+    hasher
+        .finalize()
+        .iter()
+        .take(8)
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+#[cfg(unix)]
+fn exit_status(code: i32) -> ExitStatus {
+    use std::os::unix::process::ExitStatusExt;
+    ExitStatus::from_raw((code & 0xff) << 8)
+}
+
+#[cfg(windows)]
+fn exit_status(code: i32) -> ExitStatus {
+    use std::os::windows::process::ExitStatusExt;
+    ExitStatus::from_raw(code as u32)
+}
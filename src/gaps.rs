@@ -0,0 +1,148 @@
+//! 活動のない期間（休暇・育休など）の検出と指定（`--gaps`・設定ファイルの`gaps`）
+//!
+//! 指定した期間は推移の集計で「評価のペースから除外」と明示し、指定のない長い空白は
+//! 活動データから検出してプロンプトに含めます。どちらもペースの落ち込みとして
+//! 書かれないよう、LLMへの指示と合わせて使います。
+
+use crate::github::{Issue, PullRequest};
+use anyhow::Context;
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::str::FromStr;
+
+/// 空白として検出する最短の日数
+pub const MIN_GAP_DAYS: i64 = 14;
+
+/// 活動のない期間
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Gap {
+    pub since: NaiveDate,
+    pub until: NaiveDate,
+    /// 理由（例: 育休、指定時のみ）
+    pub label: Option<String>,
+    /// 利用者が指定した期間か（`false`は活動データから検出したもの）
+    pub declared: bool,
+}
+
+impl Gap {
+    /// `since`〜`until`（両端を含む）と重なる日数
+    pub fn overlap_days(&self, since: NaiveDate, until: NaiveDate) -> i64 {
+        let start = self.since.max(since);
+        let end = self.until.min(until);
+        ((end - start).num_days() + 1).max(0)
+    }
+
+    pub fn days(&self) -> i64 {
+        (self.until - self.since).num_days() + 1
+    }
+
+    /// プロンプト・表示用の説明（例: `2025-03-01〜2025-03-21（21日、育休）`）
+    pub fn describe(&self) -> String {
+        let reason = match (&self.label, self.declared) {
+            (Some(label), _) => format!("、{label}"),
+            (None, true) => String::new(),
+            (None, false) => "、活動データから検出".to_string(),
+        };
+        format!(
+            "{}〜{}（{}日{reason}）",
+            self.since,
+            self.until,
+            self.days()
+        )
+    }
+}
+
+/// `YYYY-MM-DD..YYYY-MM-DD`、理由を付ける場合は`YYYY-MM-DD..YYYY-MM-DD:育休`
+impl FromStr for Gap {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> anyhow::Result<Self> {
+        let (range, label) = match value.split_once(':') {
+            Some((range, label)) => (range, Some(label.trim().to_string())),
+            None => (value, None),
+        };
+        let (since, until) = range.split_once("..").with_context(|| {
+            format!("期間は YYYY-MM-DD..YYYY-MM-DD の形式で指定してください: {value}")
+        })?;
+        let parse = |date: &str| {
+            NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+                .with_context(|| format!("日付の形式が不正です: {date}"))
+        };
+        let (since, until) = (parse(since)?, parse(until)?);
+        anyhow::ensure!(since <= until, "期間の開始日が終了日より後です: {value}");
+        Ok(Self {
+            since,
+            until,
+            label: label.filter(|l| !l.is_empty()),
+            declared: true,
+        })
+    }
+}
+
+impl TryFrom<String> for Gap {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> anyhow::Result<Self> {
+        value.parse()
+    }
+}
+
+/// `since`〜`until`で、PR/Issueの作成・クローズのない`MIN_GAP_DAYS`日以上の期間を検出
+///
+/// `declared`の期間は指定済みとして除き、その前後の空白だけを返します。
+pub fn detect(
+    prs: &[PullRequest],
+    issues: &[Issue],
+    since: &str,
+    until: &str,
+    declared: &[Gap],
+) -> Vec<Gap> {
+    let parse = |value: &str| {
+        value
+            .get(..10)
+            .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+    };
+    let (Some(since), Some(until)) = (parse(since), parse(until)) else {
+        return Vec::new();
+    };
+    let mut dates: Vec<NaiveDate> = prs
+        .iter()
+        .flat_map(|pr| [Some(pr.created_at.as_str()), pr.closed_at.as_deref()])
+        .chain(
+            issues
+                .iter()
+                .flat_map(|issue| [Some(issue.created_at.as_str()), issue.closed_at.as_deref()]),
+        )
+        .flatten()
+        .filter_map(parse)
+        // 指定済みの期間の両端も境界にして、前後の空白と分ける
+        .chain(declared.iter().flat_map(|gap| [gap.since, gap.until]))
+        .filter(|date| since <= *date && *date <= until)
+        .collect();
+    dates.sort();
+    dates.dedup();
+
+    // 期間の開始・終了も境界として、活動のあった日の間の空白を調べる
+    let mut boundaries = Vec::with_capacity(dates.len() + 2);
+    boundaries.push(since - chrono::Duration::days(1));
+    boundaries.extend(dates);
+    boundaries.push(until + chrono::Duration::days(1));
+    boundaries
+        .windows(2)
+        .filter_map(|pair| {
+            let gap = Gap {
+                since: pair[0] + chrono::Duration::days(1),
+                until: pair[1] - chrono::Duration::days(1),
+                label: None,
+                declared: false,
+            };
+            (gap.days() >= MIN_GAP_DAYS).then_some(gap)
+        })
+        .filter(|gap| {
+            declared
+                .iter()
+                .all(|d| d.overlap_days(gap.since, gap.until) == 0)
+        })
+        .collect()
+}
@@ -0,0 +1,95 @@
+//! Googleドキュメントへの書き出し（`--google-docs`、設定ファイルの`[google_docs]`）
+//!
+//! サマリーをHTMLにしてGoogle Drive APIでアップロードし、Googleドキュメントに変換します。
+//! 変換でMarkdownの見出し・箇条書き・表がドキュメントの書式として残ります。
+//!
+//! アクセストークンは`GOOGLE_ACCESS_TOKEN`環境変数、なければ`gcloud auth print-access-token`
+//! （OAuthのユーザー認証、または`service_account`を設定した場合はサービスアカウントの借用）から取得します。
+
+use crate::limits;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::process::Command;
+
+const UPLOAD_URL: &str =
+    "https://www.googleapis.com/upload/drive/v3/files?uploadType=multipart&supportsAllDrives=true";
+
+const DOCUMENT_MIME_TYPE: &str = "application/vnd.google-apps.document";
+
+// multipart/relatedの区切り
+const BOUNDARY: &str = "easyhyoka-google-docs-boundary";
+
+/// Googleドキュメントの書き出し先（設定ファイルの`[google_docs]`）
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct GoogleDocsConfig {
+    /// 作成先のフォルダID（省略時はマイドライブ直下）
+    pub folder_id: Option<String>,
+    /// 借用するサービスアカウントのメールアドレス（`gcloud`でトークンを取得する場合のみ）
+    pub service_account: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CreatedFile {
+    id: String,
+}
+
+/// `title`のGoogleドキュメントを`html`から作成してURLを返す
+pub async fn create_document(config: &GoogleDocsConfig, title: &str, html: &str) -> Result<String> {
+    let token = access_token(config)?;
+    let mut metadata = serde_json::json!({
+        "name": title,
+        "mimeType": DOCUMENT_MIME_TYPE,
+    });
+    if let Some(folder_id) = &config.folder_id {
+        metadata["parents"] = serde_json::json!([folder_id]);
+    }
+    let body = format!(
+        "--{BOUNDARY}\r\nContent-Type: application/json; charset=UTF-8\r\n\r\n{metadata}\r\n--{BOUNDARY}\r\nContent-Type: text/html; charset=UTF-8\r\n\r\n{html}\r\n--{BOUNDARY}--\r\n"
+    );
+
+    let response = limits::http_client()
+        .post(UPLOAD_URL)
+        .bearer_auth(token)
+        .header(
+            "Content-Type",
+            format!("multipart/related; boundary={BOUNDARY}"),
+        )
+        .body(body)
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        let error_text = response.text().await?;
+        anyhow::bail!("Google Drive API error: {}", error_text);
+    }
+    let file: CreatedFile = response.json().await?;
+    Ok(format!(
+        "https://docs.google.com/document/d/{}/edit",
+        file.id
+    ))
+}
+
+// 環境変数、なければgcloudからアクセストークンを取得
+fn access_token(config: &GoogleDocsConfig) -> Result<String> {
+    if let Ok(token) = std::env::var("GOOGLE_ACCESS_TOKEN")
+        && !token.trim().is_empty()
+    {
+        return Ok(token.trim().to_string());
+    }
+
+    let mut command = Command::new("gcloud");
+    command.args(["auth", "print-access-token"]);
+    if let Some(account) = &config.service_account {
+        command.arg(format!("--impersonate-service-account={account}"));
+    }
+    let output = limits::output(&mut command).context(
+        "Googleドキュメントへの書き出しには、GOOGLE_ACCESS_TOKEN環境変数かgcloud CLIが必要です",
+    )?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "gcloudでアクセストークンを取得できません: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
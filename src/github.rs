@@ -0,0 +1,1931 @@
+//! `gh` CLIを使ったGitHubデータの取得
+
+use crate::source::{DataSource, ItemKind};
+use crate::{fixture, limits};
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, NaiveTime, SecondsFormat, TimeZone};
+use chrono_tz::Tz;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::process::{Command, Output};
+use std::time::Instant;
+
+/// `gh search` で一度に取得できる件数の上限
+pub const SEARCH_LIMIT: usize = 1000;
+
+/// `gh` CLIを使う取得元
+///
+/// [`DataSource`]の範囲外のDiscussions・リリース・変更ファイルなどは、
+/// このモジュールの関数で取得します。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GitHub;
+
+impl DataSource for GitHub {
+    fn name(&self) -> &'static str {
+        "GitHub"
+    }
+
+    fn ensure_ready(&self) -> Result<()> {
+        ensure_gh()
+    }
+
+    fn current_user(&self) -> Result<String> {
+        current_user()
+    }
+
+    fn fetch_prs(&self, query: &SearchQuery) -> Result<Vec<PullRequest>> {
+        fetch_prs(query)
+    }
+
+    fn fetch_issues(&self, query: &SearchQuery) -> Result<Vec<Issue>> {
+        fetch_issues(query)
+    }
+
+    fn fetch_reviews(&self, repo: &str, number: u32) -> Result<Vec<Review>> {
+        fetch_pr_reviews(repo, number)
+    }
+
+    fn fetch_comments(&self, kind: ItemKind, repo: &str, number: u32) -> Result<Vec<Comment>> {
+        match kind {
+            ItemKind::PullRequest => fetch_pr_comments(repo, number),
+            ItemKind::Issue => fetch_issue_comments(repo, number),
+        }
+    }
+}
+
+/// 検索対象（オーナー・対象ユーザー・期間）
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SearchQuery {
+    /// 取得対象のOrganizationまたはユーザー（`None`はGitHub全体の公開リポジトリ）
+    #[serde(default)]
+    pub owner: Option<String>,
+    /// Organizationが所有するリポジトリのみを対象にする（個人のリポジトリを除く）
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub org_only: bool,
+    /// 対象ユーザー（`role`で関わり方を指定）
+    pub author: String,
+    pub since: String,
+    pub until: String,
+    #[serde(default)]
+    pub role: Role,
+    /// 期間の日付と取得した日時を扱うタイムゾーン（省略時はGitHubの検索と同じUTC）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tz: Option<Tz>,
+    /// レポートのタイトル・ファイル名・プロンプトで日付の代わりに使う期間の呼び名（例: `2025年度上期`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+impl SearchQuery {
+    /// 検索で使うオーナーの絞り込み（未指定の場合は公開リポジトリ全体）
+    fn scope(&self) -> String {
+        match &self.owner {
+            Some(owner) => format!("--owner={owner}"),
+            None => "--visibility=public".to_string(),
+        }
+    }
+
+    /// 表示用の期間（`label`指定時はその呼び名、それ以外は`since〜until`）
+    pub fn period(&self) -> String {
+        match &self.label {
+            Some(label) => label.clone(),
+            None => format!("{}〜{}", self.since, self.until),
+        }
+    }
+
+    /// ファイル名に使う期間（`label`指定時はパスに使えない文字と空白を`-`に置き換えたもの）
+    pub fn file_period(&self, separator: char) -> String {
+        match &self.label {
+            Some(label) => label
+                .chars()
+                .map(|c| {
+                    if c.is_whitespace() || "/\\:*?\"<>|".contains(c) {
+                        '-'
+                    } else {
+                        c
+                    }
+                })
+                .collect(),
+            None => format!("{}{separator}{}", self.since, self.until),
+        }
+    }
+
+    /// 検索で使う期間（`tz`指定時はそのタイムゾーンでの開始日0時〜終了日23時59分59秒）
+    pub fn date_range(&self) -> String {
+        let Some(tz) = self.tz else {
+            return format!("{}..{}", self.since, self.until);
+        };
+        let bound = |date: &str, time: NaiveTime| {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| tz.from_local_datetime(&d.and_time(time)).earliest())
+                .map_or_else(
+                    || date.to_string(),
+                    |t| t.to_rfc3339_opts(SecondsFormat::Secs, false),
+                )
+        };
+        format!(
+            "{}..{}",
+            bound(&self.since, NaiveTime::MIN),
+            bound(
+                &self.until,
+                NaiveTime::from_hms_opt(23, 59, 59).unwrap_or_default()
+            )
+        )
+    }
+
+    /// RFC 3339の日時を`tz`のタイムゾーンに変換（`tz`未指定や解釈できない場合はそのまま）
+    pub fn localize(&self, timestamp: &str) -> String {
+        match (self.tz, DateTime::parse_from_rfc3339(timestamp)) {
+            (Some(tz), Ok(time)) => time
+                .with_timezone(&tz)
+                .to_rfc3339_opts(SecondsFormat::Secs, false),
+            _ => timestamp.to_string(),
+        }
+    }
+}
+
+/// 対象ユーザーとPR/Issueの関わり方（`gh search`の絞り込み条件）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// 作成者
+    #[default]
+    Author,
+    /// アサインされた担当者
+    Assignee,
+    /// レビュアー（PRのみ）
+    Reviewer,
+    /// メンションされた
+    Mentions,
+    /// 作成・担当・メンション・コメントのいずれかで関与
+    Involves,
+}
+
+impl Role {
+    fn search_qualifier(self, user: &str) -> String {
+        match self {
+            Role::Author => format!("--author={user}"),
+            Role::Assignee => format!("--assignee={user}"),
+            Role::Reviewer => format!("--reviewed-by={user}"),
+            Role::Mentions => format!("--mentions={user}"),
+            Role::Involves => format!("--involves={user}"),
+        }
+    }
+
+    /// プロンプトで使う関わり方の説明
+    pub fn description(self) -> &'static str {
+        match self {
+            Role::Author => "作成した",
+            Role::Assignee => "担当者としてアサインされた",
+            Role::Reviewer => "レビューした",
+            Role::Mentions => "メンションされた",
+            Role::Involves => "関与した",
+        }
+    }
+}
+
+/// 検索条件と取得したPR/Issue一式
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Activity {
+    pub query: SearchQuery,
+    pub prs: Vec<PullRequest>,
+    pub issues: Vec<Issue>,
+    /// 作成・回答したDiscussion（`--discussions`指定時のみ）
+    #[serde(default)]
+    pub discussions: Vec<Discussion>,
+    /// 公開したリリース（`--releases`指定時のみ）
+    #[serde(default)]
+    pub releases: Vec<Release>,
+    /// 社外のOSSへのPRと公開Gist（`--community`指定時のみ）
+    #[serde(default)]
+    pub community: Community,
+    /// Organizationでの役割と所属チーム（`--membership`指定時のみ）
+    #[serde(default)]
+    pub membership: Membership,
+    /// 他のメンバーのIssueでのトリアージ（`--triage`指定時のみ）
+    #[serde(default)]
+    pub triage: Vec<TriageItem>,
+    /// メンションと他のリポジトリからの参照（`--mentions`指定時のみ）
+    #[serde(default)]
+    pub mentions: Mentions,
+}
+
+/// コミュニティへの貢献
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Community {
+    /// `owner`と自分以外の公開リポジトリで、期間内にマージされたPR
+    #[serde(default)]
+    pub oss_prs: Vec<PullRequest>,
+    /// 期間内に作成した公開Gist
+    #[serde(default)]
+    pub gists: Vec<Gist>,
+}
+
+impl Community {
+    pub fn is_empty(&self) -> bool {
+        self.oss_prs.is_empty() && self.gists.is_empty()
+    }
+}
+
+/// Organizationでの役割と所属チーム
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Membership {
+    /// Organizationでの役割（`admin`・`member`、取得できない場合は`None`）
+    #[serde(default)]
+    pub role: Option<String>,
+    #[serde(default)]
+    pub teams: Vec<Team>,
+}
+
+impl Membership {
+    pub fn is_empty(&self) -> bool {
+        self.role.is_none() && self.teams.is_empty()
+    }
+}
+
+/// 他のメンバーからのメンションと、他のリポジトリからの参照
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Mentions {
+    /// 期間内に更新され`query.author`がメンションされた、他のメンバーのPR/Issue
+    #[serde(default)]
+    pub mentioned_in: Vec<Reference>,
+    /// 期間内に他のリポジトリのPR/Issueから参照された、本人のPR
+    #[serde(default)]
+    pub cross_references: Vec<Reference>,
+}
+
+impl Mentions {
+    pub fn is_empty(&self) -> bool {
+        self.mentioned_in.is_empty() && self.cross_references.is_empty()
+    }
+}
+
+/// メンション・参照元のPR/Issue
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Reference {
+    pub title: String,
+    pub url: String,
+    pub repository: Repository,
+    /// メンション・参照した人
+    pub author: Option<CommentAuthor>,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    /// 参照された本人のPRのURL（メンションの場合は`None`）
+    #[serde(default)]
+    pub referenced: Option<String>,
+}
+
+/// 他のメンバーが起票したIssueでのトリアージ（ラベル付け・クローズ・最初の応答）
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TriageItem {
+    pub number: u32,
+    pub title: String,
+    pub url: String,
+    pub repository: Repository,
+    pub author: Option<CommentAuthor>,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    /// 期間内に付けたラベル（`fetch_triage`で設定）
+    #[serde(default)]
+    pub labels_applied: Vec<String>,
+    /// 期間内にクローズした場合の理由（`COMPLETED`・`NOT_PLANNED`・`DUPLICATE`）
+    #[serde(default)]
+    pub closed_as: Option<String>,
+    /// 期間内にコメントしたか
+    #[serde(default)]
+    pub commented: bool,
+    /// 起票者以外で最初にコメントしたか
+    #[serde(default)]
+    pub first_responder: bool,
+}
+
+impl TriageItem {
+    /// ラベル付け・クローズ・コメントのいずれも期間内になかったか
+    pub fn is_empty(&self) -> bool {
+        self.labels_applied.is_empty() && self.closed_as.is_none() && !self.commented
+    }
+}
+
+// Issueのタイムラインの1件（`fetch_triage`の中間形式）
+#[derive(Debug, Deserialize)]
+struct TimelineEvent {
+    #[serde(rename = "type")]
+    kind: String,
+    actor: Option<String>,
+    #[serde(rename = "createdAt")]
+    created_at: String,
+    label: Option<String>,
+    #[serde(rename = "stateReason")]
+    state_reason: Option<String>,
+}
+
+/// 所属チーム
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Team {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// チームでの役割（`MAINTAINER`・`MEMBER`）
+    #[serde(default)]
+    pub role: Option<String>,
+}
+
+/// 公開Gist
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Gist {
+    pub url: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    /// ファイル名
+    #[serde(default)]
+    pub files: Vec<String>,
+}
+
+impl Activity {
+    /// 同じURLのPR/Issue/Discussion/リリースを最初の1件だけ残してまとめ、取り除いた件数を返す
+    ///
+    /// 複数のOwnerや重なった期間で検索した場合に、同じ項目が重複して数えられるのを防ぎます。
+    pub fn dedupe(&mut self) -> usize {
+        dedupe_by(&mut self.prs, |pr| &pr.url)
+            + dedupe_by(&mut self.issues, |issue| &issue.url)
+            + dedupe_by(&mut self.discussions, |discussion| &discussion.url)
+            + dedupe_by(&mut self.releases, |release| &release.url)
+            + dedupe_by(&mut self.community.oss_prs, |pr| &pr.url)
+            + dedupe_by(&mut self.community.gists, |gist| &gist.url)
+    }
+
+    /// 作成・クローズ・コメント・レビュー・公開の日時を`query.tz`のタイムゾーンに変換
+    ///
+    /// 日時の先頭（`YYYY-MM-DD`）で集計するため、期間の境界付近の活動が
+    /// 別の月や半期に数えられないようにします。
+    pub fn localize_timestamps(&mut self) {
+        let query = &self.query;
+        if query.tz.is_none() {
+            return;
+        }
+        let localize = |time: &mut String| *time = query.localize(time);
+        let localize_opt = |time: &mut Option<String>| {
+            if let Some(time) = time {
+                *time = query.localize(time);
+            }
+        };
+        for pr in &mut self.prs {
+            localize(&mut pr.created_at);
+            localize_opt(&mut pr.closed_at);
+            pr.comments
+                .iter_mut()
+                .for_each(|c| localize(&mut c.created_at));
+            for review in pr.reviews.iter_mut().chain(&mut pr.authored_reviews) {
+                localize_opt(&mut review.submitted_at);
+            }
+        }
+        for issue in &mut self.issues {
+            localize(&mut issue.created_at);
+            localize_opt(&mut issue.closed_at);
+            issue
+                .comments
+                .iter_mut()
+                .for_each(|c| localize(&mut c.created_at));
+        }
+        for discussion in &mut self.discussions {
+            localize(&mut discussion.created_at);
+        }
+        for release in &mut self.releases {
+            localize_opt(&mut release.published_at);
+        }
+        for pr in &mut self.community.oss_prs {
+            localize(&mut pr.created_at);
+            localize_opt(&mut pr.closed_at);
+        }
+        for gist in &mut self.community.gists {
+            localize(&mut gist.created_at);
+        }
+    }
+}
+
+// URLは末尾のスラッシュと大文字・小文字の違いを無視して比較する
+fn dedupe_by<T>(items: &mut Vec<T>, url: impl Fn(&T) -> &str) -> usize {
+    let before = items.len();
+    let mut seen = HashSet::new();
+    items.retain(|item| seen.insert(url(item).trim_end_matches('/').to_ascii_lowercase()));
+    before - items.len()
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Repository {
+    #[serde(rename = "nameWithOwner")]
+    pub name_with_owner: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PullRequest {
+    pub number: u32,
+    pub title: String,
+    pub body: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    /// クローズ日時（マージ済みPRではマージ日時と同じ）
+    #[serde(rename = "closedAt", default)]
+    pub closed_at: Option<String>,
+    pub state: String,
+    /// ドラフトのままのPRか
+    #[serde(rename = "isDraft", default)]
+    pub is_draft: bool,
+    pub url: String,
+    pub repository: Repository,
+    #[serde(default)]
+    pub author: Option<CommentAuthor>,
+    #[serde(default)]
+    pub labels: Vec<Label>,
+    /// 担当者（アサインされたユーザー）
+    #[serde(default)]
+    pub assignees: Vec<CommentAuthor>,
+    /// コメント総数（`comments`は一部のPR/Issueのみ取得するため別に保持）
+    #[serde(rename = "commentsCount", default)]
+    pub comments_count: usize,
+    /// 付与したトピック（`topics::tag_activity`で設定）
+    #[serde(default)]
+    pub topics: Vec<String>,
+    /// revert PRの場合、取り消し対象のPRのURL（`revert::mark_reverts`で設定）
+    #[serde(default)]
+    pub reverts: Option<String>,
+    /// 取り消されたPRの場合、マージされたrevert PRのURL（`revert::mark_reverts`で設定）
+    #[serde(default)]
+    pub reverted_by: Option<String>,
+    // 検索結果には含まれないため、個別に取得して設定する
+    #[serde(default)]
+    pub comments: Vec<Comment>,
+    /// 他のメンバーから受けたレビュー（`source::attach_pr_reviews`で設定）
+    #[serde(default)]
+    pub reviews: Vec<Review>,
+    /// 検索対象のユーザー自身が行ったレビュー（`source::attach_pr_reviews`で設定）
+    #[serde(default)]
+    pub authored_reviews: Vec<Review>,
+    /// 他の人のPRでのレビュースレッド（`attach_review_threads`で設定）
+    #[serde(default)]
+    pub review_threads: Vec<ReviewThread>,
+    /// まだレビューしていないレビュー依頼先のユーザー・チーム（`attach_review_requests`で設定）
+    #[serde(default)]
+    pub requested_reviewers: Vec<String>,
+    /// マイルストーンとプロジェクト（`attach_pr_roadmap`で設定）
+    #[serde(default)]
+    pub roadmap: Roadmap,
+    /// PR本体へのリアクション（`attach_pr_reactions`で設定）
+    #[serde(default)]
+    pub reactions: Reactions,
+    /// PRのコミット（`attach_pr_commits`で設定）
+    #[serde(default)]
+    pub commits: Vec<Commit>,
+    /// このPRで解決したIssue（`attach_closing_issues`で設定）
+    #[serde(default)]
+    pub closing_issues: Vec<LinkedIssue>,
+    /// 変更したファイル（`attach_pr_files`で設定）
+    #[serde(default)]
+    pub files: Vec<ChangedFile>,
+    /// マージコミットのチェック結果（`attach_ship_signals`で設定）
+    #[serde(default)]
+    pub checks: Vec<CheckRun>,
+    /// マージコミットのデプロイ（`attach_ship_signals`で設定）
+    #[serde(default)]
+    pub deployments: Vec<Deployment>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Issue {
+    pub number: u32,
+    pub title: String,
+    pub body: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    /// クローズ日時
+    #[serde(rename = "closedAt", default)]
+    pub closed_at: Option<String>,
+    pub state: String,
+    pub url: String,
+    pub repository: Repository,
+    #[serde(default)]
+    pub author: Option<CommentAuthor>,
+    #[serde(default)]
+    pub labels: Vec<Label>,
+    /// 担当者（アサインされたユーザー）
+    #[serde(default)]
+    pub assignees: Vec<CommentAuthor>,
+    /// コメント総数（`comments`は一部のPR/Issueのみ取得するため別に保持）
+    #[serde(rename = "commentsCount", default)]
+    pub comments_count: usize,
+    /// 付与したトピック（`topics::tag_activity`で設定）
+    #[serde(default)]
+    pub topics: Vec<String>,
+    /// マイルストーンとプロジェクト（`attach_issue_roadmap`で設定）
+    #[serde(default)]
+    pub roadmap: Roadmap,
+    /// Issue本体へのリアクション（`attach_issue_reactions`で設定）
+    #[serde(default)]
+    pub reactions: Reactions,
+    // 検索結果には含まれないため、個別に取得して設定する
+    #[serde(default)]
+    pub comments: Vec<Comment>,
+}
+
+/// PRに含まれるコミット
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Commit {
+    /// 短縮したコミットハッシュ
+    pub sha: String,
+    pub message: String,
+}
+
+impl Commit {
+    /// コミットメッセージの1行目
+    pub fn subject(&self) -> &str {
+        self.message.lines().next().unwrap_or_default()
+    }
+}
+
+/// PR/Issueが属するマイルストーンとプロジェクト（Projects）
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct Roadmap {
+    #[serde(default)]
+    pub milestone: Option<String>,
+    /// プロジェクトのタイトル
+    #[serde(default)]
+    pub projects: Vec<String>,
+}
+
+impl Roadmap {
+    pub fn is_empty(&self) -> bool {
+        self.milestone.is_none() && self.projects.is_empty()
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Comment {
+    pub author: Option<CommentAuthor>,
+    pub body: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    #[serde(default)]
+    pub reactions: Reactions,
+}
+
+/// PR/Issue・コメントに付いたリアクションの数
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy)]
+pub struct Reactions {
+    #[serde(rename = "+1", default)]
+    pub thumbs_up: usize,
+    #[serde(default)]
+    pub hooray: usize,
+    #[serde(default)]
+    pub heart: usize,
+    #[serde(default)]
+    pub rocket: usize,
+    /// 👎なども含めたすべてのリアクションの数
+    #[serde(rename = "total_count", default)]
+    pub total: usize,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct CommentAuthor {
+    pub login: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Label {
+    pub name: String,
+}
+
+/// PRで変更したファイル
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ChangedFile {
+    pub path: String,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+/// GitHub Discussionsのスレッド
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Discussion {
+    pub number: u32,
+    pub title: String,
+    pub url: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    pub repository: Repository,
+    /// カテゴリ名（Q&A、Ideasなど）
+    pub category: Option<String>,
+    pub author: Option<CommentAuthor>,
+    #[serde(rename = "commentsCount", default)]
+    pub comments_count: usize,
+    /// 回答として採用されたコメントの投稿者
+    #[serde(rename = "answerAuthor")]
+    pub answer_author: Option<String>,
+}
+
+/// リポジトリのリリース
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Release {
+    /// リリースしたリポジトリ（`fetch_releases`で設定）
+    #[serde(default)]
+    pub repository: String,
+    #[serde(rename = "tagName")]
+    pub tag_name: String,
+    /// リリースのタイトル
+    pub name: Option<String>,
+    pub url: String,
+    #[serde(rename = "publishedAt")]
+    pub published_at: Option<String>,
+    pub author: Option<CommentAuthor>,
+}
+
+/// コミットに対するチェック（GitHub ActionsなどのCI）の結果
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct CheckRun {
+    pub name: String,
+    /// success / failure / neutral / skipped / cancelled など（実行中は`None`）
+    pub conclusion: Option<String>,
+}
+
+impl CheckRun {
+    /// 失敗扱いにならない結果か
+    pub fn passed(&self) -> bool {
+        matches!(
+            self.conclusion.as_deref(),
+            Some("success" | "neutral" | "skipped")
+        )
+    }
+}
+
+/// コミットのデプロイと最新の状態
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Deployment {
+    pub id: u64,
+    pub environment: String,
+    /// success / failure / inactive など（状態がない場合は`None`）
+    pub state: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+}
+
+impl Deployment {
+    /// 成功したデプロイか（後続のデプロイで置き換えられた`inactive`も含む）
+    pub fn succeeded(&self) -> bool {
+        matches!(self.state.as_deref(), Some("success" | "inactive"))
+    }
+
+    /// 本番環境へのデプロイか（環境名で判定）
+    pub fn is_production(&self) -> bool {
+        let environment = self.environment.to_lowercase();
+        environment.starts_with("prod") || environment == "live"
+    }
+}
+
+/// PRの "closes #N" などで紐づいたIssue
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct LinkedIssue {
+    pub repository: String,
+    pub number: u32,
+    pub url: String,
+    pub title: Option<String>,
+    pub state: Option<String>,
+    /// Issueの起票者
+    pub author: Option<String>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// PRの作成者自身が起票したIssueか
+    #[serde(default)]
+    pub filed_by_author: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Review {
+    pub author: Option<CommentAuthor>,
+    /// APPROVED / CHANGES_REQUESTED / COMMENTED / DISMISSED
+    pub state: String,
+    #[serde(default)]
+    pub body: String,
+    #[serde(rename = "submittedAt")]
+    pub submitted_at: Option<String>,
+}
+
+/// PRのレビュースレッド（コードへのコメントから始まる議論）
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ReviewThread {
+    /// スレッドを始めたユーザー
+    pub author: Option<String>,
+    #[serde(rename = "isResolved")]
+    pub is_resolved: bool,
+}
+
+/// `gh` CLIの利用可否
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GhStatus {
+    /// インストール済みで認証済み（`GH_TOKEN`/`GITHUB_TOKEN`による認証を含む）
+    Ready,
+    NotInstalled,
+    /// 未ログインで、トークンの環境変数も設定されていない
+    Unauthenticated,
+    /// トークンの環境変数が設定されているが、認証に失敗した
+    InvalidToken,
+}
+
+/// `gh` CLIがインストール・認証済みかを確認
+pub fn gh_status() -> GhStatus {
+    match gh(&["--version"]) {
+        Ok(output) if output.status.success() => {}
+        _ => return GhStatus::NotInstalled,
+    }
+    let authenticated = gh(&["auth", "status"]).is_ok_and(|output| output.status.success());
+    if authenticated {
+        GhStatus::Ready
+    } else if token_from_env().is_some() {
+        GhStatus::InvalidToken
+    } else {
+        GhStatus::Unauthenticated
+    }
+}
+
+/// `gh`を使えない場合に、対処方法を含むエラーを返す
+///
+/// `gh`は`GH_TOKEN`/`GITHUB_TOKEN`環境変数のトークンでも認証できるため、
+/// `gh auth login`できない環境（CIなど）ではトークンを設定すれば動作します。
+pub fn ensure_gh() -> Result<()> {
+    match gh_status() {
+        GhStatus::Ready => Ok(()),
+        GhStatus::NotInstalled => anyhow::bail!(
+            "GitHub CLI（gh）が見つかりません。https://cli.github.com/ からインストールし、`gh auth login`で認証してください"
+        ),
+        GhStatus::Unauthenticated => anyhow::bail!(
+            "GitHub CLI（gh）が認証されていません。`gh auth login`を実行するか、GITHUB_TOKEN環境変数にトークン（repo, read:org権限）を設定してください"
+        ),
+        GhStatus::InvalidToken => anyhow::bail!(
+            "{}環境変数のトークンでGitHubに認証できません。トークンの有効期限と権限（repo, read:org）を確認してください",
+            token_from_env().unwrap_or("GITHUB_TOKEN")
+        ),
+    }
+}
+
+// ghが認証に使うトークンの環境変数名（設定されているもの）
+fn token_from_env() -> Option<&'static str> {
+    ["GH_TOKEN", "GITHUB_TOKEN"]
+        .into_iter()
+        .find(|name| std::env::var(name).is_ok_and(|v| !v.is_empty()))
+}
+
+/// ghコマンドで認証中のGitHubユーザー名を取得
+pub fn current_user() -> Result<String> {
+    let output = gh(&["api", "user", "--jq", ".login"])?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to get current GitHub user: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+// TODO: 将来的な拡張案
+// - 1000件を超える場合は日付範囲を自動分割して再帰的に取得
+// - GraphQL APIを使用してカーソルベースのページネーションを実装
+// - 並列処理で複数の期間を同時に取得
+/// 期間内に作成されたPRを検索（最大 [`SEARCH_LIMIT`] 件）
+pub fn fetch_prs(query: &SearchQuery) -> Result<Vec<PullRequest>> {
+    let mut prs: Vec<PullRequest> = search("prs", query, &format!("{SEARCH_FIELDS},isDraft"))?;
+    if query.org_only {
+        retain_org_owned(&mut prs, |pr| &pr.repository.name_with_owner);
+    }
+    Ok(prs)
+}
+
+/// 期間内に作成されたIssueを検索（最大 [`SEARCH_LIMIT`] 件）
+///
+/// レビュアーとしての検索ではIssueは対象外のため空を返します。
+pub fn fetch_issues(query: &SearchQuery) -> Result<Vec<Issue>> {
+    if query.role == Role::Reviewer {
+        return Ok(Vec::new());
+    }
+    let mut issues: Vec<Issue> = search("issues", query, SEARCH_FIELDS)?;
+    if query.org_only {
+        retain_org_owned(&mut issues, |issue| &issue.repository.name_with_owner);
+    }
+    Ok(issues)
+}
+
+// Organizationが所有するリポジトリの項目のみを残す（所有者の種類は所有者ごとに1回だけ確認）
+fn retain_org_owned<T>(items: &mut Vec<T>, repo: impl Fn(&T) -> &str) {
+    let mut is_org: HashMap<String, bool> = HashMap::new();
+    items.retain(|item| {
+        let owner = repo(item).split('/').next().unwrap_or_default();
+        *is_org
+            .entry(owner.to_string())
+            .or_insert_with(|| is_organization(owner))
+    });
+}
+
+// 所有者がOrganizationか（確認できない場合はfalse）
+fn is_organization(owner: &str) -> bool {
+    gh(&["api", &format!("users/{owner}"), "--jq", ".type"]).is_ok_and(|output| {
+        output.status.success() && String::from_utf8_lossy(&output.stdout).trim() == "Organization"
+    })
+}
+
+// PR/Issue共通で取得するフィールド
+const SEARCH_FIELDS: &str = "number,title,body,createdAt,closedAt,state,url,repository,author,assignees,labels,commentsCount";
+
+fn search<T: DeserializeOwned>(kind: &str, query: &SearchQuery, fields: &str) -> Result<Vec<T>> {
+    let output = gh(&[
+        "search",
+        kind,
+        &query.scope(),
+        &query.role.search_qualifier(&query.author),
+        &format!("--created={}", query.date_range()),
+        &format!("--limit={SEARCH_LIMIT}"),
+        &format!("--json={fields}"),
+    ])?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "gh command failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    Ok(serde_json::from_slice(&output.stdout)?)
+}
+
+const DISCUSSION_SEARCH_QUERY: &str = r#"
+query($q: String!) {
+  search(query: $q, type: DISCUSSION, first: 100) {
+    nodes {
+      ... on Discussion {
+        number
+        title
+        url
+        createdAt
+        repository { nameWithOwner }
+        category { name }
+        author { login }
+        comments { totalCount }
+        answer { author { login } }
+      }
+    }
+  }
+}
+"#;
+
+/// 期間内に作成したDiscussionと、期間内にコメント（回答）したDiscussionを検索（それぞれ最大100件）
+pub fn fetch_discussions(query: &SearchQuery) -> Result<Vec<Discussion>> {
+    let period = query.date_range();
+    let scope = match &query.owner {
+        Some(owner) => format!("user:{owner}"),
+        None => "is:public".to_string(),
+    };
+    let mut discussions =
+        search_discussions(&format!("{scope} author:{} created:{period}", query.author))?;
+    for discussion in search_discussions(&format!(
+        "{scope} commenter:{} updated:{period}",
+        query.author
+    ))? {
+        if !discussions.iter().any(|d| d.url == discussion.url) {
+            discussions.push(discussion);
+        }
+    }
+    if query.org_only {
+        retain_org_owned(&mut discussions, |d| &d.repository.name_with_owner);
+    }
+    Ok(discussions)
+}
+
+fn search_discussions(search: &str) -> Result<Vec<Discussion>> {
+    let output = gh(&[
+        "api",
+        "graphql",
+        "-f",
+        &format!("query={DISCUSSION_SEARCH_QUERY}"),
+        "-f",
+        &format!("q={search}"),
+        "--jq",
+        ".data.search.nodes[] | {number, title, url, createdAt, repository, category: .category.name, author, commentsCount: .comments.totalCount, answerAuthor: .answer.author.login}",
+    ])?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "gh command failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    let mut discussions = Vec::new();
+    for line in output.stdout.split(|&b| b == b'\n') {
+        if !line.is_empty() {
+            discussions.push(serde_json::from_slice(line)?);
+        }
+    }
+    Ok(discussions)
+}
+
+/// `query.author`のコミュニティへの貢献（社外のOSSでマージされたPRと公開Gist）を取得
+///
+/// OSSのPRは`query.owner`と本人以外が所有する公開リポジトリのものに限ります。
+pub fn fetch_community(query: &SearchQuery) -> Result<Community> {
+    let output = gh(&[
+        "search",
+        "prs",
+        "--visibility=public",
+        &format!("--author={}", query.author),
+        &format!("--merged={}", query.date_range()),
+        &format!("--limit={SEARCH_LIMIT}"),
+        &format!("--json={SEARCH_FIELDS}"),
+    ])?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "gh command failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    let mut oss_prs: Vec<PullRequest> = serde_json::from_slice(&output.stdout)?;
+    oss_prs.retain(|pr| {
+        let owner = pr
+            .repository
+            .name_with_owner
+            .split('/')
+            .next()
+            .unwrap_or_default();
+        !owner.eq_ignore_ascii_case(&query.author)
+            && query
+                .owner
+                .as_deref()
+                .is_none_or(|o| !owner.eq_ignore_ascii_case(o))
+    });
+
+    // Gistは新しい順の100件から、作成日の日付部分で期間内のものを選ぶ
+    let gists: Vec<Gist> = fetch_jsonl(
+        &format!("users/{}/gists?per_page=100", query.author),
+        ".[] | {url: .html_url, description: .description, createdAt: .created_at, files: (.files | keys)}",
+    )?;
+    let gists = gists
+        .into_iter()
+        .filter(|gist| {
+            let created = query.localize(&gist.created_at);
+            created
+                .get(..10)
+                .is_some_and(|date| query.since.as_str() <= date && date <= query.until.as_str())
+        })
+        .collect();
+
+    Ok(Community { oss_prs, gists })
+}
+
+const TIMELINE_QUERY: &str = r#"
+query($owner: String!, $name: String!, $number: Int!) {
+  repository(owner: $owner, name: $name) {
+    issue(number: $number) {
+      timelineItems(first: 100, itemTypes: [LABELED_EVENT, CLOSED_EVENT, ISSUE_COMMENT]) {
+        nodes {
+          __typename
+          ... on LabeledEvent { actor { login } createdAt label { name } }
+          ... on ClosedEvent { actor { login } createdAt stateReason }
+          ... on IssueComment { author { login } createdAt }
+        }
+      }
+    }
+  }
+}
+"#;
+
+/// `query.author`が関与した他のメンバーのIssueを検索し、期間内のトリアージを取得
+///
+/// 期間内に更新され`query.author`が関与（コメント・担当・メンション）したIssueのうち、
+/// ラベル付け・クローズ・コメントのいずれかを期間内に行ったものを返します。
+/// 関与のないIssueへのラベル付けだけは検索できないため含まれません。
+/// `on_progress`はIssueを1件取得するごとに呼ばれます。
+pub fn fetch_triage(query: &SearchQuery, on_progress: impl FnMut()) -> Result<Vec<TriageItem>> {
+    let output = gh(&[
+        "search",
+        "issues",
+        &query.scope(),
+        &format!("--involves={}", query.author),
+        &format!("--updated={}", query.date_range()),
+        &format!("--limit={SEARCH_LIMIT}"),
+        "--json=number,title,url,repository,author,createdAt",
+    ])?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "gh command failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    let mut items: Vec<TriageItem> = serde_json::from_slice(&output.stdout)?;
+    items.retain(|item| item.author.as_ref().is_none_or(|a| a.login != query.author));
+    if query.org_only {
+        retain_org_owned(&mut items, |item| &item.repository.name_with_owner);
+    }
+
+    let in_period = |time: &str| {
+        query
+            .localize(time)
+            .get(..10)
+            .is_some_and(|date| query.since.as_str() <= date && date <= query.until.as_str())
+    };
+    limits::for_each_concurrent(items.iter_mut(), on_progress, |item| {
+        let Some((owner, name)) = item.repository.name_with_owner.split_once('/') else {
+            return;
+        };
+        let events: Vec<TimelineEvent> = gh(&[
+            "api",
+            "graphql",
+            "-f",
+            &format!("query={TIMELINE_QUERY}"),
+            "-f",
+            &format!("owner={owner}"),
+            "-f",
+            &format!("name={name}"),
+            "-F",
+            &format!("number={}", item.number),
+            "--jq",
+            ".data.repository.issue.timelineItems.nodes[] | {type: .__typename, actor: (.actor.login // .author.login), createdAt, label: .label.name, stateReason}",
+        ])
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| {
+            output
+                .stdout
+                .split(|&b| b == b'\n')
+                .filter_map(|line| serde_json::from_slice(line).ok())
+                .collect()
+        })
+        .unwrap_or_default();
+
+        let issue_author = item.author.as_ref().map(|a| a.login.as_str());
+        let by_author = |e: &&TimelineEvent| e.actor.as_deref() == Some(query.author.as_str());
+        for event in events
+            .iter()
+            .filter(by_author)
+            .filter(|e| in_period(&e.created_at))
+        {
+            match event.kind.as_str() {
+                "LabeledEvent" => item.labels_applied.extend(event.label.clone()),
+                "ClosedEvent" => item.closed_as = event.state_reason.clone(),
+                "IssueComment" => item.commented = true,
+                _ => {}
+            }
+        }
+        item.first_responder = events
+            .iter()
+            .find(|e| e.kind == "IssueComment" && e.actor.as_deref() != issue_author)
+            .is_some_and(|e| by_author(&e) && in_period(&e.created_at));
+    });
+    items.retain(|item| !item.is_empty());
+    Ok(items)
+}
+
+/// 期間内に更新され`query.author`がメンションされた、他のメンバーのPR/Issueを検索
+pub fn fetch_mentions(query: &SearchQuery) -> Result<Vec<Reference>> {
+    let output = gh(&[
+        "search",
+        "issues",
+        "--include-prs",
+        &query.scope(),
+        &format!("--mentions={}", query.author),
+        &format!("--updated={}", query.date_range()),
+        &format!("--limit={SEARCH_LIMIT}"),
+        "--json=title,url,repository,author,createdAt",
+    ])?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "gh command failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    let mut references: Vec<Reference> = serde_json::from_slice(&output.stdout)?;
+    references.retain(|r| r.author.as_ref().is_none_or(|a| a.login != query.author));
+    if query.org_only {
+        retain_org_owned(&mut references, |r| &r.repository.name_with_owner);
+    }
+    Ok(references)
+}
+
+const CROSS_REFERENCE_QUERY: &str = r#"
+query($owner: String!, $name: String!, $number: Int!) {
+  repository(owner: $owner, name: $name) {
+    pullRequest(number: $number) {
+      timelineItems(first: 100, itemTypes: [CROSS_REFERENCED_EVENT]) {
+        nodes {
+          ... on CrossReferencedEvent {
+            actor { login }
+            createdAt
+            isCrossRepository
+            source {
+              ... on Issue { title url repository { nameWithOwner } }
+              ... on PullRequest { title url repository { nameWithOwner } }
+            }
+          }
+        }
+      }
+    }
+  }
+}
+"#;
+
+/// `prs`が期間内に他のリポジトリのPR/Issueから参照された記録を取得（本人による参照は除く）
+///
+/// `on_progress`はPRを1件取得するごとに呼ばれます。
+pub fn fetch_cross_references(
+    query: &SearchQuery,
+    prs: &[PullRequest],
+    on_progress: impl FnMut(),
+) -> Vec<Reference> {
+    let mut fetched: Vec<(&PullRequest, Vec<Reference>)> =
+        prs.iter().map(|pr| (pr, Vec::new())).collect();
+    limits::for_each_concurrent(fetched.iter_mut(), on_progress, |(pr, references)| {
+        let Some((owner, name)) = pr.repository.name_with_owner.split_once('/') else {
+            return;
+        };
+        let Ok(output) = gh(&[
+            "api",
+            "graphql",
+            "-f",
+            &format!("query={CROSS_REFERENCE_QUERY}"),
+            "-f",
+            &format!("owner={owner}"),
+            "-f",
+            &format!("name={name}"),
+            "-F",
+            &format!("number={}", pr.number),
+            "--jq",
+            ".data.repository.pullRequest.timelineItems.nodes[] | select(.isCrossRepository) | {title: .source.title, url: .source.url, repository: .source.repository, author: .actor, createdAt}",
+        ]) else {
+            return;
+        };
+        if !output.status.success() {
+            return;
+        }
+        *references = output
+            .stdout
+            .split(|&b| b == b'\n')
+            .filter_map(|line| serde_json::from_slice::<Reference>(line).ok())
+            .filter(|r| r.author.as_ref().is_none_or(|a| a.login != query.author))
+            .filter(|r| {
+                query.localize(&r.created_at).get(..10).is_some_and(|date| {
+                    query.since.as_str() <= date && date <= query.until.as_str()
+                })
+            })
+            .map(|r| Reference {
+                referenced: Some(pr.url.clone()),
+                ..r
+            })
+            .collect();
+    });
+    fetched
+        .into_iter()
+        .flat_map(|(_, references)| references)
+        .collect()
+}
+
+// `TEAMS_QUERY`の結果のチーム1件（メンバーは`$login`で絞り込み済み）
+#[derive(Deserialize)]
+struct TeamNode {
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    members: Vec<TeamMember>,
+}
+
+#[derive(Deserialize)]
+struct TeamMember {
+    login: String,
+    role: String,
+}
+
+const TEAMS_QUERY: &str = r#"
+query($org: String!, $login: String!) {
+  organization(login: $org) {
+    teams(first: 100, userLogins: [$login]) {
+      nodes {
+        name
+        description
+        members(query: $login, first: 10) {
+          edges { role node { login } }
+        }
+      }
+    }
+  }
+}
+"#;
+
+/// `query.author`の`query.owner`での役割と所属チームを取得
+///
+/// 役割は本人か管理者にしか見えないため、取得できない場合は`None`にします。
+/// チームは閲覧できる（秘密でない、または自分の所属する）ものに限られます。
+pub fn fetch_membership(query: &SearchQuery) -> Result<Membership> {
+    let Some(owner) = &query.owner else {
+        return Ok(Membership::default());
+    };
+    let output = gh(&[
+        "api",
+        &format!("orgs/{owner}/memberships/{}", query.author),
+        "--jq",
+        ".role",
+    ])?;
+    let role = output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|role| !role.is_empty());
+
+    // `members(query:)`はログイン名・名前の部分一致のため、本人の役割はログイン名が一致するものから選ぶ
+    let output = gh(&[
+        "api",
+        "graphql",
+        "-f",
+        &format!("query={TEAMS_QUERY}"),
+        "-f",
+        &format!("org={owner}"),
+        "-f",
+        &format!("login={}", query.author),
+        "--jq",
+        ".data.organization.teams.nodes[] | {name, description, members: [.members.edges[] | {login: .node.login, role}]}",
+    ])?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "gh command failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    let mut teams = Vec::new();
+    for line in output.stdout.split(|&b| b == b'\n') {
+        if !line.is_empty() {
+            let node: TeamNode = serde_json::from_slice(line)?;
+            let role = node
+                .members
+                .into_iter()
+                .find(|member| member.login == query.author)
+                .map(|member| member.role);
+            teams.push(Team {
+                name: node.name,
+                description: node.description,
+                role,
+            });
+        }
+    }
+    Ok(Membership { role, teams })
+}
+
+/// 各リポジトリで`query.author`が期間内に公開したリリースを取得（リポジトリごとに最新100件から検索）
+///
+/// `on_progress`は1リポジトリ取得するごとに呼ばれます。
+pub fn fetch_releases(
+    query: &SearchQuery,
+    repositories: &[String],
+    on_progress: impl FnMut(),
+) -> Vec<Release> {
+    let mut fetched: Vec<(&String, Vec<Release>)> =
+        repositories.iter().map(|repo| (repo, Vec::new())).collect();
+    limits::for_each_concurrent(fetched.iter_mut(), on_progress, |(repo, releases)| {
+        *releases = fetch_jsonl(
+            &format!("repos/{repo}/releases?per_page=100"),
+            ".[] | select(.draft | not) | {tagName: .tag_name, name: .name, url: .html_url, publishedAt: .published_at, author: {login: .author.login}}",
+        )
+        .unwrap_or_default();
+    });
+
+    let mut releases = Vec::new();
+    for (repo, fetched) in fetched {
+        releases.extend(
+            fetched
+                .into_iter()
+                .filter(|r| r.author.as_ref().is_some_and(|a| a.login == query.author))
+                .filter(|r| {
+                    // 公開日の日付部分で期間内か判定
+                    r.published_at
+                        .as_deref()
+                        .map(|time| query.localize(time))
+                        .and_then(|time| time.get(..10).map(str::to_string))
+                        .is_some_and(|date| {
+                            query.since.as_str() <= date.as_str()
+                                && date.as_str() <= query.until.as_str()
+                        })
+                })
+                .map(|r| Release {
+                    repository: repo.clone(),
+                    ..r
+                }),
+        );
+    }
+    releases
+}
+
+/// PRの会話のコメントを取得（検索結果の`commentsCount`と同じ種類。レビュー本文は`fetch_reviews`で取得）
+pub fn fetch_pr_comments(repo: &str, pr_number: u32) -> Result<Vec<Comment>> {
+    fetch_comments(&format!("repos/{repo}/issues/{pr_number}/comments"))
+}
+
+pub fn fetch_issue_comments(repo: &str, issue_number: u32) -> Result<Vec<Comment>> {
+    fetch_comments(&format!("repos/{repo}/issues/{issue_number}/comments"))
+}
+
+fn fetch_comments(endpoint: &str) -> Result<Vec<Comment>> {
+    fetch_jsonl(
+        endpoint,
+        ".[] | {author: {login: .user.login}, body: .body, createdAt: .created_at, reactions: .reactions}",
+    )
+}
+
+/// PR/Issue本体へのリアクションを取得
+pub fn fetch_reactions(repo: &str, number: u32) -> Result<Reactions> {
+    let output = gh(&[
+        "api",
+        &format!("repos/{repo}/issues/{number}"),
+        "--jq",
+        ".reactions",
+    ])?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "gh command failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    Ok(serde_json::from_slice(&output.stdout)?)
+}
+
+/// 各PRへのリアクションを取得して設定
+///
+/// `on_progress`は1件取得するごとに呼ばれます。
+pub fn attach_pr_reactions(prs: &mut [PullRequest], on_progress: impl FnMut()) {
+    limits::for_each_concurrent(prs.iter_mut(), on_progress, |pr| {
+        if let Ok(reactions) = fetch_reactions(&pr.repository.name_with_owner, pr.number) {
+            pr.reactions = reactions;
+        }
+    });
+}
+
+/// 各Issueへのリアクションを取得して設定
+///
+/// `on_progress`は1件取得するごとに呼ばれます。
+pub fn attach_issue_reactions(issues: &mut [Issue], on_progress: impl FnMut()) {
+    limits::for_each_concurrent(issues.iter_mut(), on_progress, |issue| {
+        if let Ok(reactions) = fetch_reactions(&issue.repository.name_with_owner, issue.number) {
+            issue.reactions = reactions;
+        }
+    });
+}
+
+/// PRに付いたレビュー（承認・変更要求・コメント）を取得
+pub fn fetch_pr_reviews(repo: &str, pr_number: u32) -> Result<Vec<Review>> {
+    fetch_jsonl(
+        &format!("repos/{repo}/pulls/{pr_number}/reviews"),
+        ".[] | {author: {login: .user.login}, state: .state, body: .body, submittedAt: .submitted_at}",
+    )
+}
+
+#[derive(Debug, Deserialize)]
+struct ReviewRequests {
+    users: Vec<String>,
+    teams: Vec<String>,
+}
+
+/// PRのレビュー依頼先のうち、まだレビューしていないユーザーと`@org/team`形式のチームを取得
+pub fn fetch_review_requests(repo: &str, pr_number: u32) -> Result<Vec<String>> {
+    let owner = repo.split('/').next().unwrap_or(repo);
+    let requests: Vec<ReviewRequests> = fetch_jsonl(
+        &format!("repos/{repo}/pulls/{pr_number}/requested_reviewers"),
+        "{users: [.users[].login], teams: [.teams[].slug]}",
+    )?;
+    Ok(requests
+        .into_iter()
+        .flat_map(|r| {
+            r.users
+                .into_iter()
+                .chain(r.teams.into_iter().map(|team| format!("@{owner}/{team}")))
+        })
+        .collect())
+}
+
+/// 各PRのレビュー依頼先を取得して設定
+///
+/// `on_progress`は1件取得するごとに呼ばれます。
+pub fn attach_review_requests(prs: &mut [PullRequest], on_progress: impl FnMut()) {
+    limits::for_each_concurrent(prs.iter_mut(), on_progress, |pr| {
+        if let Ok(reviewers) = fetch_review_requests(&pr.repository.name_with_owner, pr.number) {
+            pr.requested_reviewers = reviewers;
+        }
+    });
+}
+
+// PR/Issueのマイルストーンとプロジェクト（`$fields`にプロジェクトの有無を切り替えて埋め込む）
+const ROADMAP_QUERY: &str = r#"
+query($owner: String!, $name: String!, $number: Int!) {
+  repository(owner: $owner, name: $name) {
+    issueOrPullRequest(number: $number) {
+      ... on Issue { $fields }
+      ... on PullRequest { $fields }
+    }
+  }
+}
+"#;
+
+/// PR/Issueのマイルストーンとプロジェクトを取得
+///
+/// プロジェクトの取得には`read:project`スコープが必要なため、
+/// 取得できなかった場合はマイルストーンのみを取得します。
+pub fn fetch_roadmap(repo: &str, number: u32) -> Result<Roadmap> {
+    fetch_roadmap_fields(
+        repo,
+        number,
+        "milestone { title } projectItems(first: 20) { nodes { project { title } } }",
+    )
+    .or_else(|_| fetch_roadmap_fields(repo, number, "milestone { title }"))
+}
+
+fn fetch_roadmap_fields(repo: &str, number: u32, fields: &str) -> Result<Roadmap> {
+    let (owner, name) = repo.split_once('/').unwrap_or((repo, ""));
+    let output = gh(&[
+        "api",
+        "graphql",
+        "-f",
+        &format!("query={}", ROADMAP_QUERY.replace("$fields", fields)),
+        "-f",
+        &format!("owner={owner}"),
+        "-f",
+        &format!("name={name}"),
+        "-F",
+        &format!("number={number}"),
+        "--jq",
+        ".data.repository.issueOrPullRequest | {milestone: .milestone.title, projects: [.projectItems.nodes[]?.project.title]}",
+    ])?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "gh command failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    Ok(serde_json::from_slice(&output.stdout)?)
+}
+
+/// 各PRのマイルストーンとプロジェクトを取得して設定
+///
+/// `on_progress`は1件取得するごとに呼ばれます。
+pub fn attach_pr_roadmap(prs: &mut [PullRequest], on_progress: impl FnMut()) {
+    limits::for_each_concurrent(prs.iter_mut(), on_progress, |pr| {
+        if let Ok(roadmap) = fetch_roadmap(&pr.repository.name_with_owner, pr.number) {
+            pr.roadmap = roadmap;
+        }
+    });
+}
+
+/// 各Issueのマイルストーンとプロジェクトを取得して設定
+///
+/// `on_progress`は1件取得するごとに呼ばれます。
+pub fn attach_issue_roadmap(issues: &mut [Issue], on_progress: impl FnMut()) {
+    limits::for_each_concurrent(issues.iter_mut(), on_progress, |issue| {
+        if let Ok(roadmap) = fetch_roadmap(&issue.repository.name_with_owner, issue.number) {
+            issue.roadmap = roadmap;
+        }
+    });
+}
+
+// PRのレビュースレッドと、各スレッドの最初のコメントの投稿者
+const REVIEW_THREADS_QUERY: &str = r#"
+query($owner: String!, $name: String!, $number: Int!) {
+  repository(owner: $owner, name: $name) {
+    pullRequest(number: $number) {
+      reviewThreads(first: 100) {
+        nodes {
+          isResolved
+          comments(first: 1) { nodes { author { login } } }
+        }
+      }
+    }
+  }
+}
+"#;
+
+/// PRのレビュースレッドを取得（最大100件）
+pub fn fetch_review_threads(repo: &str, pr_number: u32) -> Result<Vec<ReviewThread>> {
+    let (owner, name) = repo.split_once('/').unwrap_or((repo, ""));
+    let output = gh(&[
+        "api",
+        "graphql",
+        "-f",
+        &format!("query={REVIEW_THREADS_QUERY}"),
+        "-f",
+        &format!("owner={owner}"),
+        "-f",
+        &format!("name={name}"),
+        "-F",
+        &format!("number={pr_number}"),
+        "--jq",
+        ".data.repository.pullRequest.reviewThreads.nodes[] | {author: .comments.nodes[0].author.login, isResolved}",
+    ])?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "gh command failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    let mut threads = Vec::new();
+    for line in output.stdout.split(|&b| b == b'\n') {
+        if !line.is_empty() {
+            threads.push(serde_json::from_slice(line)?);
+        }
+    }
+    Ok(threads)
+}
+
+/// `author`がレビューした他の人のPRについて、レビュースレッドを取得して設定
+///
+/// `on_progress`は1件処理するごとに呼ばれます（対象外のPRも含む）。
+pub fn attach_review_threads(prs: &mut [PullRequest], author: &str, on_progress: impl FnMut()) {
+    limits::for_each_concurrent(prs.iter_mut(), on_progress, |pr| {
+        let own_pr = pr.author.as_ref().is_some_and(|a| a.login == author);
+        if own_pr || pr.authored_reviews.is_empty() {
+            return;
+        }
+        if let Ok(threads) = fetch_review_threads(&pr.repository.name_with_owner, pr.number) {
+            pr.review_threads = threads;
+        }
+    });
+}
+
+/// PRのコミットを古い順に取得（最大100件）
+pub fn fetch_pr_commits(repo: &str, pr_number: u32) -> Result<Vec<Commit>> {
+    fetch_jsonl(
+        &format!("repos/{repo}/pulls/{pr_number}/commits?per_page=100"),
+        ".[] | {sha: .sha[0:7], message: .commit.message}",
+    )
+}
+
+/// 各PRのコミットを取得して設定
+///
+/// `on_progress`は1件取得するごとに呼ばれます。
+pub fn attach_pr_commits<'a>(
+    prs: impl Iterator<Item = &'a mut PullRequest> + Send,
+    on_progress: impl FnMut(),
+) {
+    limits::for_each_concurrent(prs, on_progress, |pr| {
+        if let Ok(commits) = fetch_pr_commits(&pr.repository.name_with_owner, pr.number) {
+            pr.commits = commits;
+        }
+    });
+}
+
+/// PRで変更したファイルを取得（最大100件）
+pub fn fetch_pr_files(repo: &str, pr_number: u32) -> Result<Vec<ChangedFile>> {
+    fetch_jsonl(
+        &format!("repos/{repo}/pulls/{pr_number}/files?per_page=100"),
+        ".[] | {path: .filename, additions: .additions, deletions: .deletions}",
+    )
+}
+
+/// 各PRで変更したファイルを取得して設定
+///
+/// `on_progress`は1件取得するごとに呼ばれます。
+pub fn attach_pr_files(prs: &mut [PullRequest], on_progress: impl FnMut()) {
+    limits::for_each_concurrent(prs.iter_mut(), on_progress, |pr| {
+        if let Ok(files) = fetch_pr_files(&pr.repository.name_with_owner, pr.number) {
+            pr.files = files;
+        }
+    });
+}
+
+/// マージ済みの各PRについて、マージコミットのチェック結果とデプロイを取得して設定
+///
+/// `on_progress`は1件取得するごとに呼ばれます。
+pub fn attach_ship_signals(prs: &mut [PullRequest], on_progress: impl FnMut()) {
+    let merged = prs.iter_mut().filter(|pr| pr.state == "merged");
+    limits::for_each_concurrent(merged, on_progress, |pr| {
+        let repo = pr.repository.name_with_owner.clone();
+        if let Ok(sha) = fetch_merge_commit(&repo, pr.number) {
+            pr.checks = fetch_check_runs(&repo, &sha).unwrap_or_default();
+            pr.deployments = fetch_deployments(&repo, &sha).unwrap_or_default();
+        }
+    });
+}
+
+fn fetch_merge_commit(repo: &str, pr_number: u32) -> Result<String> {
+    let output = gh(&[
+        "api",
+        &format!("repos/{repo}/pulls/{pr_number}"),
+        "--jq",
+        ".merge_commit_sha // empty",
+    ])?;
+
+    let sha = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() || sha.is_empty() {
+        anyhow::bail!(
+            "gh command failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(sha)
+}
+
+/// コミットのチェック結果を取得（最大100件）
+pub fn fetch_check_runs(repo: &str, sha: &str) -> Result<Vec<CheckRun>> {
+    fetch_jsonl(
+        &format!("repos/{repo}/commits/{sha}/check-runs?per_page=100"),
+        ".check_runs[] | {name: .name, conclusion: .conclusion}",
+    )
+}
+
+/// コミットのデプロイを取得し、それぞれの最新の状態を設定
+pub fn fetch_deployments(repo: &str, sha: &str) -> Result<Vec<Deployment>> {
+    let mut deployments: Vec<Deployment> = fetch_jsonl(
+        &format!("repos/{repo}/deployments?sha={sha}"),
+        ".[] | {id: .id, environment: .environment, state: null, createdAt: .created_at}",
+    )?;
+    for deployment in &mut deployments {
+        let statuses: Vec<String> = fetch_jsonl(
+            &format!(
+                "repos/{repo}/deployments/{}/statuses?per_page=1",
+                deployment.id
+            ),
+            ".[] | .state",
+        )?;
+        deployment.state = statuses.into_iter().next();
+    }
+    Ok(deployments)
+}
+
+/// PR本文の "closes #N" / "fixes owner/repo#N" / "resolves <Issue URL>" を抽出
+///
+/// リポジトリ名を省略した参照は`repo`のIssueとして扱います。
+pub fn parse_closing_references(body: &str, repo: &str) -> Vec<(String, u32)> {
+    static PATTERN: std::sync::LazyLock<regex::Regex> = std::sync::LazyLock::new(|| {
+        regex::Regex::new(
+            r"(?i)\b(?:close[sd]?|fix(?:e[sd])?|resolve[sd]?)\s*:?\s+(?:([\w.-]+/[\w.-]+)?#(\d+)|https://github\.com/([\w.-]+/[\w.-]+)/issues/(\d+))",
+        )
+        .expect("closing reference pattern should be valid")
+    });
+
+    let mut refs = Vec::new();
+    for caps in PATTERN.captures_iter(body) {
+        let (repository, number) = match (caps.get(2), caps.get(4)) {
+            (Some(number), _) => (caps.get(1).map_or(repo, |m| m.as_str()), number),
+            (None, Some(number)) => (caps.get(3).map_or(repo, |m| m.as_str()), number),
+            _ => continue,
+        };
+        if let Ok(number) = number.as_str().parse()
+            && !refs.contains(&(repository.to_string(), number))
+        {
+            refs.push((repository.to_string(), number));
+        }
+    }
+    refs
+}
+
+#[derive(Debug, Deserialize)]
+struct ClosingReferences {
+    #[serde(rename = "closingIssuesReferences", default)]
+    closing_issues_references: Vec<ClosingReference>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClosingReference {
+    number: u32,
+    url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct IssueDetail {
+    title: String,
+    state: String,
+    author: Option<String>,
+    #[serde(default)]
+    labels: Vec<String>,
+}
+
+/// 各PRが解決したIssueを本文の参照とGitHub上の紐付けから取得して設定
+///
+/// `author`はPRの作成者で、Issueを自分で起票したかの判定に使います。
+/// `on_progress`は1件取得するごとに呼ばれます。
+pub fn attach_closing_issues<'a>(
+    prs: impl Iterator<Item = &'a mut PullRequest> + Send,
+    author: &str,
+    on_progress: impl FnMut(),
+) {
+    limits::for_each_concurrent(prs, on_progress, |pr| {
+        let repo = pr.repository.name_with_owner.clone();
+        let mut refs = parse_closing_references(pr.body.as_deref().unwrap_or(""), &repo);
+
+        // UIから紐づけられたIssueも含めるため、GitHub側の参照も取得
+        if let Ok(output) = gh(&[
+            "pr",
+            "view",
+            &pr.number.to_string(),
+            "--repo",
+            &repo,
+            "--json",
+            "closingIssuesReferences",
+        ]) && output.status.success()
+            && let Ok(linked) = serde_json::from_slice::<ClosingReferences>(&output.stdout)
+        {
+            for reference in linked.closing_issues_references {
+                let repository = reference
+                    .url
+                    .trim_start_matches("https://github.com/")
+                    .split("/issues/")
+                    .next()
+                    .unwrap_or(&repo)
+                    .to_string();
+                if !refs.contains(&(repository.clone(), reference.number)) {
+                    refs.push((repository, reference.number));
+                }
+            }
+        }
+
+        pr.closing_issues = refs
+            .into_iter()
+            .map(|(repository, number)| {
+                let detail = fetch_issue_detail(&repository, number).ok();
+                LinkedIssue {
+                    url: format!("https://github.com/{repository}/issues/{number}"),
+                    filed_by_author: detail
+                        .as_ref()
+                        .and_then(|d| d.author.as_deref())
+                        .is_some_and(|a| a == author),
+                    title: detail.as_ref().map(|d| d.title.clone()),
+                    state: detail.as_ref().map(|d| d.state.clone()),
+                    labels: detail
+                        .as_ref()
+                        .map(|d| d.labels.clone())
+                        .unwrap_or_default(),
+                    author: detail.and_then(|d| d.author),
+                    repository,
+                    number,
+                }
+            })
+            .collect();
+    });
+}
+
+fn fetch_issue_detail(repo: &str, number: u32) -> Result<IssueDetail> {
+    let output = gh(&[
+        "api",
+        &format!("repos/{repo}/issues/{number}"),
+        "--jq",
+        "{title: .title, state: .state, author: .user.login, labels: [.labels[].name]}",
+    ])?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "gh command failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    Ok(serde_json::from_slice(&output.stdout)?)
+}
+
+// ghコマンドを実行し、実行内容・終了コード・所要時間をデバッグログに記録
+fn gh(args: &[&str]) -> std::io::Result<Output> {
+    let started = Instant::now();
+    let output = fixture::gh(args, || limits::output(Command::new("gh").args(args)));
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+    match &output {
+        Ok(output) => tracing::debug!(?args, status = output.status.code(), elapsed_ms, "gh"),
+        Err(error) => tracing::debug!(?args, %error, "ghを実行できません"),
+    }
+    output
+}
+
+// `gh api`の結果をjqで1行1オブジェクトに整形してパース
+fn fetch_jsonl<T: DeserializeOwned>(endpoint: &str, jq: &str) -> Result<Vec<T>> {
+    let output = gh(&["api", endpoint, "--jq", jq])?;
+
+    if !output.status.success() {
+        return Ok(Vec::new()); // エラーの場合は空のベクターを返す
+    }
+
+    // 各行をJSONとしてパース
+    let mut items = Vec::new();
+    for line in output.stdout.split(|&b| b == b'\n') {
+        if !line.is_empty()
+            && let Ok(item) = serde_json::from_slice::<T>(line)
+        {
+            items.push(item);
+        }
+    }
+
+    Ok(items)
+}
+
+/// Gistを作成してURLを返す（`public`がfalseの場合はシークレットGist）
+pub fn create_gist(
+    filename: &str,
+    description: &str,
+    content: &str,
+    public: bool,
+) -> Result<String> {
+    let mut command = Command::new("gh");
+    command.args([
+        "gist",
+        "create",
+        "--filename",
+        filename,
+        "--desc",
+        description,
+    ]);
+    if public {
+        command.arg("--public");
+    }
+    command.arg("-");
+    let output = output_with_stdin(&mut command, content)?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "gh gist create failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// `repo`に`title`のIssueを作成（同じタイトルのIssueがあれば本文を更新）してURLを返す
+pub fn post_issue(repo: &str, title: &str, body: &str) -> Result<String> {
+    #[derive(Deserialize)]
+    struct Existing {
+        number: u64,
+        title: String,
+        url: String,
+    }
+
+    let output = gh(&[
+        "issue",
+        "list",
+        "--repo",
+        repo,
+        "--state=all",
+        &format!("--search=in:title \"{title}\""),
+        "--json=number,title,url",
+    ])?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "gh issue list failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    let existing: Vec<Existing> = serde_json::from_slice(&output.stdout)?;
+
+    // 検索は部分一致のため、タイトルが完全に一致するものだけを更新する
+    if let Some(issue) = existing.into_iter().find(|issue| issue.title == title) {
+        let number = issue.number.to_string();
+        let mut command = Command::new("gh");
+        command.args(["issue", "edit", &number, "--repo", repo, "--body-file", "-"]);
+        let output = output_with_stdin(&mut command, body)?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "gh issue edit failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        return Ok(issue.url);
+    }
+
+    let mut command = Command::new("gh");
+    command.args([
+        "issue",
+        "create",
+        "--repo",
+        repo,
+        "--title",
+        title,
+        "--body-file",
+        "-",
+    ]);
+    let output = output_with_stdin(&mut command, body)?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "gh issue create failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+// 標準入力に`input`を渡してコマンドを実行
+fn output_with_stdin(command: &mut Command, input: &str) -> std::io::Result<Output> {
+    let mut child = command
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        use std::io::Write;
+        stdin.write_all(input.as_bytes())?;
+    }
+    limits::wait_with_output(child)
+}
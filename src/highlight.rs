@@ -0,0 +1,136 @@
+//! 重要度の高いPRを選び出すスコアリング
+//!
+//! プロンプトのトークン数を抑えるため、スコア上位のPRだけを本文・コメント付きで送り、
+//! それ以外はメタデータのみにする（`--highlights`）際に使います。
+//! 重みはチームごとに`--weights`のTOMLファイルで上書きできます。
+
+use crate::github::PullRequest;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// スコアリングの重み
+///
+/// TOMLで指定しなかった項目はデフォルト値になります（`labels`などの表は指定すると丸ごと置き換わります）。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ScoringWeights {
+    /// マージ済みPRへの加点
+    pub merged: f64,
+    /// コメント1件あたりの加点
+    pub comment: f64,
+    /// 受けたレビュー1件あたりの加点
+    pub review: f64,
+    /// 解決したIssue1件あたりの加点
+    pub closing_issue: f64,
+    /// descriptionの文字数が閾値以上の場合の加点（該当する最大の閾値を採用）
+    pub size_thresholds: Vec<(usize, f64)>,
+    /// ラベル名（部分一致・大文字小文字を区別しない）ごとの加点
+    pub labels: HashMap<String, f64>,
+    /// リポジトリ（`owner/name`）ごとのスコア倍率（未指定は1.0）
+    pub repositories: HashMap<String, f64>,
+}
+
+impl Default for ScoringWeights {
+    fn default() -> Self {
+        let labels = [
+            ("security", 4.0),
+            ("feature", 3.0),
+            ("enhancement", 3.0),
+            ("performance", 3.0),
+            ("breaking", 3.0),
+            ("bug", 2.0),
+            ("refactor", 1.5),
+            ("documentation", 1.0),
+        ]
+        .into_iter()
+        .map(|(name, weight)| (name.to_string(), weight))
+        .collect();
+
+        Self {
+            merged: 2.0,
+            comment: 0.5,
+            review: 0.5,
+            closing_issue: 1.5,
+            size_thresholds: vec![(300, 1.0), (1000, 2.0), (3000, 3.0)],
+            labels,
+            repositories: HashMap::new(),
+        }
+    }
+}
+
+impl ScoringWeights {
+    /// 重みファイル（TOML）を読み込む
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("重みファイルを読み込めません: {}", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("重みファイルの形式が不正です: {}", path.display()))
+    }
+
+    /// PRの重要度スコア
+    pub fn score(&self, pr: &PullRequest) -> f64 {
+        let mut score = 0.0;
+        if pr.state == "merged" {
+            score += self.merged;
+        }
+        score += pr.comments_count.max(pr.comments.len()) as f64 * self.comment;
+        score += pr.reviews.len() as f64 * self.review;
+        score += pr.closing_issues.len() as f64 * self.closing_issue;
+
+        let body_len = pr.body.as_deref().map_or(0, |b| b.chars().count());
+        score += self
+            .size_thresholds
+            .iter()
+            .filter(|(min_chars, _)| body_len >= *min_chars)
+            .map(|(_, weight)| *weight)
+            .fold(0.0, f64::max);
+
+        for label in &pr.labels {
+            let name = label.name.to_lowercase();
+            score += self
+                .labels
+                .iter()
+                .filter(|(key, _)| name.contains(&key.to_lowercase()))
+                .map(|(_, weight)| *weight)
+                .fold(0.0, f64::max);
+        }
+
+        score * self.repository_weight(&pr.repository.name_with_owner)
+    }
+
+    /// リポジトリ（`owner/name`）の重要度の倍率（未指定は1.0）
+    pub fn repository_weight(&self, repository: &str) -> f64 {
+        self.repositories.get(repository).copied().unwrap_or(1.0)
+    }
+
+    /// リポジトリの重要度の倍率で重み付けしたマージ済みPR数（倍率の指定がない場合は`None`）
+    pub fn weighted_merged_prs(&self, prs: &[PullRequest]) -> Option<f64> {
+        if self.repositories.is_empty() {
+            return None;
+        }
+        Some(
+            prs.iter()
+                .filter(|pr| pr.state == "merged")
+                .map(|pr| self.repository_weight(&pr.repository.name_with_owner))
+                .sum(),
+        )
+    }
+
+    /// スコア上位`n`件のPRのインデックス
+    ///
+    /// 同点の場合は新しいPRを優先します。
+    pub fn select_top(&self, prs: &[PullRequest], n: usize) -> HashSet<usize> {
+        let mut scored: Vec<(usize, f64)> = prs
+            .iter()
+            .enumerate()
+            .map(|(i, pr)| (i, self.score(pr)))
+            .collect();
+        scored.sort_by(|a, b| {
+            b.1.total_cmp(&a.1)
+                .then_with(|| prs[b.0].created_at.cmp(&prs[a.0].created_at))
+        });
+        scored.into_iter().take(n).map(|(i, _)| i).collect()
+    }
+}
@@ -0,0 +1,347 @@
+//! 生成したレポートの履歴（`easyhyoka history`）
+//!
+//! 生成のたびにサマリーと生成条件（コマンドライン引数・モデル・プロンプトのハッシュ）を
+//! `~/.local/share/easyhyoka/reports/`などに1件1ファイルで保存します。
+//! 同じ対象者・期間での生成し直しには版番号を振り、版同士の差分を比べられるようにします。
+//! `easyhyoka diff`向けに、箇条書きの実績単位で追加・削除を比べる処理もここに置きます。
+
+use crate::github::SearchQuery;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+// 値が秘密情報になるため、履歴には値を伏せて保存するコマンドライン引数
+const SECRET_FLAGS: &[&str] = &["--slack-webhook"];
+
+const REDACTED: &str = "***";
+
+/// 保存された1回分の生成結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    /// 履歴のID（生成日時、例: `20260401-093000`）
+    pub id: String,
+    /// 生成日時（RFC 3339）
+    pub created_at: String,
+    /// 同じ対象者・オーナー・期間での何回目の生成か
+    pub version: usize,
+    pub query: SearchQuery,
+    pub model: String,
+    /// LLMに送ったプロンプトのハッシュ（SHA-256の先頭16文字）
+    pub prompt_hash: String,
+    /// 生成時のコマンドライン引数（Webhook URLなどの秘密情報は伏せる）
+    pub arguments: Vec<String>,
+    pub summary: String,
+}
+
+impl Entry {
+    /// 生成日時をIDにした履歴を作る（版番号は保存時に決まる）
+    pub fn new(
+        query: &SearchQuery,
+        model: &str,
+        prompt: &str,
+        arguments: Vec<String>,
+        summary: &str,
+    ) -> Self {
+        let now = chrono::Local::now();
+        Self {
+            id: now.format("%Y%m%d-%H%M%S").to_string(),
+            created_at: now.to_rfc3339(),
+            version: 0,
+            query: query.clone(),
+            model: model.to_string(),
+            prompt_hash: prompt_hash(prompt),
+            arguments: redact_arguments(arguments),
+            summary: summary.to_string(),
+        }
+    }
+
+    /// 同じ対象の生成か（対象者・オーナー・期間・関わり方が同じ）
+    fn same_target(&self, other: &Entry) -> bool {
+        let (a, b) = (&self.query, &other.query);
+        a.author == b.author
+            && a.owner == b.owner
+            && a.since == b.since
+            && a.until == b.until
+            && a.role == b.role
+    }
+}
+
+/// 履歴の保存先
+#[derive(Debug, Clone)]
+pub struct History {
+    dir: PathBuf,
+}
+
+impl History {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// デフォルトの保存先（`~/.local/share/easyhyoka/reports`など）
+    pub fn default_dir() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("easyhyoka").join("reports"))
+    }
+
+    /// 版番号を振って保存し、保存した履歴を返す
+    pub fn save(&self, mut entry: Entry) -> Result<Entry> {
+        let entries = self.list()?;
+        entry.version = entries.iter().filter(|e| e.same_target(&entry)).count() + 1;
+        // 同じ秒に複数回生成した場合はIDに連番を付ける
+        let base = entry.id.clone();
+        let mut suffix = 1;
+        while entries.iter().any(|e| e.id == entry.id) {
+            suffix += 1;
+            entry.id = format!("{base}-{suffix}");
+        }
+
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("履歴の保存先を作成できません: {}", self.dir.display()))?;
+        let path = self.dir.join(format!("{}.json", entry.id));
+        std::fs::write(&path, serde_json::to_string_pretty(&entry)?)
+            .with_context(|| format!("履歴を保存できません: {}", path.display()))?;
+        Ok(entry)
+    }
+
+    /// 保存済みの履歴（古い順）
+    pub fn list(&self) -> Result<Vec<Entry>> {
+        let read_dir = match std::fs::read_dir(&self.dir) {
+            Ok(read_dir) => read_dir,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(e).with_context(|| {
+                    format!("履歴の保存先を読み込めません: {}", self.dir.display())
+                });
+            }
+        };
+        let mut entries = Vec::new();
+        for path in read_dir.filter_map(|e| e.ok().map(|e| e.path())) {
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let json = std::fs::read_to_string(&path)
+                .with_context(|| format!("履歴を読み込めません: {}", path.display()))?;
+            let entry: Entry = serde_json::from_str(&json)
+                .with_context(|| format!("履歴の形式が不正です: {}", path.display()))?;
+            entries.push(entry);
+        }
+        entries.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+        Ok(entries)
+    }
+
+    /// IDの履歴を読み込む
+    pub fn get(&self, id: &str) -> Result<Entry> {
+        self.list()?
+            .into_iter()
+            .find(|e| e.id == id)
+            .with_context(|| {
+                format!("履歴が見つかりません: {id}（easyhyoka history listで確認できます）")
+            })
+    }
+
+    /// `entry`と同じ対象の最新の履歴（`entry`自身を含む）
+    pub fn latest_of(&self, entry: &Entry) -> Result<Entry> {
+        Ok(self
+            .list()?
+            .into_iter()
+            .rfind(|e| e.same_target(entry))
+            .unwrap_or_else(|| entry.clone()))
+    }
+}
+
+/// プロンプトのハッシュ（SHA-256の先頭16文字）
+pub fn prompt_hash(prompt: &str) -> String {
+    Sha256::digest(prompt.as_bytes())
+        .iter()
+        .take(8)
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+// 秘密情報を値に持つ引数の値を伏せる（`--flag value`と`--flag=value`の両方）
+fn redact_arguments(arguments: Vec<String>) -> Vec<String> {
+    let mut redact_next = false;
+    arguments
+        .into_iter()
+        .map(|argument| {
+            if std::mem::take(&mut redact_next) {
+                return REDACTED.to_string();
+            }
+            if SECRET_FLAGS.contains(&argument.as_str()) {
+                redact_next = true;
+                return argument;
+            }
+            match argument.split_once('=') {
+                Some((flag, _)) if SECRET_FLAGS.contains(&flag) => format!("{flag}={REDACTED}"),
+                _ => argument,
+            }
+        })
+        .collect()
+}
+
+/// 2つのサマリーの行単位の差分（`-`は削除、`+`は追加、空白は共通の行）
+pub fn diff(old: &str, new: &str) -> String {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // 最長共通部分列の長さ（後ろから）
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut output = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            output.push_str(&format!("  {}\n", old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            output.push_str(&format!("- {}\n", old[i]));
+            i += 1;
+        } else {
+            output.push_str(&format!("+ {}\n", new[j]));
+            j += 1;
+        }
+    }
+    output
+}
+
+/// 2つのサマリーの実績（箇条書きの項目）単位の違い（`easyhyoka diff`）
+#[derive(Debug, Clone, Default)]
+pub struct AchievementDiff {
+    /// 新しい方にだけある実績
+    pub added: Vec<String>,
+    /// 古い方にだけある実績
+    pub removed: Vec<String>,
+    /// 同じPR/IssueのURLを引用したまま書き換えられた実績（古い方, 新しい方）
+    pub reworded: Vec<(String, String)>,
+}
+
+impl AchievementDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.reworded.is_empty()
+    }
+}
+
+static LIST_MARKER: LazyLock<regex::Regex> = LazyLock::new(|| {
+    regex::Regex::new(r"^\s*(?:[-*+]|\d+[.)])\s+(?:\[[ xX]\]\s+)?")
+        .expect("list marker pattern should be valid")
+});
+static URL: LazyLock<regex::Regex> = LazyLock::new(|| {
+    regex::Regex::new(r"https?://[^\s)>\]]+").expect("url pattern should be valid")
+});
+
+/// 箇条書きの項目を実績として比べ、追加・削除・書き換えられたものを返す
+pub fn compare_achievements(old: &str, new: &str) -> AchievementDiff {
+    let old = achievements(old);
+    let new = achievements(new);
+    let mut diff = AchievementDiff::default();
+    let mut matched_new = vec![false; new.len()];
+
+    for item in &old {
+        if let Some(i) = new.iter().position(|n| n == item) {
+            matched_new[i] = true;
+            continue;
+        }
+        // 文言が違っても同じURLを引用していれば書き換えとみなす
+        let urls = urls(item);
+        let reworded = new.iter().enumerate().position(|(i, n)| {
+            !matched_new[i] && !urls.is_empty() && urls.iter().any(|u| n.contains(u.as_str()))
+        });
+        match reworded {
+            Some(i) => {
+                matched_new[i] = true;
+                diff.reworded.push((item.clone(), new[i].clone()));
+            }
+            None => diff.removed.push(item.clone()),
+        }
+    }
+    diff.added = new
+        .into_iter()
+        .zip(matched_new)
+        .filter(|(_, matched)| !matched)
+        .map(|(item, _)| item)
+        .collect();
+    diff
+}
+
+// 箇条書きの項目（記号を除き、前後の空白を詰めたもの）
+fn achievements(summary: &str) -> Vec<String> {
+    summary
+        .lines()
+        .filter_map(|line| LIST_MARKER.find(line).map(|m| line[m.end()..].trim()))
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn urls(text: &str) -> Vec<String> {
+    URL.find_iter(text)
+        .map(|m| m.as_str().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn diff_marks_removed_and_added_lines() {
+        assert_eq!(diff("a\nb\nc", "a\nc\nd"), "  a\n- b\n  c\n+ d\n");
+    }
+
+    #[test]
+    fn compare_achievements_detects_added_removed_and_reworded_items() {
+        let old = "## 成果\n- パーサーを追加（https://github.com/acme/x/pull/1）\n- READMEを更新\n* CIを高速化";
+        let new = "## 成果\n1. 検索結果のパーサーを追加（https://github.com/acme/x/pull/1）\n- CIを高速化\n- 再試行を実装";
+        let diff = compare_achievements(old, new);
+        assert_eq!(diff.added, strings(&["再試行を実装"]));
+        assert_eq!(diff.removed, strings(&["READMEを更新"]));
+        assert_eq!(
+            diff.reworded,
+            vec![(
+                "パーサーを追加（https://github.com/acme/x/pull/1）".to_string(),
+                "検索結果のパーサーを追加（https://github.com/acme/x/pull/1）".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn compare_achievements_is_empty_for_same_items() {
+        assert!(compare_achievements("- a\n- b", "* a\n+ b").is_empty());
+    }
+
+    #[test]
+    fn redact_arguments_hides_secret_values() {
+        let arguments = strings(&[
+            "--slack-webhook",
+            "https://hooks.slack.com/services/x",
+            "--slack-webhook=https://hooks.slack.com/services/y",
+            "--owner",
+            "acme",
+        ]);
+        assert_eq!(
+            redact_arguments(arguments),
+            strings(&[
+                "--slack-webhook",
+                "***",
+                "--slack-webhook=***",
+                "--owner",
+                "acme"
+            ])
+        );
+    }
+}
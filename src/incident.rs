@@ -0,0 +1,128 @@
+//! 障害対応・オンコールの作業の検出
+//!
+//! ラベル名とタイトルの正規表現で障害対応のPR/Issueを判定します。
+//! 判定ルールは設定ファイルの`[incident]`で変更できます。
+
+use crate::github::{Issue, Label, PullRequest};
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// 障害対応とみなすラベル・タイトルのルール（設定ファイルの`[incident]`）
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct IncidentRules {
+    /// ラベル名（大文字小文字を区別しない部分一致）
+    pub labels: Vec<String>,
+    /// タイトルの正規表現
+    pub title_patterns: Vec<String>,
+}
+
+impl Default for IncidentRules {
+    fn default() -> Self {
+        Self {
+            labels: [
+                "incident",
+                "hotfix",
+                "outage",
+                "postmortem",
+                "on-call",
+                "sev1",
+                "sev2",
+            ]
+            .map(String::from)
+            .to_vec(),
+            title_patterns: [
+                r"(?i)\b(hotfix|incident|outage|postmortem|rollback)\b",
+                r"障害|緊急",
+            ]
+            .map(String::from)
+            .to_vec(),
+        }
+    }
+}
+
+impl IncidentRules {
+    /// 正規表現をコンパイルして判定器を作る
+    pub fn matcher(&self) -> Result<IncidentMatcher> {
+        let title_patterns = self
+            .title_patterns
+            .iter()
+            .map(|pattern| {
+                Regex::new(pattern)
+                    .with_context(|| format!("障害対応のタイトルの正規表現が不正です: {pattern}"))
+            })
+            .collect::<Result<_>>()?;
+        Ok(IncidentMatcher {
+            labels: self.labels.iter().map(|l| l.to_lowercase()).collect(),
+            title_patterns,
+        })
+    }
+}
+
+/// 障害対応のPR/Issueの判定器
+#[derive(Debug, Clone)]
+pub struct IncidentMatcher {
+    labels: Vec<String>,
+    title_patterns: Vec<Regex>,
+}
+
+impl Default for IncidentMatcher {
+    fn default() -> Self {
+        IncidentRules::default()
+            .matcher()
+            .expect("default incident rules should be valid")
+    }
+}
+
+impl IncidentMatcher {
+    pub fn is_incident(&self, title: &str, labels: &[Label]) -> bool {
+        labels.iter().any(|label| {
+            let name = label.name.to_lowercase();
+            self.labels.iter().any(|l| name.contains(l.as_str()))
+        }) || self.title_patterns.iter().any(|p| p.is_match(title))
+    }
+}
+
+/// 障害対応の作業の集計
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct IncidentStats {
+    pub prs: usize,
+    pub merged_prs: usize,
+    pub issues: usize,
+    /// 障害対応のあったリポジトリ
+    pub repositories: BTreeSet<String>,
+}
+
+impl IncidentStats {
+    pub fn compute(prs: &[PullRequest], issues: &[Issue], matcher: &IncidentMatcher) -> Self {
+        let mut stats = Self::default();
+        for pr in prs
+            .iter()
+            .filter(|pr| matcher.is_incident(&pr.title, &pr.labels))
+        {
+            stats.prs += 1;
+            if pr.state == "merged" {
+                stats.merged_prs += 1;
+            }
+            stats
+                .repositories
+                .insert(pr.repository.name_with_owner.clone());
+        }
+        for issue in issues
+            .iter()
+            .filter(|issue| matcher.is_incident(&issue.title, &issue.labels))
+        {
+            stats.issues += 1;
+            stats
+                .repositories
+                .insert(issue.repository.name_with_owner.clone());
+        }
+        stats
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prs == 0 && self.issues == 0
+    }
+}
@@ -0,0 +1,144 @@
+//! 実績の記録ファイルへの追記（`easyhyoka log`）
+//!
+//! 定期的に実行し、前回の記録以降の活動だけをまとめて、日付ごとの項目として記録ファイル
+//! （Markdown）の末尾に追記します。各項目にはどの日までを記録したかを示すHTMLコメントを
+//! 付けておき、次回はその翌日から取得します。評価の時期には記録ファイルを読み返すだけで
+//! 期間中の実績を振り返れます。
+//!
+//! `easyhyoka compile-journal`は、評価期間内の項目（後から書き足したメモを含む）を
+//! その時点の記録としてプロンプトに含め、最終的な評価文書を作成します。
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+
+/// 記録ファイルを新しく作るときの見出し
+pub const TITLE: &str = "# 実績の記録";
+
+// 項目ごとの記録した最後の日（`<!-- easyhyoka-log until=2025-01-07 -->`）
+const MARKER_PREFIX: &str = "<!-- easyhyoka-log until=";
+const MARKER_SUFFIX: &str = " -->";
+
+/// 記録ファイルの項目1件
+#[derive(Debug, Clone, Serialize)]
+pub struct Entry {
+    /// 項目の見出し（記録した期間または期間の呼び名）
+    pub heading: String,
+    /// 記録した最後の日
+    pub until: NaiveDate,
+    /// まとめと、後から書き足したメモ
+    pub body: String,
+}
+
+impl Entry {
+    /// 記録した最後の日が`since`〜`until`（`YYYY-MM-DD`）に含まれるか
+    pub fn in_period(&self, since: &str, until: &str) -> bool {
+        let date = self.until.to_string();
+        since <= date.as_str() && date.as_str() <= until
+    }
+}
+
+/// 記録ファイルの項目（ファイルがなければ空）
+///
+/// 記録した日を示すHTMLコメントの直前の`##`の見出しから、次の`##`の見出しまでを1件とします。
+/// HTMLコメントのない見出しの下の文章は、どの項目にも含めません。
+pub fn entries(path: &Path) -> Result<Vec<Entry>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("記録ファイルを読み込めません: {}", path.display()))?;
+
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut heading: Option<String> = None;
+    let mut in_entry = false;
+    let mut in_code = false;
+    for line in content.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+        }
+        if !in_code && let Some(title) = line.strip_prefix("## ") {
+            heading = Some(title.trim().to_string());
+            in_entry = false;
+            continue;
+        }
+        if !in_code && let Some(until) = marker(line) {
+            entries.push(Entry {
+                heading: heading.take().unwrap_or_else(|| until.to_string()),
+                until,
+                body: String::new(),
+            });
+            in_entry = true;
+            continue;
+        }
+        if in_entry && let Some(entry) = entries.last_mut() {
+            entry.body.push_str(line);
+            entry.body.push('\n');
+        }
+    }
+    for entry in &mut entries {
+        entry.body = entry.body.trim().to_string();
+    }
+    Ok(entries)
+}
+
+/// 記録ファイルの最後の項目が記録した最後の日（ファイルや項目がなければ`None`）
+pub fn last_until(path: &Path) -> Result<Option<NaiveDate>> {
+    Ok(entries(path)?.iter().map(|entry| entry.until).max())
+}
+
+// 記録した日を示すHTMLコメントの日付
+fn marker(line: &str) -> Option<NaiveDate> {
+    let date = line
+        .trim()
+        .strip_prefix(MARKER_PREFIX)?
+        .strip_suffix(MARKER_SUFFIX)?;
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+}
+
+/// 記録ファイルに追記する項目（見出し・記録した最後の日・まとめ）
+///
+/// まとめの見出しは項目の見出し（`##`）の下に収まるよう2段階下げます。
+pub fn entry(heading: &str, until: NaiveDate, summary: &str) -> String {
+    format!(
+        "## {heading}\n\n{MARKER_PREFIX}{until}{MARKER_SUFFIX}\n\n{}\n",
+        demote_headings(summary.trim())
+    )
+}
+
+// Markdownの見出し（コードブロックの外）を2段階下げる
+fn demote_headings(markdown: &str) -> String {
+    let mut in_code = false;
+    let mut lines: Vec<String> = Vec::new();
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+        }
+        if !in_code && line.starts_with('#') {
+            lines.push(format!("##{line}"));
+        } else {
+            lines.push(line.to_string());
+        }
+    }
+    lines.join("\n")
+}
+
+/// `entry`を記録ファイルの末尾に追記する（ファイルがなければ[`TITLE`]を付けて作る）
+pub fn append(path: &Path, entry: &str) -> Result<()> {
+    let is_new = !path.exists() || std::fs::metadata(path)?.len() == 0;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("記録ファイルを開けません: {}", path.display()))?;
+    let content = if is_new {
+        format!("{TITLE}\n\n{entry}")
+    } else {
+        format!("\n{entry}")
+    };
+    file.write_all(content.as_bytes())
+        .with_context(|| format!("記録ファイルに書き込めません: {}", path.display()))
+}
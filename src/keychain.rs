@@ -0,0 +1,50 @@
+//! OSのキーチェーンに保存したAPIキー（`easyhyoka auth`）
+//!
+//! macOSのキーチェーン、WindowsのCredential Manager、LinuxのSecret Serviceに
+//! サービス名`easyhyoka`、環境変数名をアカウント名として保存します。APIキーを読むときは
+//! キーチェーンを先に見て、なければ環境変数（`.env`を含む）を使います。
+
+use anyhow::{Context, Result};
+
+const SERVICE: &str = "easyhyoka";
+
+fn entry(name: &str) -> Result<keyring::Entry> {
+    keyring::Entry::new(SERVICE, name).context("キーチェーンを利用できません")
+}
+
+/// `name`（`OPENAI_API_KEY`など）の値を保存
+pub fn set(name: &str, value: &str) -> Result<()> {
+    entry(name)?
+        .set_password(value)
+        .with_context(|| format!("{name}をキーチェーンに保存できません"))
+}
+
+/// 保存した値（保存されていない場合は`None`）
+pub fn get(name: &str) -> Result<Option<String>> {
+    match entry(name)?.get_password() {
+        Ok(value) => Ok(Some(value)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("{name}をキーチェーンから読み込めません")),
+    }
+}
+
+/// 保存した値を削除（削除した場合は`true`）
+pub fn delete(name: &str) -> Result<bool> {
+    match entry(name)?.delete_credential() {
+        Ok(()) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(e) => Err(e).with_context(|| format!("{name}をキーチェーンから削除できません")),
+    }
+}
+
+/// キーチェーン、環境変数の順に`name`の値を探す
+///
+/// キーチェーンを使えない環境（Secret Serviceのないサーバーなど）では環境変数だけを見ます。
+pub fn api_key(name: &str) -> Option<String> {
+    match get(name) {
+        Ok(Some(value)) => return Some(value),
+        Ok(None) => {}
+        Err(e) => tracing::debug!(error = %format!("{e:#}"), "keychain unavailable"),
+    }
+    std::env::var(name).ok()
+}
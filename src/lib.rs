@@ -0,0 +1,53 @@
+//! GitHubのPull Request/Issueを取得し、OpenAIで評価サマリーを生成するライブラリ
+//!
+//! `easyhyoka` バイナリと同じ処理を他のツールから直接呼び出せるよう、
+//! 取得（[`github`]）・集計（[`stats`]）・プロンプト構築（[`report`]）・要約（[`llm`]）を
+//! モジュールとして公開しています。生成結果の配信先（[`slack`]など）も同様に利用できます。
+
+#[cfg(feature = "anthropic")]
+pub mod anthropic;
+pub mod areas;
+pub mod availability;
+pub mod batch;
+pub mod budget;
+pub mod cache;
+pub mod checkpoint;
+pub mod clipboard;
+pub mod cluster;
+pub mod collab;
+pub mod condense;
+pub mod config;
+pub mod confluence;
+pub mod demo;
+pub mod email;
+pub mod error;
+pub mod evidence;
+pub mod export;
+pub mod fiscal;
+pub mod fixture;
+pub mod gaps;
+pub mod gdocs;
+pub mod github;
+pub mod highlight;
+pub mod history;
+pub mod incident;
+pub mod journal;
+pub mod keychain;
+pub mod limits;
+pub mod llm;
+pub mod network;
+pub mod quotes;
+pub mod render;
+pub mod report;
+pub mod revert;
+pub mod schedule;
+pub mod slack;
+pub mod snapshot;
+pub mod source;
+pub mod stats;
+pub mod strip;
+pub mod techstack;
+pub mod template;
+pub mod topics;
+pub mod transcript;
+pub mod unshipped;
@@ -0,0 +1,154 @@
+//! 外部呼び出し（`gh`のサブプロセス・HTTPリクエスト）の並列数とタイムアウト
+//!
+//! 値はプロセス全体で共有し、起動時に一度だけ設定します（`--concurrency`・`--timeout`）。
+//! 設定されるまでは並列実行せず、タイムアウトもしません（CLIの既定は`--concurrency 4`・`--timeout 300`）。
+
+use std::io::{self, Read};
+use std::process::{Child, Command, Output, Stdio};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError, mpsc};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+static CONCURRENCY: AtomicUsize = AtomicUsize::new(1);
+
+// 0はタイムアウトなし
+static TIMEOUT_SECS: AtomicU64 = AtomicU64::new(0);
+
+// 終了を確認する間隔
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// PRごとの追加取得などで同時に実行する数（1未満は1として扱う）
+pub fn set_concurrency(concurrency: usize) {
+    CONCURRENCY.store(concurrency.max(1), Ordering::Relaxed);
+}
+
+pub fn concurrency() -> usize {
+    CONCURRENCY.load(Ordering::Relaxed)
+}
+
+/// 1回の呼び出しのタイムアウト（`None`でタイムアウトなし）
+pub fn set_timeout(timeout: Option<Duration>) {
+    TIMEOUT_SECS.store(timeout.map_or(0, |t| t.as_secs()), Ordering::Relaxed);
+}
+
+pub fn timeout() -> Option<Duration> {
+    match TIMEOUT_SECS.load(Ordering::Relaxed) {
+        0 => None,
+        secs => Some(Duration::from_secs(secs)),
+    }
+}
+
+/// タイムアウトとプロキシ・TLSの設定（[`crate::network`]）を適用したHTTPクライアント
+pub fn http_client() -> reqwest::Client {
+    let builder = reqwest::Client::builder();
+    build(match timeout() {
+        Some(timeout) => builder.timeout(timeout),
+        None => builder,
+    })
+}
+
+/// LLMの呼び出し用のHTTPクライアント
+///
+/// 長い生成やストリーミングの応答を途中で打ち切らないよう、リクエスト全体ではなく
+/// 接続と、応答の受信が途切れている時間にだけタイムアウトを適用します。
+pub fn llm_http_client() -> reqwest::Client {
+    let builder = reqwest::Client::builder();
+    build(match timeout() {
+        Some(timeout) => builder.connect_timeout(timeout).read_timeout(timeout),
+        None => builder,
+    })
+}
+
+fn build(builder: reqwest::ClientBuilder) -> reqwest::Client {
+    let builder = crate::network::apply(builder);
+    // 設定は起動時に`network::configure`で検証済み。それでも作れない場合は理由を残して既定のクライアントで続ける
+    builder.build().unwrap_or_else(|e| {
+        tracing::warn!(error = %e, "HTTPクライアントを作成できないため、プロキシ・TLSの設定なしで接続します");
+        reqwest::Client::new()
+    })
+}
+
+/// コマンドを実行して出力を返す（タイムアウトした場合はプロセスを終了してエラー）
+pub fn output(command: &mut Command) -> io::Result<Output> {
+    let child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    wait_with_output(child)
+}
+
+/// 起動済みのプロセスの終了を待って出力を返す（標準出力・標準エラー出力はパイプにしておく）
+pub fn wait_with_output(mut child: Child) -> io::Result<Output> {
+    let Some(timeout) = timeout() else {
+        return child.wait_with_output();
+    };
+
+    // 出力がパイプのバッファを超えても止まらないよう、待つ間も読み続ける
+    let stdout = child.stdout.take().map(read_to_end);
+    let stderr = child.stderr.take().map(read_to_end);
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("{}秒以内に終了しませんでした", timeout.as_secs()),
+            ));
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    };
+
+    let join = |handle: Option<JoinHandle<Vec<u8>>>| {
+        handle.and_then(|h| h.join().ok()).unwrap_or_default()
+    };
+    Ok(Output {
+        status,
+        stdout: join(stdout),
+        stderr: join(stderr),
+    })
+}
+
+fn read_to_end(mut reader: impl Read + Send + 'static) -> JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut buffer = Vec::new();
+        let _ = reader.read_to_end(&mut buffer);
+        buffer
+    })
+}
+
+/// 各要素に`f`を最大[`concurrency`]件ずつ並列に適用
+///
+/// `on_progress`は1件終わるごとに、呼び出し元のスレッドで呼ばれます。
+pub fn for_each_concurrent<'a, T: Send + 'a>(
+    items: impl Iterator<Item = &'a mut T> + Send,
+    mut on_progress: impl FnMut(),
+    f: impl Fn(&mut T) + Sync,
+) {
+    let queue = Mutex::new(items);
+    let (done, finished) = mpsc::channel();
+    std::thread::scope(|scope| {
+        for _ in 0..concurrency() {
+            let (queue, f, done) = (&queue, &f, done.clone());
+            scope.spawn(move || {
+                loop {
+                    let next = queue.lock().unwrap_or_else(PoisonError::into_inner).next();
+                    let Some(item) = next else {
+                        break;
+                    };
+                    f(item);
+                    let _ = done.send(());
+                }
+            });
+        }
+        drop(done);
+        for () in finished {
+            on_progress();
+        }
+    });
+}
@@ -0,0 +1,875 @@
+//! LLMによるサマリー生成
+//!
+//! プロバイダごとのクライアントは[`LlmClient`]を実装します。OpenAI（[`OpenAiClient`]）が
+//! デフォルトで、他のプロバイダはcargoのfeatureで有効化します（`anthropic`）。
+
+use crate::cache::ResponseCache;
+use crate::demo::DemoClient;
+use crate::evidence::{self, Evidence};
+use crate::fixture;
+use crate::github::{Issue, PullRequest, SearchQuery};
+use crate::limits;
+use crate::report::{self, PromptOptions, ResumeLanguage};
+use crate::transcript::{Call, Transcript};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+pub const DEFAULT_MODEL: &str = "gpt-4.1-mini-2025-04-14";
+
+/// クラスタのラベル付けなど、軽い処理に使う安価なモデル
+pub const LIGHT_MODEL: &str = "gpt-4.1-nano-2025-04-14";
+
+pub const EMBEDDING_MODEL: &str = "text-embedding-3-small";
+
+const CHAT_COMPLETIONS_URL: &str = "https://api.openai.com/v1/chat/completions";
+
+const EMBEDDINGS_URL: &str = "https://api.openai.com/v1/embeddings";
+
+// 1リクエストあたりの埋め込み対象の件数
+const EMBEDDING_BATCH_SIZE: usize = 100;
+
+#[derive(Debug, Serialize)]
+struct OpenAIRequest {
+    model: String,
+    messages: Vec<Message>,
+    // 推論モデルは温度を受け付けない
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reasoning_effort: Option<ReasoningEffort>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_completion_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
+}
+
+/// 会話のメッセージ（複数ターンのやり取りに使う）
+#[derive(Debug, Clone, Serialize)]
+pub struct Message {
+    pub role: String,
+    pub content: String,
+}
+
+impl Message {
+    pub fn system(content: impl Into<String>) -> Self {
+        Self::new("system", content)
+    }
+
+    pub fn user(content: impl Into<String>) -> Self {
+        Self::new("user", content)
+    }
+
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self::new("assistant", content)
+    }
+
+    fn new(role: &str, content: impl Into<String>) -> Self {
+        Self {
+            role: role.to_string(),
+            content: content.into(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIResponse {
+    choices: Vec<Choice>,
+    #[serde(default)]
+    usage: Option<Usage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Usage {
+    prompt_tokens: u64,
+    completion_tokens: u64,
+    #[serde(default)]
+    completion_tokens_details: Option<CompletionTokensDetails>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CompletionTokensDetails {
+    #[serde(default)]
+    reasoning_tokens: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Choice {
+    message: MessageResponse,
+    #[serde(default)]
+    finish_reason: Option<String>,
+}
+
+// 推論モデルは、推論で出力トークンを使い切ると本文が空（null）になる
+#[derive(Debug, Deserialize)]
+struct MessageResponse {
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    refusal: Option<String>,
+}
+
+// ストリーミング時に届く応答の断片
+#[derive(Debug, Deserialize)]
+struct StreamChunk {
+    choices: Vec<StreamChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamChoice {
+    delta: Delta,
+}
+
+#[derive(Debug, Deserialize)]
+struct Delta {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+/// チャット形式のLLMのクライアント
+pub trait LlmClient: Sync {
+    /// 使用するモデル名
+    fn model(&self) -> &str;
+
+    /// 会話履歴全体を送信し、次の応答本文を返す
+    fn complete(&self, messages: &[Message]) -> impl Future<Output = Result<String>> + Send;
+
+    /// 応答の断片が届くたびに`on_token`を呼び、最後に応答本文全体を返す
+    ///
+    /// ストリーミングに対応しないプロバイダでは、応答全体を1度に渡します。
+    fn stream(
+        &self,
+        messages: &[Message],
+        mut on_token: impl FnMut(&str) + Send,
+    ) -> impl Future<Output = Result<String>> + Send {
+        async move {
+            let content = self.complete(messages).await?;
+            on_token(&content);
+            Ok(content)
+        }
+    }
+
+    /// システムプロンプトとユーザープロンプトを送信し、応答本文を返す
+    fn chat(
+        &self,
+        system_prompt: &str,
+        prompt: &str,
+    ) -> impl Future<Output = Result<String>> + Send {
+        let messages = [Message::system(system_prompt), Message::user(prompt)];
+        async move { self.complete(&messages).await }
+    }
+
+    /// テキストのトークン数（プロバイダのトークナイザーを使わない場合は[`estimate_tokens`]の概算）
+    fn count_tokens(&self, text: &str) -> usize {
+        estimate_tokens(text)
+    }
+}
+
+/// トークン数の概算（ASCIIは4文字で1トークン、日本語などそれ以外は1文字1トークン）
+pub fn estimate_tokens(text: &str) -> usize {
+    let ascii = text.bytes().filter(u8::is_ascii).count();
+    let others = text.chars().filter(|c| !c.is_ascii()).count();
+    ascii.div_ceil(4) + others
+}
+
+/// 応答の温度の既定値
+pub const DEFAULT_TEMPERATURE: f32 = 0.7;
+
+/// 推論モデルの推論の深さ（`reasoning_effort`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReasoningEffort {
+    Low,
+    Medium,
+    High,
+}
+
+/// OpenAIの推論モデル（o1・o3・o4-mini・gpt-5など）か
+///
+/// 推論モデルには温度を送らず、システムプロンプトは`developer`ロールで送ります。
+pub fn is_reasoning_model(model: &str) -> bool {
+    let model = model.strip_prefix("openai/").unwrap_or(model);
+    ["o1", "o3", "o4", "gpt-5"]
+        .iter()
+        .any(|prefix| model == *prefix || model.starts_with(&format!("{prefix}-")))
+}
+
+/// キャッシュのキーに使うモデル名（温度・最大トークン数を変えた場合は区別する）
+pub(crate) fn cache_model(model: &str, temperature: f32, max_output_tokens: Option<u32>) -> String {
+    if temperature == DEFAULT_TEMPERATURE && max_output_tokens.is_none() {
+        return model.to_string();
+    }
+    format!("{model}?temperature={temperature}&max_output_tokens={max_output_tokens:?}")
+}
+
+/// OpenAI Chat Completions APIのクライアント
+#[derive(Debug, Clone)]
+pub struct OpenAiClient {
+    http: reqwest::Client,
+    api_key: String,
+    model: String,
+    temperature: f32,
+    max_output_tokens: Option<u32>,
+    reasoning_effort: Option<ReasoningEffort>,
+    cache: Option<ResponseCache>,
+    transcript: Option<Transcript>,
+}
+
+impl OpenAiClient {
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            http: limits::llm_http_client(),
+            api_key: api_key.into(),
+            model: DEFAULT_MODEL.to_string(),
+            temperature: DEFAULT_TEMPERATURE,
+            max_output_tokens: None,
+            reasoning_effort: None,
+            cache: None,
+            transcript: None,
+        }
+    }
+
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = model.into();
+        self
+    }
+
+    pub fn with_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = temperature;
+        self
+    }
+
+    /// 応答の最大トークン数（未指定の場合はモデルの上限まで）
+    pub fn with_max_output_tokens(mut self, max_output_tokens: u32) -> Self {
+        self.max_output_tokens = Some(max_output_tokens);
+        self
+    }
+
+    /// 推論モデルの推論の深さ（推論モデル以外では送らない）
+    pub fn with_reasoning_effort(mut self, effort: ReasoningEffort) -> Self {
+        self.reasoning_effort = Some(effort);
+        self
+    }
+
+    fn is_reasoning(&self) -> bool {
+        is_reasoning_model(&self.model)
+    }
+
+    fn cache_key(&self, messages: &[Message]) -> String {
+        let mut model = cache_model(&self.model, self.temperature, self.max_output_tokens);
+        if let Some(effort) = self.reasoning_effort.filter(|_| self.is_reasoning()) {
+            model.push_str(&format!("#reasoning_effort={effort:?}"));
+        }
+        ResponseCache::key(&model, messages)
+    }
+
+    /// 応答をディスクにキャッシュし、同じモデル・メッセージへの再送信を省く
+    pub fn with_cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// 呼び出しごとのプロンプト・リクエスト・応答を記録する
+    pub fn with_transcript(mut self, transcript: Transcript) -> Self {
+        self.transcript = Some(transcript);
+        self
+    }
+
+    // キャッシュ済みの応答（キャッシュを設定していない場合は常に`None`）
+    fn cached(&self, messages: &[Message]) -> Option<String> {
+        let key = self.cache_key(messages);
+        let cached = self.cache.as_ref()?.get(&key)?;
+        tracing::info!(model = %self.model, %key, "キャッシュ済みの応答を使用");
+        Some(cached)
+    }
+
+    fn store(&self, messages: &[Message], content: &str) -> Result<()> {
+        match &self.cache {
+            Some(cache) => cache.put(&self.cache_key(messages), content),
+            None => Ok(()),
+        }
+    }
+
+    fn build_request(&self, messages: &[Message], stream: bool) -> OpenAIRequest {
+        let reasoning = self.is_reasoning();
+        let messages = messages
+            .iter()
+            .map(|message| match message.role.as_str() {
+                "system" if reasoning => Message::new("developer", message.content.clone()),
+                _ => message.clone(),
+            })
+            .collect();
+        OpenAIRequest {
+            model: self.model.clone(),
+            messages,
+            temperature: (!reasoning).then_some(self.temperature),
+            reasoning_effort: self.reasoning_effort.filter(|_| reasoning),
+            max_completion_tokens: self.max_output_tokens,
+            stream,
+        }
+    }
+
+    /// Chat Completions APIのリクエスト本文（Batch APIの各リクエストの`body`に使う）
+    pub fn request_body(&self, messages: &[Message]) -> Result<serde_json::Value> {
+        Ok(serde_json::to_value(self.build_request(messages, false))?)
+    }
+
+    async fn send(&self, request: &OpenAIRequest) -> Result<reqwest::Response> {
+        let response = self
+            .http
+            .post(CHAT_COMPLETIONS_URL)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .json(request)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            anyhow::bail!("OpenAI API error: {}", error_text);
+        }
+        Ok(response)
+    }
+
+    // 呼び出しを記録（--save-transcript指定時のみ）
+    fn record(
+        &self,
+        messages: &[Message],
+        request: Option<&OpenAIRequest>,
+        response: &serde_json::Value,
+        started: Option<Instant>,
+    ) -> Result<()> {
+        let Some(transcript) = &self.transcript else {
+            return Ok(());
+        };
+        let request = request.map(serde_json::to_value).transpose()?;
+        transcript.record(Call {
+            provider: "openai",
+            model: &self.model,
+            messages,
+            request: request.as_ref(),
+            response,
+            started,
+        })
+    }
+
+    async fn request(&self, messages: &[Message]) -> Result<String> {
+        let started = Instant::now();
+        let request = self.build_request(messages, false);
+        let body = self.send(&request).await?.text().await?;
+        self.record(
+            messages,
+            Some(&request),
+            &serde_json::from_str(&body)?,
+            Some(started),
+        )?;
+
+        let openai_response: OpenAIResponse = serde_json::from_str(&body)?;
+        let usage = openai_response.usage.as_ref();
+        tracing::info!(
+            model = %self.model,
+            messages = messages.len(),
+            prompt_chars = messages.iter().map(|m| m.content.chars().count()).sum::<usize>(),
+            prompt_tokens = usage.map(|u| u.prompt_tokens),
+            completion_tokens = usage.map(|u| u.completion_tokens),
+            reasoning_tokens = usage
+                .and_then(|u| u.completion_tokens_details.as_ref())
+                .map(|d| d.reasoning_tokens),
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "OpenAI chat completion"
+        );
+        let choice = openai_response
+            .choices
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("No response from OpenAI"))?;
+        if let Some(refusal) = &choice.message.refusal {
+            anyhow::bail!("OpenAIが応答を拒否しました: {refusal}");
+        }
+        match choice.message.content.as_deref() {
+            Some(content) if !content.is_empty() => Ok(content.to_string()),
+            _ if choice.finish_reason.as_deref() == Some("length") => anyhow::bail!(
+                "出力トークンの上限に達し、応答が空でした（推論モデルでは--max-output-tokensを増やすか、--reasoning-effortを下げてください）"
+            ),
+            _ => anyhow::bail!("No response from OpenAI"),
+        }
+    }
+
+    /// テキストごとの埋め込みベクトルを取得（入力と同じ順序で返す）
+    pub async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>> {
+        ensure_online(EMBEDDING_MODEL)?;
+        let mut embeddings = Vec::with_capacity(inputs.len());
+        for batch in inputs.chunks(EMBEDDING_BATCH_SIZE) {
+            let started = Instant::now();
+            let response = self
+                .http
+                .post(EMBEDDINGS_URL)
+                .header("Authorization", format!("Bearer {}", self.api_key))
+                .json(&EmbeddingRequest {
+                    model: EMBEDDING_MODEL,
+                    input: batch,
+                })
+                .send()
+                .await?;
+
+            if !response.status().is_success() {
+                let error_text = response.text().await?;
+                anyhow::bail!("OpenAI API error: {}", error_text);
+            }
+
+            let mut data = response.json::<EmbeddingResponse>().await?.data;
+            tracing::info!(
+                model = EMBEDDING_MODEL,
+                inputs = batch.len(),
+                elapsed_ms = started.elapsed().as_millis() as u64,
+                "OpenAI embeddings"
+            );
+            data.sort_by_key(|d| d.index);
+            embeddings.extend(data.into_iter().map(|d| d.embedding));
+        }
+        Ok(embeddings)
+    }
+}
+
+// キャッシュを設定している場合は、キャッシュ済みの応答があればそれを返す
+impl LlmClient for OpenAiClient {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn complete(&self, messages: &[Message]) -> Result<String> {
+        if let Some(cached) = self.cached(messages) {
+            self.record(
+                messages,
+                None,
+                &serde_json::json!({ "content": cached }),
+                None,
+            )?;
+            return Ok(cached);
+        }
+        ensure_online(&self.model)?;
+        let content = self.request(messages).await?;
+        self.store(messages, &content)?;
+        Ok(content)
+    }
+
+    async fn stream(
+        &self,
+        messages: &[Message],
+        mut on_token: impl FnMut(&str) + Send,
+    ) -> Result<String> {
+        if let Some(cached) = self.cached(messages) {
+            self.record(
+                messages,
+                None,
+                &serde_json::json!({ "content": cached }),
+                None,
+            )?;
+            on_token(&cached);
+            return Ok(cached);
+        }
+        ensure_online(&self.model)?;
+
+        let started = Instant::now();
+        let request = self.build_request(messages, true);
+        let response = self.send(&request).await?;
+        let mut content = String::new();
+        read_sse(response, |data| {
+            if data == "[DONE]" {
+                return Ok(());
+            }
+            let chunk: StreamChunk = serde_json::from_str(data)?;
+            if let Some(text) = chunk
+                .choices
+                .first()
+                .and_then(|c| c.delta.content.as_deref())
+            {
+                on_token(text);
+                content.push_str(text);
+            }
+            Ok(())
+        })
+        .await?;
+        tracing::info!(
+            model = %self.model,
+            messages = messages.len(),
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "OpenAI chat completion (stream)"
+        );
+        self.record(
+            messages,
+            Some(&request),
+            &serde_json::json!({ "content": content }),
+            Some(started),
+        )?;
+
+        self.store(messages, &content)?;
+        Ok(content)
+    }
+}
+
+/// LLMのプロバイダ（設定ファイルの`[[models]]`の`provider`）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    #[default]
+    OpenAi,
+    /// `anthropic` featureでビルドした場合のみ使える
+    Anthropic,
+}
+
+impl Provider {
+    /// モデル名から推定したプロバイダ（`claude-`で始まればAnthropic、それ以外はOpenAI）
+    pub fn for_model(model: &str) -> Self {
+        if model.starts_with("claude") {
+            Provider::Anthropic
+        } else {
+            Provider::OpenAi
+        }
+    }
+
+    /// APIキーを読む環境変数
+    pub fn api_key_var(self) -> &'static str {
+        match self {
+            Provider::OpenAi => "OPENAI_API_KEY",
+            Provider::Anthropic => "ANTHROPIC_API_KEY",
+        }
+    }
+}
+
+/// フォールバックの連鎖に並べるモデル（設定ファイルの`[[models]]`）
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ModelSpec {
+    pub provider: Provider,
+    /// モデル名（省略時はプロバイダの既定のモデル）
+    pub model: Option<String>,
+}
+
+/// 設定で選んだプロバイダのクライアント
+#[derive(Debug, Clone)]
+pub enum ProviderClient {
+    OpenAi(OpenAiClient),
+    #[cfg(feature = "anthropic")]
+    Anthropic(crate::anthropic::AnthropicClient),
+    /// 失敗したら次のモデルで再試行する連鎖
+    Fallback(FallbackChain),
+    /// 用意済みのサマリーを返すデモ用のクライアント（`--demo`）
+    Demo(DemoClient),
+}
+
+impl LlmClient for ProviderClient {
+    fn model(&self) -> &str {
+        match self {
+            Self::OpenAi(client) => client.model(),
+            #[cfg(feature = "anthropic")]
+            Self::Anthropic(client) => client.model(),
+            Self::Fallback(chain) => chain.active().model(),
+            Self::Demo(client) => client.model(),
+        }
+    }
+
+    async fn complete(&self, messages: &[Message]) -> Result<String> {
+        match self {
+            Self::Fallback(chain) => chain.complete(messages).await,
+            client => client.complete_single(messages).await,
+        }
+    }
+
+    async fn stream(
+        &self,
+        messages: &[Message],
+        on_token: impl FnMut(&str) + Send,
+    ) -> Result<String> {
+        match self {
+            Self::Fallback(chain) => chain.stream(messages, on_token).await,
+            client => client.stream_single(messages, on_token).await,
+        }
+    }
+}
+
+// 連鎖の中の各モデルの呼び出し（連鎖の入れ子には対応しない）
+impl ProviderClient {
+    async fn complete_single(&self, messages: &[Message]) -> Result<String> {
+        match self {
+            Self::OpenAi(client) => client.complete(messages).await,
+            #[cfg(feature = "anthropic")]
+            Self::Anthropic(client) => client.complete(messages).await,
+            Self::Demo(client) => client.complete(messages).await,
+            Self::Fallback(_) => anyhow::bail!("フォールバックの連鎖は入れ子にできません"),
+        }
+    }
+
+    async fn stream_single(
+        &self,
+        messages: &[Message],
+        on_token: impl FnMut(&str) + Send,
+    ) -> Result<String> {
+        match self {
+            Self::OpenAi(client) => client.stream(messages, on_token).await,
+            #[cfg(feature = "anthropic")]
+            Self::Anthropic(client) => client.stream(messages, on_token).await,
+            Self::Demo(client) => client.stream(messages, on_token).await,
+            Self::Fallback(_) => anyhow::bail!("フォールバックの連鎖は入れ子にできません"),
+        }
+    }
+}
+
+/// 順に試すモデルの連鎖
+///
+/// モデルがエラー（レート制限・コンテキスト長の超過・障害など）を返したら次のモデルで再試行し、
+/// 以降の呼び出しも成功したモデルから始めます。使ったモデルは[`LlmClient::model`]で分かります。
+#[derive(Debug, Clone)]
+pub struct FallbackChain {
+    clients: Vec<ProviderClient>,
+    active: Arc<AtomicUsize>,
+}
+
+impl FallbackChain {
+    /// `clients`を試す順に並べた連鎖（空の場合は`None`）
+    pub fn new(clients: Vec<ProviderClient>) -> Option<Self> {
+        if clients.is_empty() {
+            return None;
+        }
+        Some(Self {
+            clients,
+            active: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+
+    /// 直近に成功した（まだ呼び出していなければ最初の）モデル
+    pub fn active(&self) -> &ProviderClient {
+        &self.clients[self.active.load(Ordering::Relaxed)]
+    }
+
+    async fn complete(&self, messages: &[Message]) -> Result<String> {
+        let mut last_error = None;
+        for index in self.active.load(Ordering::Relaxed)..self.clients.len() {
+            match self.clients[index].complete_single(messages).await {
+                Ok(content) => {
+                    self.activate(index);
+                    return Ok(content);
+                }
+                Err(e) => last_error = Some(self.fail(index, e)),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("試せるモデルがありません")))
+    }
+
+    // 応答の一部を出力した後の失敗は、出力が重複するため次のモデルで再試行しない
+    async fn stream(
+        &self,
+        messages: &[Message],
+        mut on_token: impl FnMut(&str) + Send,
+    ) -> Result<String> {
+        let mut last_error = None;
+        for index in self.active.load(Ordering::Relaxed)..self.clients.len() {
+            let mut emitted = false;
+            let result = self.clients[index]
+                .stream_single(messages, |token| {
+                    emitted = true;
+                    on_token(token);
+                })
+                .await;
+            match result {
+                Ok(content) => {
+                    self.activate(index);
+                    return Ok(content);
+                }
+                Err(e) if emitted => return Err(e),
+                Err(e) => last_error = Some(self.fail(index, e)),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("試せるモデルがありません")))
+    }
+
+    fn activate(&self, index: usize) {
+        if self.active.swap(index, Ordering::Relaxed) != index {
+            tracing::warn!(model = %self.clients[index].model(), "フォールバック先のモデルで生成しました");
+        }
+    }
+
+    fn fail(&self, index: usize, error: anyhow::Error) -> anyhow::Error {
+        tracing::warn!(model = %self.clients[index].model(), error = %format!("{error:#}"), "モデルの呼び出しに失敗");
+        error.context(format!(
+            "{}の呼び出しに失敗しました",
+            self.clients[index].model()
+        ))
+    }
+}
+
+/// 再生中（`--replay`）は記録された応答がない呼び出しをエラーにする
+pub(crate) fn ensure_online(model: &str) -> Result<()> {
+    if fixture::is_replay() {
+        anyhow::bail!("{model}への呼び出しは記録されていません（--replayではAPIを呼び出しません）");
+    }
+    Ok(())
+}
+
+/// Server-Sent Eventsの応答を読み、イベントごとに`data:`の内容を`on_data`に渡す
+pub(crate) async fn read_sse(
+    mut response: reqwest::Response,
+    mut on_data: impl FnMut(&str) -> Result<()>,
+) -> Result<()> {
+    // マルチバイト文字がチャンクの境界で分かれることがあるため、行単位に区切ってから変換する
+    let mut buffer: Vec<u8> = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        buffer.extend_from_slice(&chunk);
+        while let Some(end) = buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            if let Some(data) = line.trim_end().strip_prefix("data:") {
+                on_data(data.trim_start())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 応答を囲むMarkdownのコードブロック（```json など）を取り除く
+pub fn strip_code_fence(response: &str) -> &str {
+    response
+        .trim()
+        .trim_start_matches("```json")
+        .trim_start_matches("```")
+        .trim_end_matches("```")
+        .trim()
+}
+
+/// 活動データ全体から評価サマリーを生成
+pub async fn generate_summary(
+    client: &impl LlmClient,
+    query: &SearchQuery,
+    prs: &[PullRequest],
+    issues: &[Issue],
+    options: &PromptOptions,
+) -> Result<String> {
+    let prompt = report::build_prompt(query, prs, issues, options)?;
+    client.chat(options.tone.system_prompt(), &prompt).await
+}
+
+/// 下書きを品質チェックリストで講評し、講評を反映した最終版を返す
+pub async fn refine(
+    client: &impl LlmClient,
+    query: &SearchQuery,
+    prs: &[PullRequest],
+    issues: &[Issue],
+    options: &PromptOptions,
+    draft: &str,
+) -> Result<String> {
+    let prompt = report::build_critique_prompt(query, prs, issues, options, draft)?;
+    let critique = client.chat(report::CRITIQUE_SYSTEM_PROMPT, &prompt).await?;
+
+    let prompt = report::build_revision_prompt(query, prs, issues, options, draft, &critique)?;
+    client.chat(options.tone.system_prompt(), &prompt).await
+}
+
+/// 生成済みのサマリーを活動データと照合し、引用付きに書き直したサマリーを返す
+pub async fn fact_check(
+    client: &impl LlmClient,
+    query: &SearchQuery,
+    prs: &[PullRequest],
+    issues: &[Issue],
+    options: &PromptOptions,
+    summary: &str,
+) -> Result<String> {
+    let prompt = report::build_fact_check_prompt(query, prs, issues, options, summary)?;
+    client.chat(report::FACT_CHECK_SYSTEM_PROMPT, &prompt).await
+}
+
+/// 生成済みサマリーの見出しごとに根拠となるPR/IssueのURLを選ばせ、活動データにあるものを返す
+///
+/// 2つ目の値は、活動データに含まれないため除いたURLです。
+pub async fn evidence(
+    client: &impl LlmClient,
+    query: &SearchQuery,
+    prs: &[PullRequest],
+    issues: &[Issue],
+    options: &PromptOptions,
+    summary: &str,
+) -> Result<(Vec<Evidence>, Vec<String>)> {
+    let sections = evidence::sections(summary);
+    if sections.is_empty() {
+        return Ok((Vec::new(), Vec::new()));
+    }
+    let prompt = report::build_evidence_prompt(query, prs, issues, options, summary, &sections)?;
+    let response = client.chat(report::EVIDENCE_SYSTEM_PROMPT, &prompt).await?;
+    evidence::parse(&response, &sections, prs, issues)
+}
+
+/// 複数のモデルで生成したサマリー（モデル名, サマリー）を1つに統合して返す
+pub async fn merge_summaries(
+    client: &impl LlmClient,
+    query: &SearchQuery,
+    prs: &[PullRequest],
+    issues: &[Issue],
+    options: &PromptOptions,
+    candidates: &[(String, String)],
+) -> Result<String> {
+    let prompt = report::build_merge_prompt(query, prs, issues, options, candidates)?;
+    client.chat(options.tone.system_prompt(), &prompt).await
+}
+
+/// 生成済みのサマリーを`to`の言語に翻訳して返す
+pub async fn translate(
+    client: &impl LlmClient,
+    summary: &str,
+    to: ResumeLanguage,
+) -> Result<String> {
+    let prompt = report::build_translation_prompt(summary, to);
+    client
+        .chat(report::TRANSLATION_SYSTEM_PROMPT, &prompt)
+        .await
+}
+
+/// リポジトリごとのサマリーと全体サマリーを生成し、Markdownとして連結して返す
+///
+/// `on_progress`には生成中の対象（リポジトリ名、または全体サマリー）が渡されます。
+pub async fn generate_per_repo_summary(
+    client: &impl LlmClient,
+    query: &SearchQuery,
+    prs: &[PullRequest],
+    issues: &[Issue],
+    options: &PromptOptions,
+    mut on_progress: impl FnMut(&str),
+) -> Result<String> {
+    let repos = report::group_by_repository(prs, issues);
+
+    let mut repo_summaries = Vec::new();
+    for repo in &repos {
+        on_progress(&repo.name);
+        let prompt = report::build_repository_prompt(query, repo, options)?;
+        let summary = client.chat(options.tone.system_prompt(), &prompt).await?;
+        repo_summaries.push((repo.name.clone(), summary));
+    }
+
+    on_progress("全体サマリー");
+    let prompt = report::build_overview_prompt(query, prs, issues, &repo_summaries, options);
+    let overview = client.chat(options.tone.system_prompt(), &prompt).await?;
+
+    let mut output = String::new();
+    for (repo, summary) in &repo_summaries {
+        output.push_str(&format!("## {repo}\n\n{summary}\n\n"));
+    }
+    output.push_str(&format!("## 全体サマリー\n\n{overview}"));
+
+    Ok(output)
+}
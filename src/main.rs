@@ -1,599 +1,3083 @@
-use anyhow::Result;
-use clap::Parser;
-use serde::{Deserialize, Serialize};
+use anyhow::{Context, Result};
+use chrono_tz::Tz;
+use clap::{Parser, Subcommand, ValueEnum};
+#[cfg(feature = "anthropic")]
+use easy_hyoka::anthropic::AnthropicClient;
+use easy_hyoka::availability::Availability;
+use easy_hyoka::batch::{self, BatchClient};
+use easy_hyoka::budget::{self, Truncated, Truncation};
+use easy_hyoka::cache::ResponseCache;
+use easy_hyoka::checkpoint::Checkpoint;
+use easy_hyoka::condense::Condense;
+use easy_hyoka::config::Config;
+use easy_hyoka::demo::DemoClient;
+use easy_hyoka::error::Failure;
+use easy_hyoka::fiscal::FiscalPeriod;
+use easy_hyoka::gaps::{self, Gap};
+use easy_hyoka::github::{
+    self, Activity, Community, GitHub, Issue, Membership, Mentions, PullRequest, Role, SearchQuery,
+};
+use easy_hyoka::highlight::ScoringWeights;
+use easy_hyoka::history::{self, History};
+use easy_hyoka::incident::IncidentStats;
+use easy_hyoka::journal;
+use easy_hyoka::llm::{
+    self, FallbackChain, LlmClient, ModelSpec, OpenAiClient, Provider, ProviderClient,
+    ReasoningEffort,
+};
+use easy_hyoka::render::{self, ReportContext};
+use easy_hyoka::report::{Mode, PromptOptions, ResumeLanguage, Tone};
+use easy_hyoka::schedule::CronSchedule;
+use easy_hyoka::snapshot::Snapshot;
+use easy_hyoka::source::{self, DataSource};
+use easy_hyoka::stats::{self, ActivityStats, Granularity, WorkPattern};
+use easy_hyoka::strip::StripRules;
+use easy_hyoka::transcript::Transcript;
+use easy_hyoka::{
+    areas, clipboard, cluster, condense, confluence, demo, email, evidence, export, fixture, gdocs,
+    keychain, limits, network, report, revert, slack, strip, template, topics,
+};
+use indicatif::ProgressBar;
+use std::collections::HashSet;
 use std::env;
-use std::process::Command;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
+use tracing_subscriber::EnvFilter;
 
-#[derive(Parser, Debug)]
+mod chat;
+mod mcp;
+mod progress;
+mod tui;
+
+use progress::Progress;
+
+#[derive(Parser, Debug, Clone)]
 #[command(name = "easyhyoka")]
 #[command(about = "GitHub PR/Issuesを取得してOpenAIで実績一覧を生成")]
 struct Args {
-    #[arg(long)]
-    owner: String,
-
-    #[arg(long)]
+    #[command(subcommand)]
+    command: Option<Commands>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "取得対象のOrganizationまたはユーザー（省略時はGitHub全体の公開リポジトリ）"
+    )]
+    owner: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Organizationが所有するリポジトリのみを対象にする（個人のリポジトリを除く）"
+    )]
+    org_only: bool,
+
+    #[arg(long, global = true)]
     author: Option<String>,
 
-    #[arg(long, default_value = "2025-01-01")]
-    since: String,
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t = RoleArg::Author,
+        help = "対象ユーザーとPR/Issueの関わり方"
+    )]
+    role: RoleArg,
+
+    #[arg(
+        long,
+        global = true,
+        help = "期間の開始日（省略時は2025-01-01、--mode weeklyでは7日前）"
+    )]
+    since: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "期間の終了日（省略時は2025-06-30、--mode weeklyでは今日）"
+    )]
+    until: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "YYYYQn",
+        value_parser = FiscalPeriod::parse_quarter,
+        conflicts_with_all = ["since", "until", "half"],
+        help = "会計年度の四半期で期間を指定（例: 2025Q1、年度の開始月は設定ファイルのfiscal_year_start）"
+    )]
+    quarter: Option<FiscalPeriod>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "YYYYHn",
+        value_parser = FiscalPeriod::parse_half,
+        conflicts_with_all = ["since", "until"],
+        help = "会計年度の半期で期間を指定（例: 2025H1は上期、2025H2は下期）"
+    )]
+    half: Option<FiscalPeriod>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "LABEL",
+        value_parser = parse_period_label,
+        help = "レポートのタイトル・ファイル名・プロンプトで日付の代わりに使う期間の呼び名（例: 2025年度上期）"
+    )]
+    period_label: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "TZ",
+        help = "期間の日付と活動日時を扱うタイムゾーン（例: Asia/Tokyo、省略時はUTC）"
+    )]
+    tz: Option<Tz>,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t = BucketArg::Month,
+        help = "期間中の推移を集計する単位"
+    )]
+    bucket: BucketArg,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        help = "期間中の推移を表示（ascii: 棒グラフ、heatmap: 日別のヒートマップ、json: JSON）"
+    )]
+    timeline: Option<TimelineFormat>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "曜日・時間帯別の作成数を集計し、深夜・土日の作業が続いている場合は注記する（作業負荷の把握用、プロンプトには含めない）"
+    )]
+    work_hours: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        help = "重要度の高いN件のPRのみ本文・コメント付きで送信し、残りはメタデータのみにする"
+    )]
+    highlights: Option<usize>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "FILE",
+        help = "PRの重要度スコアに使う重みファイル（TOML）"
+    )]
+    weights: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "PR/Issueの本文・コメントを送信せず、タイトルや件数などのメタデータのみで生成"
+    )]
+    metadata_only: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "PRを埋め込みでクラスタリングし、プロジェクト候補としてプロンプトに含める（OpenAI APIを使用）"
+    )]
+    cluster: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "キーワードでトピックを判定できなかったPR/Issueを安価なモデルで分類（OpenAI APIを使用）"
+    )]
+    classify_topics: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        requires = "cluster",
+        help = "クラスタ数（省略時はPR数から自動で決定）"
+    )]
+    clusters: Option<usize>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "生成後にサマリーを活動データと照合し、根拠のない記述の修正と引用の付与を行う"
+    )]
+    fact_check: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "サマリーの見出しごとに根拠となるPR/IssueのURLを選ばせ、付録として末尾に付ける"
+    )]
+    evidence: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "受けたコメント・レビューから称賛や設計の議論を抜き出し、原文のままの引用としてプロンプトに含める"
+    )]
+    quotes: bool,
+
+    #[arg(
+        long,
+        global = true,
+        conflicts_with_all = ["per_repo", "questions"],
+        help = "長期間オープンのPR・動きのないIssue・マージされずにクローズしたPRを集め、リスクとフォローアップの項目を加える（実績サマリー・週報・360度評価のみ）"
+    )]
+    unshipped: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "下書き→講評→最終版の順に生成し、サマリーの品質を高める"
+    )]
+    refine: bool,
+
+    #[arg(
+        long,
+        global = true,
+        conflicts_with = "tui",
+        help = "サマリー生成後に、追加の質問や修正依頼を対話的に行う"
+    )]
+    chat: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "生成したサマリーをもう一方の言語（日本語⇔英語）に翻訳し、両方を並べて出力する"
+    )]
+    bilingual: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t = ModeArg::Summary,
+        help = "生成するドキュメントの種類"
+    )]
+    mode: ModeArg,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t = ToneArg::Promotional,
+        help = "評価のトーン"
+    )]
+    tone: ToneArg,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "LEVEL",
+        help = "昇格先のレベル（--mode promo用、例: L5）"
+    )]
+    target_level: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t = ResumeLangArg::En,
+        help = "職務経歴書の出力言語（--mode resume用）"
+    )]
+    resume_lang: ResumeLangArg,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "FILE",
+        num_args = 1..,
+        required_if_eq("mode", "360"),
+        help = "ピアフィードバックのテキストファイル（--mode 360用、複数指定可）"
+    )]
+    peer_feedback: Vec<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "FILE",
+        help = "本人の自己評価のテキストファイル（--mode 360用）"
+    )]
+    self_assessment: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "FILE",
+        conflicts_with = "per_repo",
+        help = "自己評価フォームの設問ファイル（Markdownなど）。各設問への回答案を生成"
+    )]
+    questions: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t = ProviderArg::Openai,
+        help = "サマリーの生成に使うLLMのプロバイダ（--cluster・--classify-topicsは常にOpenAI）"
+    )]
+    provider: ProviderArg,
+
+    #[arg(
+        long,
+        global = true,
+        help = "キャッシュ済みの応答を使わず、LLMで生成し直す"
+    )]
+    no_cache: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "T",
+        help = "サマリーの生成の温度（低いほど決定的、既定: 0.7）"
+    )]
+    temperature: Option<f32>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        help = "サマリーの生成で出力する最大トークン数"
+    )]
+    max_output_tokens: Option<u32>,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        help = "OpenAIの推論モデル（o3・o4-mini・gpt-5など）の推論の深さ（高いほど深く分析、時間と費用が増える）"
+    )]
+    reasoning_effort: Option<ReasoningEffortArg>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "前回中断した取得を再開せず、最初から取得し直す"
+    )]
+    no_resume: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "生成したサマリーを履歴（easyhyoka history）に保存しない"
+    )]
+    no_history: bool,
+
+    #[arg(
+        long,
+        global = true,
+        conflicts_with_all = ["replay", "feedback", "gist", "post_issue"],
+        help = "GitHubの代わりに合成データで実行する（GitHubには接続せず、--commitsなど取得に関わる指定は無視。APIキーがなければ用意済みのサマリーを表示）"
+    )]
+    demo: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "DIR",
+        help = "LLMの呼び出しごとに、プロンプト・APIのリクエストと応答・トークン使用量をJSONでDIRに保存する"
+    )]
+    save_transcript: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "FILE",
+        help = "HTTPSの接続で追加で信頼するCA証明書（PEM、設定ファイルの[http]のca_certより優先）"
+    )]
+    ca_cert: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "DIR",
+        conflicts_with = "replay",
+        help = "ghとLLMの呼び出し結果をDIRに記録する（--replayで再生できる）"
+    )]
+    record: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "DIR",
+        conflicts_with_all = ["gist", "post_issue", "slack_webhook", "email", "google_docs", "confluence_space"],
+        help = "--recordで記録した結果を再生し、ネットワークや認証情報なしで実行する（Gist・Issue・Slackなどへの公開はしない）"
+    )]
+    replay: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "TEXT",
+        help = "直近に取得したデータを使い、指示を加えてサマリーだけを生成し直す（例: \"移行プロジェクトを強調\"）"
+    )]
+    feedback: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        default_value_t = 4,
+        value_parser = clap::value_parser!(u16).range(1..),
+        help = "PRごとの追加取得などで同時に実行するghの数"
+    )]
+    concurrency: u16,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "SECS",
+        default_value_t = 300,
+        help = "ghの実行・HTTPリクエスト1回あたりのタイムアウト秒数（LLMの呼び出しでは接続と応答が途切れている時間に適用、0でタイムアウトなし）"
+    )]
+    timeout: u64,
+
+    #[arg(long, global = true, help = "OpenAIに送信するプロンプトを表示")]
+    show_prompts: bool,
 
-    #[arg(long, default_value = "2025-06-30")]
-    until: String,
+    #[arg(
+        long,
+        global = true,
+        help = "リポジトリごとに個別のサマリーを生成し、最後に全体サマリーを作成"
+    )]
+    per_repo: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_delimiter = ',',
+        value_name = "MODELS",
+        conflicts_with_all = ["per_repo", "tui"],
+        help = "複数のモデル（カンマ区切り、claude-で始まるものはAnthropic）で並行して生成し、モデルごとに並べて出力する"
+    )]
+    models: Vec<String>,
+
+    #[arg(
+        long,
+        global = true,
+        requires = "models",
+        help = "--modelsで生成したサマリーを比較し、1つのサマリーに統合する"
+    )]
+    merge_models: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "PRのレビューとレビュー依頼先を取得（受けたレビュー、--role reviewerでは自分のレビューの応答時間・承認率・スレッドの解決率も集計）"
+    )]
+    reviews: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "PRが解決したIssue（closes/fixes #N）を取得し、他メンバー起票分も含めて集計"
+    )]
+    resolved_issues: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "主要なPR（--highlights指定時は上位N件、それ以外は全件）が解決したIssueのタイトル・ラベルをプロンプトに含める"
+    )]
+    linked_issues: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "主要なPR（--highlights指定時は上位N件、それ以外は全件）のコミットを取得し、コミット数とメッセージをプロンプトに含める"
+    )]
+    commits: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "各PRの変更ファイルを取得し、言語・技術別の内訳を集計（PRごとに追加のAPI呼び出し）"
+    )]
+    tech_stack: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "OWNER/REPO",
+        value_delimiter = ',',
+        help = "モノレポとして、変更ファイルのパスの先頭のディレクトリ（例: services/payments）ごとに集計するリポジトリ（カンマ区切り、変更ファイルを取得）"
+    )]
+    monorepo: Vec<String>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "PR/Issueのマイルストーンとプロジェクト（Projects）を取得し、それごとにまとめる（1件ごとに追加のAPI呼び出し、Projectsにはread:projectスコープが必要）"
+    )]
+    roadmap: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "PR/Issueへのリアクション（👍・🎉など）を取得し、反響として集計（1件ごとに追加のAPI呼び出し）"
+    )]
+    reactions: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "マージ済みPRのCI結果とデプロイを取得し、本番まで届いた成果を集計（PRごとに追加のAPI呼び出し）"
+    )]
+    ship_signals: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "各PRの変更ファイルを取得し、主にドキュメントを変更したPRを別に集計（PRごとに追加のAPI呼び出し）"
+    )]
+    docs: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "作成・回答したGitHub Discussionsを取得し、知識共有の貢献として含める"
+    )]
+    discussions: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "PR/Issueのあるリポジトリで期間内に公開したリリースを取得（リポジトリごとに追加のAPI呼び出し）"
+    )]
+    releases: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "社外のOSSでマージされたPRと公開Gistを取得し、コミュニティへの貢献としてまとめる"
+    )]
+    community: bool,
+
+    #[arg(
+        long,
+        global = true,
+        requires = "owner",
+        help = "Organizationでの役割と所属チームを取得し、本人の責任範囲を踏まえて評価する"
+    )]
+    membership: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "他のメンバーのIssueへのラベル付け・クローズ・最初の応答を取得し、トリアージの貢献として評価する"
+    )]
+    triage: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "他のメンバーからのメンションと、他のリポジトリからのPRの参照を取得し、頼られている度合いとして評価する"
+    )]
+    mentions: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "SINCE..UNTIL[:REASON]",
+        value_delimiter = ',',
+        help = "休暇などで活動のない期間（カンマ区切り、例: 2025-03-01..2025-03-21:育休）。推移の評価から除外する"
+    )]
+    gaps: Vec<Gap>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "取得したPR/Issueを選択してサマリーを生成するTUIを起動"
+    )]
+    tui: bool,
+
+    #[arg(
+        long,
+        global = true,
+        conflicts_with_all = ["tui", "chat"],
+        help = "CI向けの実行（進捗を1行ずつ標準エラー出力に表示し、サマリーを$GITHUB_STEP_SUMMARYにも書き出す）"
+    )]
+    ci: bool,
+
+    #[arg(
+        short,
+        long,
+        global = true,
+        action = clap::ArgAction::Count,
+        help = "詳細なログを標準エラー出力に表示（-v: API呼び出しの所要時間・トークン数、-vv: ghの実行内容も）"
+    )]
+    verbose: u8,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t = LogFormat::Text,
+        help = "ログの形式"
+    )]
+    log_format: LogFormat,
+
+    #[arg(
+        long,
+        short,
+        global = true,
+        help = "進捗表示を抑制し、サマリーのみを出力"
+    )]
+    quiet: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "設定ファイルのパス（省略時は ~/.config/easyhyoka/config.toml）"
+    )]
+    config: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "生成したサマリーを投稿するSlackのIncoming Webhook URL"
+    )]
+    slack_webhook: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "ADDRESS",
+        help = "生成したレポートをメールで送る宛先（複数指定可、送信方法は設定ファイルの[email]）"
+    )]
+    email: Vec<String>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "生成したサマリーをGoogleドキュメントとして作成（作成先は設定ファイルの[google_docs]）"
+    )]
+    google_docs: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "KEY",
+        help = "生成したサマリーをConfluenceのこのスペースに公開（同じタイトルのページがあれば更新）"
+    )]
+    confluence_space: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "PAGE_ID",
+        requires = "confluence_space",
+        help = "Confluenceでページを作成する親ページのID"
+    )]
+    confluence_parent: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "OWNER/REPO",
+        help = "生成したサマリーを本文とするIssueをこのリポジトリに作成（同じタイトルのIssueがあれば更新）"
+    )]
+    post_issue: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "生成したサマリー（Markdown）をクリップボードにもコピー"
+    )]
+    copy: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_values_t = [OutputFormat::Md],
+        help = "出力形式（複数指定すると、--outputの拡張子を形式ごとに変えて書き出す）"
+    )]
+    format: Vec<OutputFormat>,
+
+    #[arg(
+        long,
+        short,
+        global = true,
+        help = "出力先ファイル（省略時は標準出力）"
+    )]
+    output: Option<PathBuf>,
+
+    #[arg(long, global = true, help = "生成したサマリーをGistとしてアップロード")]
+    gist: bool,
+
+    #[arg(
+        long,
+        global = true,
+        requires = "gist",
+        help = "Gistをシークレットとして作成"
+    )]
+    secret: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "DIR",
+        help = "取得したデータをCSV（prs/issues/comments）で書き出すディレクトリ"
+    )]
+    export_csv: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "FILE",
+        help = "プロンプトに含める1件ずつのデータをJSON Lines形式で書き出すファイル"
+    )]
+    export_jsonl: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        help = "プロンプトのトークン数の上限（概算、超える場合は--truncateの順にデータを削る）"
+    )]
+    max_prompt_tokens: Option<usize>,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_delimiter = ',',
+        default_values_t = [TruncateArg::Bodies, TruncateArg::Comments, TruncateArg::Oldest],
+        help = "上限を超えたときにデータを削る順（カンマ区切り）"
+    )]
+    truncate: Vec<TruncateArg>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "CHARS",
+        help = "本文がCHARS文字を超えるPR/Issueを、プロンプトの構築前に短くする"
+    )]
+    summarize_bodies: Option<usize>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "PR本文のテンプレートから、記入のないセクションや定型のチェックリストを除く"
+    )]
+    template_sections: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_delimiter = ',',
+        value_name = "KINDS",
+        help = "本文・コメントから取り除くノイズ（カンマ区切り、images: 画像・base64、html: HTMLコメント・タグ、code: 長いコードブロック）"
+    )]
+    strip: Vec<StripArg>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        help = "--strip codeで省略するコードブロックの行数（既定: 40）"
+    )]
+    max_code_lines: Option<usize>,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t = CondenseArg::Llm,
+        requires = "summarize_bodies",
+        help = "長い本文の短くし方（llm: 安価なモデルで要約、extract: 先頭から行単位で切り詰め）"
+    )]
+    condense: CondenseArg,
+}
 
-    #[arg(long, help = "OpenAIに送信するプロンプトを表示")]
-    show_prompts: bool,
+#[derive(Subcommand, Debug, Clone)]
+enum Commands {
+    /// 取得したデータのスナップショットを保存・読み込み
+    Snapshot {
+        #[command(subcommand)]
+        action: SnapshotAction,
+    },
+    /// cron式のスケジュールで取得・生成を繰り返し実行（定期ダイジェスト用）
+    Serve {
+        #[arg(
+            long,
+            value_name = "EXPR",
+            help = "実行スケジュール（分 時 日 月 曜日、例: \"0 9 * * MON\"）。期間の指定がなければ前回の実行日から実行日の前日までを対象にする"
+        )]
+        cron: String,
+    },
+    /// MCPサーバーとして起動し、取得・集計・サマリー生成をツールとして公開（標準入出力）
+    Mcp,
+    /// 生成したレポートの履歴の一覧・表示・差分
+    History {
+        #[command(subcommand)]
+        action: HistoryAction,
+    },
+    /// OpenAI Batch APIで複数の対象者のサマリーをまとめて生成（通常の半額、結果は24時間以内）
+    Batch {
+        #[command(subcommand)]
+        action: BatchAction,
+    },
+    /// APIキーをOSのキーチェーンに保存・削除（環境変数や.envより優先して使う）
+    Auth {
+        #[command(subcommand)]
+        action: AuthAction,
+    },
+    /// 2つのサマリーを実績（箇条書きの項目）単位で比べ、追加・削除された実績を表示
+    Diff {
+        /// 古い方のレポート（Markdown・JSONのファイル、または履歴のID）
+        old: String,
+        /// 新しい方のレポート（Markdown・JSONのファイル、または履歴のID）
+        new: String,
+    },
+    /// 実績の記録ファイルのうち期間内の項目と最新の統計から、期間全体の評価文書を作成（記録をPRのメタデータより重視）
+    CompileJournal {
+        /// `log`で追記してきた実績の記録ファイル
+        #[arg(default_value = "achievements.md")]
+        journal: PathBuf,
+    },
+    /// 前回の記録以降の活動だけをまとめ、日付ごとの項目として実績の記録ファイルに追記（cronなどで定期実行）
+    Log {
+        /// 実績の記録ファイル（Markdown、なければ作成）
+        #[arg(default_value = "achievements.md")]
+        journal: PathBuf,
+    },
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-struct Repository {
-    #[serde(rename = "nameWithOwner")]
-    name_with_owner: String,
+#[derive(Subcommand, Debug, Clone)]
+enum AuthAction {
+    /// OpenAI APIキーをキーチェーンに保存（KEYを省略すると標準入力から読む）
+    SetOpenaiKey { key: Option<String> },
+    /// Anthropic APIキーをキーチェーンに保存（KEYを省略すると標準入力から読む）
+    SetAnthropicKey { key: Option<String> },
+    /// キーチェーンに保存したAPIキーを削除
+    Delete {
+        #[arg(value_enum)]
+        key: KeyArg,
+    },
+    /// APIキーの参照元（キーチェーン・環境変数）を表示
+    Status,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-struct PullRequest {
-    number: u32,
-    title: String,
-    body: Option<String>,
-    #[serde(rename = "createdAt")]
-    created_at: String,
-    state: String,
-    url: String,
-    repository: Repository,
-    #[serde(skip)]
-    comments: Vec<Comment>,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum KeyArg {
+    /// OPENAI_API_KEY
+    Openai,
+    /// ANTHROPIC_API_KEY
+    Anthropic,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-struct Issue {
-    number: u32,
-    title: String,
-    body: Option<String>,
-    #[serde(rename = "createdAt")]
-    created_at: String,
-    state: String,
-    url: String,
-    repository: Repository,
-    #[serde(skip)]
-    comments: Vec<Comment>,
+impl From<KeyArg> for Provider {
+    fn from(key: KeyArg) -> Self {
+        match key {
+            KeyArg::Openai => Provider::OpenAi,
+            KeyArg::Anthropic => Provider::Anthropic,
+        }
+    }
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
-struct Comment {
-    author: Option<CommentAuthor>,
-    body: String,
-    #[serde(rename = "createdAt")]
-    created_at: String,
+#[derive(Subcommand, Debug, Clone)]
+enum BatchAction {
+    /// 対象者ごとにデータを取得し、サマリー生成のリクエストをバッチとして送信
+    Submit {
+        /// 対象者（カンマ区切り）
+        #[arg(required = true, value_delimiter = ',')]
+        authors: Vec<String>,
+    },
+    /// 送信したバッチの処理状況を表示（IDを省略すると保存済みのすべてのバッチ）
+    Status { id: Option<String> },
+    /// 完了したバッチの結果を、対象者ごとのMarkdownファイルとして書き出す
+    Collect {
+        id: String,
+        /// 書き出し先のディレクトリ
+        #[arg(long, value_name = "DIR", default_value = ".")]
+        dir: PathBuf,
+    },
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
-struct CommentAuthor {
-    login: String,
+#[derive(Subcommand, Debug, Clone)]
+enum HistoryAction {
+    /// 保存された履歴を古い順に一覧表示
+    List,
+    /// 履歴のサマリーと生成条件を表示
+    Show { id: String },
+    /// 2つの履歴のサマリーの差分を表示（NEWを省略すると同じ対象の最新の版と比べる）
+    Diff { old: String, new: Option<String> },
 }
 
-#[derive(Debug, Serialize)]
-struct OpenAIRequest {
-    model: String,
-    messages: Vec<Message>,
-    temperature: f32,
+#[derive(Subcommand, Debug, Clone)]
+enum SnapshotAction {
+    /// GitHubからデータを取得してJSONに保存（サマリーは生成しない）
+    Save { path: PathBuf },
+    /// 保存したJSONからサマリーを生成（GitHubへはアクセスしない）
+    Load { path: PathBuf },
 }
 
-#[derive(Debug, Serialize)]
-struct Message {
-    role: String,
-    content: String,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum RoleArg {
+    /// 作成したPR/Issue
+    Author,
+    /// 担当者としてアサインされたPR/Issue
+    Assignee,
+    /// レビューしたPR
+    Reviewer,
+    /// メンションされたPR/Issue
+    Mentions,
+    /// 作成・担当・メンション・コメントのいずれかで関与したPR/Issue
+    Involves,
 }
 
-#[derive(Debug, Deserialize)]
-struct OpenAIResponse {
-    choices: Vec<Choice>,
+impl From<RoleArg> for Role {
+    fn from(role: RoleArg) -> Self {
+        match role {
+            RoleArg::Author => Role::Author,
+            RoleArg::Assignee => Role::Assignee,
+            RoleArg::Reviewer => Role::Reviewer,
+            RoleArg::Mentions => Role::Mentions,
+            RoleArg::Involves => Role::Involves,
+        }
+    }
 }
 
-#[derive(Debug, Deserialize)]
-struct Choice {
-    message: MessageResponse,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ModeArg {
+    /// 評価期間の実績サマリー
+    Summary,
+    /// 昇格基準（スコープ・インパクト・技術的な深さ・リーダーシップ）に沿った推薦資料
+    Promo,
+    /// 直近1週間の週報・1on1用トピック（リリース済み・進行中・ブロッカー）
+    Weekly,
+    /// 職務経歴書向けの実績箇条書き（STAR/インパクト形式）
+    Resume,
+    /// 自己評価・ピアフィードバック・活動データを突き合わせた360度評価
+    #[value(name = "360")]
+    ThreeSixty,
 }
 
-#[derive(Debug, Deserialize)]
-struct MessageResponse {
-    content: String,
+impl From<ModeArg> for Mode {
+    fn from(mode: ModeArg) -> Self {
+        match mode {
+            ModeArg::Summary => Mode::Summary,
+            ModeArg::Promo => Mode::Promo,
+            ModeArg::Weekly => Mode::Weekly,
+            ModeArg::Resume => Mode::Resume,
+            ModeArg::ThreeSixty => Mode::ThreeSixty,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ToneArg {
+    /// 成果を最大限にアピールする
+    Promotional,
+    /// 強みと成長領域の両方を公平に記述する
+    Balanced,
+    /// 改善点を率直に指摘する
+    Critical,
+}
+
+impl From<ToneArg> for Tone {
+    fn from(tone: ToneArg) -> Self {
+        match tone {
+            ToneArg::Promotional => Tone::Promotional,
+            ToneArg::Balanced => Tone::Balanced,
+            ToneArg::Critical => Tone::Critical,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ResumeLangArg {
+    /// 英語
+    En,
+    /// 日本語
+    Ja,
+}
+
+impl From<ResumeLangArg> for ResumeLanguage {
+    fn from(lang: ResumeLangArg) -> Self {
+        match lang {
+            ResumeLangArg::En => ResumeLanguage::English,
+            ResumeLangArg::Ja => ResumeLanguage::Japanese,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum BucketArg {
+    /// 週別（月曜始まり）
+    Week,
+    /// 月別
+    Month,
+}
+
+impl From<BucketArg> for Granularity {
+    fn from(bucket: BucketArg) -> Self {
+        match bucket {
+            BucketArg::Week => Granularity::Week,
+            BucketArg::Month => Granularity::Month,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum StripArg {
+    /// 埋め込み画像とbase64のデータURI
+    Images,
+    /// HTMLコメントとタグ
+    Html,
+    /// 長いコードブロック
+    Code,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum CondenseArg {
+    /// 安価なモデルで1件ずつ要約
+    Llm,
+    /// 先頭から行単位で切り詰め（APIを呼ばない）
+    Extract,
+}
+
+impl From<CondenseArg> for Condense {
+    fn from(condense: CondenseArg) -> Self {
+        match condense {
+            CondenseArg::Llm => Condense::Llm,
+            CondenseArg::Extract => Condense::Extract,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum TruncateArg {
+    /// PR/Issueの本文を省く（古いものから）
+    Bodies,
+    /// コメントとレビューの本文を省く（古いPR/Issueから）
+    Comments,
+    /// 古いPR/Issueを除外する
+    Oldest,
+}
+
+impl From<TruncateArg> for Truncation {
+    fn from(truncate: TruncateArg) -> Self {
+        match truncate {
+            TruncateArg::Bodies => Truncation::Bodies,
+            TruncateArg::Comments => Truncation::Comments,
+            TruncateArg::Oldest => Truncation::Oldest,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum TimelineFormat {
+    /// ターミナル向けの棒グラフ
+    Ascii,
+    /// ターミナル向けのカレンダー形式のヒートマップ（日別）
+    Heatmap,
+    /// JSON
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ReasoningEffortArg {
+    Low,
+    Medium,
+    High,
+}
+
+impl From<ReasoningEffortArg> for ReasoningEffort {
+    fn from(effort: ReasoningEffortArg) -> Self {
+        match effort {
+            ReasoningEffortArg::Low => ReasoningEffort::Low,
+            ReasoningEffortArg::Medium => ReasoningEffort::Medium,
+            ReasoningEffortArg::High => ReasoningEffort::High,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ProviderArg {
+    /// OpenAI（OPENAI_API_KEY）
+    Openai,
+    /// Anthropic（ANTHROPIC_API_KEY、`anthropic` featureでビルドした場合のみ）
+    #[cfg(feature = "anthropic")]
+    Anthropic,
+}
+
+impl From<ProviderArg> for Provider {
+    fn from(provider: ProviderArg) -> Self {
+        match provider {
+            ProviderArg::Openai => Provider::OpenAi,
+            #[cfg(feature = "anthropic")]
+            ProviderArg::Anthropic => Provider::Anthropic,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum LogFormat {
+    /// 人が読むためのテキスト
+    Text,
+    /// 1行1オブジェクトのJSON
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// Markdown
+    Md,
+    /// 統計グラフ付きの単体HTML
+    Html,
+    /// タイトルページ・統計表付きのPDF（typst CLIが必要）
+    Pdf,
+    /// 検索条件・統計情報・サマリーをまとめたJSON
+    Json,
+}
+
+impl OutputFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Md => "md",
+            OutputFormat::Html => "html",
+            OutputFormat::Pdf => "pdf",
+            OutputFormat::Json => "json",
+        }
+    }
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> ExitCode {
+    match try_main().await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("Error: {error:?}");
+            ExitCode::from(Failure::of(&error).map_or(1, Failure::exit_code))
+        }
+    }
+}
+
+async fn try_main() -> Result<()> {
     dotenv::dotenv().ok();
     let mut args = Args::parse();
+    init_logging(args.verbose, args.log_format);
+    limits::set_concurrency(args.concurrency.into());
+    limits::set_timeout((args.timeout > 0).then(|| Duration::from_secs(args.timeout)));
+    if let Some(dir) = &args.record {
+        fixture::set_mode(fixture::Mode::Record(dir.clone()));
+    } else if let Some(dir) = &args.replay {
+        fixture::set_mode(fixture::Mode::Replay(dir.clone()));
+    }
+
+    let progress = Progress::new(args.quiet, args.ci);
+    let mut config = Config::load(args.config.as_deref()).context(Failure::Config)?;
+    if let Some(path) = &args.ca_cert {
+        config.http.ca_cert = Some(path.clone());
+    }
+    // モノレポは設定ファイルとCLI引数の両方を合わせる（変更ファイルの取得はargsで判定）
+    args.monorepo.append(&mut config.monorepo.repositories);
+    args.monorepo.sort();
+    args.monorepo.dedup();
+    config.monorepo.repositories = args.monorepo.clone();
+    // 四半期・半期は設定ファイルの年度の開始月で日付に直す
+    if let Some(fiscal) = args.quarter.or(args.half) {
+        let (since, until) = fiscal
+            .range(config.fiscal_year_start.unwrap_or(1))
+            .context(Failure::Config)?;
+        args.since = Some(since.to_string());
+        args.until = Some(until.to_string());
+    }
+    network::configure(&config.http).context(Failure::Config)?;
+    // リスクとフォローアップの項目は実績サマリー・週報・360度評価のプロンプトにだけ加える
+    if args.unshipped && matches!(args.mode, ModeArg::Promo | ModeArg::Resume) {
+        return Err(anyhow::anyhow!(
+            "--unshippedは--mode promo・resumeでは使えません（実績サマリー・週報・360度評価のみ）"
+        )
+        .context(Failure::Config));
+    }
+
+    match &args.command {
+        Some(Commands::Snapshot {
+            action: SnapshotAction::Save { path },
+        }) => {
+            let activity = fetch_activity(&args, &config, &progress)?;
+            Snapshot::new(activity).save(path)?;
+            progress.message(format!(
+                "スナップショットを保存しました: {}",
+                path.display()
+            ));
+            Ok(())
+        }
+        Some(Commands::Snapshot {
+            action: SnapshotAction::Load { path },
+        }) => {
+            let snapshot = Snapshot::load(path).context(Failure::Config)?;
+            progress.message(format!(
+                "スナップショットを読み込みました（{}件のPR、{}件のIssue、取得日時: {}）",
+                snapshot.activity.prs.len(),
+                snapshot.activity.issues.len(),
+                snapshot.fetched_at
+            ));
+            run(&args, &config, &progress, snapshot.activity).await
+        }
+        Some(Commands::Serve { cron }) => serve(&args, &config, &progress, cron).await,
+        Some(Commands::Mcp) => mcp::Server::new(&args, &config).run().await,
+        Some(Commands::Batch { action }) => run_batch(&args, &config, &progress, action).await,
+        Some(Commands::History { action }) => show_history(action).context(Failure::Config),
+        Some(Commands::Auth { action }) => run_auth(action).context(Failure::Config),
+        Some(Commands::Diff { old, new }) => diff_reports(old, new).context(Failure::Config),
+        Some(Commands::Log { journal }) => append_log(&args, &config, &progress, journal).await,
+        // 記録ファイルはprompt_optionsでプロンプトに含め、取得以降は通常の実行と同じ
+        Some(Commands::CompileJournal { .. }) => {
+            if args.per_repo {
+                return Err(anyhow::anyhow!(
+                    "compile-journalでは--per-repoは使えません（記録はリポジトリごとに分けられないため）"
+                )
+                .context(Failure::Config));
+            }
+            // 期間内の記録がなければ、GitHubから取得する前に中止
+            journal_entries(&args).context(Failure::Config)?;
+            let activity = if args.demo {
+                demo_activity(&args, &progress)
+            } else {
+                fetch_activity(&args, &config, &progress)?
+            };
+            run(&args, &config, &progress, activity).await
+        }
+        None if args.demo => {
+            let activity = demo_activity(&args, &progress);
+            run(&args, &config, &progress, activity).await
+        }
+        None if args.feedback.is_some() => {
+            let activity = last_activity(&args, &progress).context(Failure::Config)?;
+            run(&args, &config, &progress, activity).await
+        }
+        None => {
+            let activity = fetch_activity(&args, &config, &progress)?;
+            // --feedbackで生成し直せるよう、直近の取得結果として保存（失敗しても続ける）
+            if let Err(e) = save_last_activity(&activity) {
+                progress.warn(format!("警告: 取得結果を保存できません: {e:#}"));
+            }
+            run(&args, &config, &progress, activity).await
+        }
+    }
+}
 
-    // authorが指定されていない場合は、ghコマンドで現在のユーザーを取得
-    if args.author.is_none() {
-        let output = Command::new("gh")
-            .args(["api", "user", "--jq", ".login"])
-            .output()?;
+// スケジュールに従って取得・生成を繰り返す（失敗した回は警告して次の回を待つ）
+async fn serve(args: &Args, config: &Config, progress: &Progress, cron: &str) -> Result<()> {
+    let schedule = CronSchedule::parse(cron).context(Failure::Config)?;
+    if args.tui || args.show_prompts {
+        return Err(
+            anyhow::anyhow!("serveでは--tui・--show-promptsは使えません").context(Failure::Config),
+        );
+    }
+    llm_client(args, config, "サマリーの生成")?;
+    if !args.email.is_empty() && config.email.is_none() {
+        return Err(
+            anyhow::anyhow!("--emailには設定ファイルの[email]が必要です").context(Failure::Config),
+        );
+    }
 
-        if !output.status.success() {
-            anyhow::bail!(
-                "Failed to get current GitHub user: {}",
-                String::from_utf8_lossy(&output.stderr)
+    loop {
+        let now = chrono::Local::now();
+        let Some(next) = schedule.next_after(now) else {
+            return Err(
+                anyhow::anyhow!("次回の実行日時がありません: {cron}").context(Failure::Config)
             );
+        };
+        progress.message(format!("次回の実行: {}", next.format("%Y-%m-%d %H:%M")));
+        tokio::time::sleep((next - now).to_std().unwrap_or_default()).await;
+
+        // 出力先の{date}は実行日に置き換え、回ごとに別ファイルに書き出す
+        let mut scheduled = args.clone();
+        // 期間の指定がなければ、前回の実行日から今回の実行日の前日までを対象にする
+        if args.since.is_none()
+            && args.until.is_none()
+            && let Some(previous) = schedule.previous_before(next)
+        {
+            let since = previous.date_naive();
+            let until = (next.date_naive() - chrono::Duration::days(1)).max(since);
+            scheduled.since = Some(since.to_string());
+            scheduled.until = Some(until.to_string());
         }
+        scheduled.output = args.output.as_ref().map(|path| {
+            PathBuf::from(
+                path.to_string_lossy()
+                    .replace("{date}", &next.date_naive().to_string()),
+            )
+        });
+        tracing::info!(scheduled_at = %next, "定期実行を開始");
+        let result = match fetch_activity(&scheduled, config, progress) {
+            Ok(activity) => run(&scheduled, config, progress, activity).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            progress.warn(format!("定期実行に失敗しました: {e:#}"));
+        }
+    }
+}
 
-        let username = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        println!("現在のGitHubユーザー: {username}");
-        args.author = Some(username);
+// 前回の記録の翌日から昨日までの活動をまとめて記録ファイルに追記する
+async fn append_log(
+    args: &Args,
+    config: &Config,
+    progress: &Progress,
+    journal_path: &std::path::Path,
+) -> Result<()> {
+    if args.tui || args.show_prompts {
+        return Err(
+            anyhow::anyhow!("logでは--tui・--show-promptsは使えません").context(Failure::Config)
+        );
+    }
+    let client = llm_client(args, config, "実績の記録")?;
+
+    // 当日の活動は次回に回し、記録済みの日と重ならないよう前回の記録の翌日から取得
+    let mut target = args.clone();
+    target.mode = ModeArg::Weekly;
+    let last = journal::last_until(journal_path).context(Failure::Config)?;
+    if args.since.is_none() {
+        target.since = last.map(|date| (date + chrono::Duration::days(1)).to_string());
+    }
+    if args.until.is_none() {
+        target.until = Some((today(args) - chrono::Duration::days(1)).to_string());
+    }
+    let (since, until) = period(&target);
+    if since > until {
+        progress.message(format!("{until}まで記録済みです"));
+        return Ok(());
+    }
+    target.since = Some(since);
+    target.until = Some(until.clone());
+    let until = chrono::NaiveDate::parse_from_str(&until, "%Y-%m-%d")
+        .with_context(|| format!("期間の終了日を解釈できません: {until}"))
+        .context(Failure::Config)?;
+
+    let activity = if args.demo {
+        demo_activity(&target, progress)
+    } else {
+        fetch_activity(&target, config, progress)?
+    };
+    let (query, prs, issues, options) = prepare(&target, config, progress, activity).await?;
+    if prs.is_empty() && issues.is_empty() {
+        progress.message(format!(
+            "{}に新しい活動はありません（次回もこの期間から記録します）",
+            query.period()
+        ));
+        return Ok(());
+    }
+
+    let spinner = progress.spinner(format!("{}で新しい活動をまとめています...", client.model()));
+    let summary = llm::generate_summary(&client, &query, &prs, &issues, &options)
+        .await
+        .context(Failure::Llm)?;
+    spinner.finish_and_clear();
+
+    journal::append(
+        journal_path,
+        &journal::entry(&query.period(), until, &summary),
+    )
+    .context(Failure::Config)?;
+    progress.message(format!(
+        "{}の実績を記録しました: {}",
+        query.period(),
+        journal_path.display()
+    ));
+    Ok(())
+}
+
+// Batch APIへの送信・状況の確認・結果の回収
+async fn run_batch(
+    args: &Args,
+    config: &Config,
+    progress: &Progress,
+    action: &BatchAction,
+) -> Result<()> {
+    let client = BatchClient::new(openai_api_key("Batch APIの利用")?);
+    let dir = batch::default_dir()
+        .context("バッチの保存先のディレクトリがありません")
+        .context(Failure::Config)?;
+    match action {
+        BatchAction::Submit { authors } => {
+            let mut requests = Vec::new();
+            for author in authors {
+                progress.message(format!("\n{author}のデータを準備中..."));
+                let mut target = args.clone();
+                target.author = Some(author.clone());
+                let activity = fetch_activity(&target, config, progress)?;
+                let (query, prs, issues, options) =
+                    prepare(&target, config, progress, activity).await?;
+                requests.push(batch::Request {
+                    prompt: report::build_prompt(&query, &prs, &issues, &options)?,
+                    system_prompt: options.tone.system_prompt().to_string(),
+                    query,
+                });
+            }
+            let job = client
+                .submit(&batch_chat_client(args, config)?, &requests)
+                .await
+                .context(Failure::Llm)?;
+            job.save(&dir)?;
+            progress.message(format!(
+                "{}件のリクエストをバッチ{}として送信しました（easyhyoka batch status {}で確認できます）",
+                job.items.len(),
+                job.batch_id,
+                job.batch_id
+            ));
+        }
+        BatchAction::Status { id } => {
+            let jobs = match id {
+                Some(id) => vec![batch::Job::load(&dir, id).context(Failure::Config)?],
+                None => batch::Job::list(&dir)?,
+            };
+            for job in jobs {
+                let status = client.status(&job.batch_id).await.context(Failure::Llm)?;
+                let counts = &status.request_counts;
+                println!(
+                    "{}  {}  {}/{}件完了（失敗{}件）  送信: {}",
+                    job.batch_id,
+                    status.status,
+                    counts.completed,
+                    counts.total,
+                    counts.failed,
+                    job.submitted_at
+                );
+            }
+        }
+        BatchAction::Collect { id, dir: output } => {
+            let job = batch::Job::load(&dir, id).context(Failure::Config)?;
+            let results = client.collect(&job).await.context(Failure::Llm)?;
+            std::fs::create_dir_all(output)
+                .with_context(|| format!("書き出し先を作成できません: {}", output.display()))?;
+            for (item, result) in results {
+                let query = &item.query;
+                match result {
+                    Ok(summary) => {
+                        let path =
+                            output.join(format!("{}_{}.md", query.author, query.file_period('_')));
+                        std::fs::write(&path, summary)
+                            .with_context(|| format!("書き込めません: {}", path.display()))?;
+                        progress.message(format!("  {}: {}", query.author, path.display()));
+                    }
+                    Err(e) => progress.warn(format!(
+                        "警告: {}のサマリーを取得できません: {e:#}",
+                        query.author
+                    )),
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// キーチェーンへのAPIキーの保存・削除・参照元の表示
+fn run_auth(action: &AuthAction) -> Result<()> {
+    let (provider, key) = match action {
+        AuthAction::SetOpenaiKey { key } => (Provider::OpenAi, key),
+        AuthAction::SetAnthropicKey { key } => (Provider::Anthropic, key),
+        AuthAction::Delete { key } => {
+            let name = Provider::from(*key).api_key_var();
+            if keychain::delete(name)? {
+                eprintln!("キーチェーンから{name}を削除しました");
+            } else {
+                eprintln!("{name}はキーチェーンに保存されていません");
+            }
+            return Ok(());
+        }
+        AuthAction::Status => {
+            for provider in [Provider::OpenAi, Provider::Anthropic] {
+                let name = provider.api_key_var();
+                let source = if keychain::get(name).ok().flatten().is_some() {
+                    "キーチェーン"
+                } else if env::var(name).is_ok() {
+                    "環境変数"
+                } else {
+                    "未設定"
+                };
+                println!("{name}: {source}");
+            }
+            return Ok(());
+        }
+    };
+    let name = provider.api_key_var();
+    // シェルの履歴に残らないよう、引数を省略した場合は標準入力から読む
+    let value = match key {
+        Some(key) => key.clone(),
+        None => {
+            eprint!("{name}を入力してください: ");
+            let mut line = String::new();
+            std::io::stdin().read_line(&mut line)?;
+            line
+        }
+    };
+    let value = value.trim();
+    anyhow::ensure!(!value.is_empty(), "APIキーが空です");
+    keychain::set(name, value)?;
+    eprintln!("{name}をキーチェーンに保存しました");
+    Ok(())
+}
+
+// 履歴の一覧・表示・差分を標準出力に出す
+fn show_history(action: &HistoryAction) -> Result<()> {
+    let dir = History::default_dir().context("履歴の保存先のディレクトリがありません")?;
+    let history = History::new(dir);
+    match action {
+        HistoryAction::List => {
+            for entry in history.list()? {
+                let query = &entry.query;
+                println!(
+                    "{}  v{}  {} {}  {}  {}",
+                    entry.id,
+                    entry.version,
+                    query.author,
+                    query.period(),
+                    query.owner.as_deref().unwrap_or("-"),
+                    entry.model
+                );
+            }
+        }
+        HistoryAction::Show { id } => {
+            let entry = history.get(id)?;
+            println!("ID: {}（v{}）", entry.id, entry.version);
+            println!("生成日時: {}", entry.created_at);
+            println!("対象: {} {}", entry.query.author, entry.query.period());
+            println!("モデル: {}", entry.model);
+            println!("プロンプトのハッシュ: {}", entry.prompt_hash);
+            println!("引数: {}", entry.arguments.join(" "));
+            println!("\n{}", entry.summary);
+        }
+        HistoryAction::Diff { old, new } => {
+            let old = history.get(old)?;
+            let new = match new {
+                Some(id) => history.get(id)?,
+                None => history.latest_of(&old)?,
+            };
+            println!("--- {}（v{}、{}）", old.id, old.version, old.model);
+            println!("+++ {}（v{}、{}）", new.id, new.version, new.model);
+            if old.prompt_hash != new.prompt_hash {
+                println!(
+                    "（プロンプトが異なります: {} → {}）",
+                    old.prompt_hash, new.prompt_hash
+                );
+            }
+            print!("{}", history::diff(&old.summary, &new.summary));
+        }
+    }
+    Ok(())
+}
+
+// 2つのレポートの実績の違いを標準出力に出す
+fn diff_reports(old: &str, new: &str) -> Result<()> {
+    let diff = history::compare_achievements(&read_report(old)?, &read_report(new)?);
+    if diff.is_empty() {
+        println!("実績の違いはありません");
+        return Ok(());
+    }
+    if !diff.added.is_empty() {
+        println!("## 追加された実績（{}件）", diff.added.len());
+        for item in &diff.added {
+            println!("+ {item}");
+        }
+        println!();
+    }
+    if !diff.removed.is_empty() {
+        println!("## 削除された実績（{}件）", diff.removed.len());
+        for item in &diff.removed {
+            println!("- {item}");
+        }
+        println!();
+    }
+    if !diff.reworded.is_empty() {
+        println!("## 書き換えられた実績（{}件）", diff.reworded.len());
+        for (old, new) in &diff.reworded {
+            println!("- {old}");
+            println!("+ {new}");
+        }
+    }
+    Ok(())
+}
+
+// レポートのサマリー本文（ファイルがなければ履歴のIDとして探す、JSONはsummaryを取り出す）
+fn read_report(source: &str) -> Result<String> {
+    let path = std::path::Path::new(source);
+    if !path.exists() {
+        let dir = History::default_dir()
+            .with_context(|| format!("レポートのファイルが見つかりません: {source}"))?;
+        return Ok(History::new(dir).get(source)?.summary);
+    }
+    let text = read_text(path, "レポート")?;
+    if path.extension().is_some_and(|ext| ext == "json") {
+        let json: serde_json::Value = serde_json::from_str(&text)
+            .with_context(|| format!("レポートのJSONが不正です: {source}"))?;
+        return json["summary"]
+            .as_str()
+            .map(str::to_string)
+            .with_context(|| format!("レポートのJSONにsummaryがありません: {source}"));
+    }
+    Ok(text)
+}
+
+// ログを標準エラー出力に出す（-vでinfo、-vvでdebug、RUST_LOGがあればそちらを優先）
+fn init_logging(verbose: u8, format: LogFormat) {
+    let level = match verbose {
+        0 => "warn",
+        1 => "info",
+        _ => "debug",
+    };
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(format!("warn,easy_hyoka={level}")));
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr);
+    match format {
+        LogFormat::Text => builder.init(),
+        LogFormat::Json => builder.json().init(),
+    }
+}
+
+// GitHubからPR/Issueとコメントを取得
+fn fetch_activity(args: &Args, config: &Config, progress: &Progress) -> Result<Activity> {
+    fetch_from_github(args, config, progress).context(Failure::GitHub)
+}
+
+// 合成データ（--demo）
+fn demo_activity(args: &Args, progress: &Progress) -> Activity {
+    let (since, until) = period(args);
+    let query = SearchQuery {
+        owner: None,
+        org_only: false,
+        author: args
+            .author
+            .clone()
+            .unwrap_or_else(|| demo::AUTHOR.to_string()),
+        since,
+        until,
+        role: args.role.into(),
+        tz: args.tz,
+        label: args.period_label.clone(),
+    };
+    progress.message(format!(
+        "デモ: 架空のOrganization {}の合成データを使います（GitHubには接続しません）",
+        demo::OWNER
+    ));
+    demo::activity(query)
+}
+
+fn save_last_activity(activity: &Activity) -> Result<()> {
+    let Some(path) = Snapshot::last_path() else {
+        return Ok(());
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("保存先を作成できません: {}", dir.display()))?;
+    }
+    Snapshot::new(activity.clone()).save(&path)
+}
+
+// 直近に取得したデータを読み込む（--feedback用）
+fn last_activity(args: &Args, progress: &Progress) -> Result<Activity> {
+    let path = Snapshot::last_path()
+        .filter(|path| path.exists())
+        .context("--feedbackで使う取得済みのデータがありません。先に指示なしで実行してください")?;
+    let snapshot = Snapshot::load(&path)?;
+    let query = &snapshot.activity.query;
+    // 指定された対象者・期間が取得済みのデータと違う場合は、別の人・期間で生成し直さないよう中止
+    for (name, given, saved) in [
+        ("--author", &args.author, &query.author),
+        ("--since", &args.since, &query.since),
+        ("--until", &args.until, &query.until),
+    ] {
+        if let Some(given) = given {
+            anyhow::ensure!(
+                given == saved,
+                "{name} {given}は取得済みのデータ（{saved}）と異なります。先に指示なしで実行して取得し直してください"
+            );
+        }
+    }
+    progress.message(format!(
+        "{}に取得したデータ（{} {}〜{}、{}件のPR、{}件のIssue）からサマリーを生成し直します",
+        snapshot.fetched_at,
+        query.author,
+        query.since,
+        query.until,
+        snapshot.activity.prs.len(),
+        snapshot.activity.issues.len()
+    ));
+    Ok(snapshot.activity)
+}
+
+fn fetch_from_github(args: &Args, config: &Config, progress: &Progress) -> Result<Activity> {
+    let source = GitHub;
+    source.ensure_ready()?;
+
+    // authorが指定されていない場合は、取得元の認証中のユーザーを使う
+    let author = match args.author.clone() {
+        Some(author) => author,
+        None => {
+            let username = source.current_user()?;
+            progress.message(format!("現在の{}ユーザー: {username}", source.name()));
+            username
+        }
+    };
+
+    let (since, until) = period(args);
+    let query = SearchQuery {
+        owner: args.owner.clone(),
+        org_only: args.org_only,
+        author,
+        since,
+        until,
+        role: args.role.into(),
+        tz: args.tz,
+        label: args.period_label.clone(),
+    };
+
+    // 前回中断した取得があれば続きから再開（--no-resume指定時は最初から）
+    let mut checkpoint = Checkpoint::load(&query);
+    if args.no_resume {
+        checkpoint.reset();
+    } else if checkpoint.is_resumed() {
+        progress.message("前回中断した取得を途中から再開します（最初から取得するには--no-resume）");
     }
 
-    println!("GitHub PR/Issuesを取得中...");
+    progress.message(format!("{} PR/Issuesを取得中...", source.name()));
 
     // PR取得
-    let prs = fetch_prs(&args)?;
-    println!("  {} 件のPRを取得しました", prs.len());
+    let mut prs = match checkpoint.prs.take() {
+        Some(prs) => prs,
+        None => {
+            let spinner = progress.spinner("PRを検索中...");
+            let mut prs = source.fetch_prs(&query)?;
+            spinner.finish_and_clear();
+            warn_if_limit_reached(progress, prs.len(), "PR");
+
+            // 各PRのコメントを取得（コメントの多い5件のPRのみ）
+            let targets = source::most_discussed(prs.iter().map(|pr| pr.comments_count), 5);
+            let bar = progress.bar(targets.len(), "議論の多いPRのコメントを取得中");
+            source::attach_pr_comments(&source, &mut prs, 5, || bar.inc(1));
+            bar.finish_and_clear();
+            checkpoint.prs = Some(prs.clone());
+            checkpoint.save()?;
+            prs
+        }
+    };
+    // 各PRのレビューを取得（--reviews指定時のみ、全PRが対象）
+    if args.reviews {
+        let bar = progress.bar(prs.len(), "PRが受けたレビューを取得中");
+        attach_resumable(&mut checkpoint, "reviews", &mut prs, &bar, |prs| {
+            source::attach_pr_reviews(&source, prs, &query.author, || bar.inc(1))
+        })?;
+        bar.finish_and_clear();
+        let bar = progress.bar(prs.len(), "レビューしたPRのレビュースレッドを取得中");
+        attach_resumable(&mut checkpoint, "review_threads", &mut prs, &bar, |prs| {
+            github::attach_review_threads(prs, &query.author, || bar.inc(1))
+        })?;
+        bar.finish_and_clear();
+        let bar = progress.bar(prs.len(), "PRのレビュー依頼先を取得中");
+        attach_resumable(&mut checkpoint, "review_requests", &mut prs, &bar, |prs| {
+            github::attach_review_requests(prs, || bar.inc(1))
+        })?;
+        bar.finish_and_clear();
+    }
+    // 各PRが解決したIssueを取得（--resolved-issues指定時のみ）
+    if args.resolved_issues {
+        let bar = progress.bar(prs.len(), "PRが解決したIssueを取得中");
+        attach_resumable(&mut checkpoint, "closing_issues", &mut prs, &bar, |prs| {
+            github::attach_closing_issues(prs.iter_mut(), &query.author, || bar.inc(1))
+        })?;
+        bar.finish_and_clear();
+    }
+    // 各PRの変更ファイルを取得（--tech-stack・--docs・--monorepo指定時のみ）
+    if args.tech_stack || args.docs || !args.monorepo.is_empty() {
+        let bar = progress.bar(prs.len(), "PRの変更ファイルを取得中");
+        attach_resumable(&mut checkpoint, "files", &mut prs, &bar, |prs| {
+            github::attach_pr_files(prs, || bar.inc(1))
+        })?;
+        bar.finish_and_clear();
+    }
+    // マージ済みPRのCI結果とデプロイを取得（--ship-signals指定時のみ）
+    if args.ship_signals {
+        let bar = progress.bar(prs.len(), "マージ済みPRのCI結果とデプロイを取得中");
+        attach_resumable(&mut checkpoint, "ship_signals", &mut prs, &bar, |prs| {
+            let skipped = prs.iter().filter(|pr| pr.state != "merged").count();
+            github::attach_ship_signals(prs, || bar.inc(1));
+            bar.inc(skipped as u64);
+        })?;
+        bar.finish_and_clear();
+    }
+    // 各PRのマイルストーンとプロジェクトを取得（--roadmap指定時のみ）
+    if args.roadmap {
+        let bar = progress.bar(prs.len(), "PRのマイルストーンとプロジェクトを取得中");
+        attach_resumable(&mut checkpoint, "roadmap", &mut prs, &bar, |prs| {
+            github::attach_pr_roadmap(prs, || bar.inc(1))
+        })?;
+        bar.finish_and_clear();
+    }
+    // 各PRへのリアクションを取得（--reactions指定時のみ）
+    if args.reactions {
+        let bar = progress.bar(prs.len(), "PRへのリアクションを取得中");
+        attach_resumable(&mut checkpoint, "reactions", &mut prs, &bar, |prs| {
+            github::attach_pr_reactions(prs, || bar.inc(1))
+        })?;
+        bar.finish_and_clear();
+    }
+    // 主要なPRが解決したIssueを取得（--linked-issues指定時のみ、--resolved-issuesで取得済みの場合は不要）
+    if args.linked_issues && !args.resolved_issues {
+        let highlighted = highlighted_urls(&prs, args, config)?;
+        let bar = progress.bar(prs.len(), "主要なPRが解決したIssueを取得中");
+        attach_resumable(&mut checkpoint, "linked_issues", &mut prs, &bar, |prs| {
+            let skipped = prs
+                .iter()
+                .filter(|pr| !highlighted.contains(&pr.url))
+                .count();
+            github::attach_closing_issues(
+                prs.iter_mut().filter(|pr| highlighted.contains(&pr.url)),
+                &query.author,
+                || bar.inc(1),
+            );
+            bar.inc(skipped as u64);
+        })?;
+        bar.finish_and_clear();
+    }
+    // 主要なPRのコミットを取得（--commits指定時のみ）
+    if args.commits {
+        let highlighted = highlighted_urls(&prs, args, config)?;
+        let bar = progress.bar(prs.len(), "主要なPRのコミットを取得中");
+        attach_resumable(&mut checkpoint, "commits", &mut prs, &bar, |prs| {
+            let skipped = prs
+                .iter()
+                .filter(|pr| !highlighted.contains(&pr.url))
+                .count();
+            github::attach_pr_commits(
+                prs.iter_mut().filter(|pr| highlighted.contains(&pr.url)),
+                || bar.inc(1),
+            );
+            bar.inc(skipped as u64);
+        })?;
+        bar.finish_and_clear();
+    }
+    progress.message(format!("  {} 件のPRを取得しました", prs.len()));
 
     // Issues取得
-    let issues = fetch_issues(&args)?;
-    println!("  {} 件のIssuesを取得しました", issues.len());
+    let issues = match checkpoint.issues.clone() {
+        Some(issues) => issues,
+        None => {
+            let spinner = progress.spinner("Issueを検索中...");
+            let mut issues = source.fetch_issues(&query)?;
+            spinner.finish_and_clear();
+            warn_if_limit_reached(progress, issues.len(), "Issue");
+
+            // 各Issueのコメントを取得（コメントの多い5件のみ）
+            let targets =
+                source::most_discussed(issues.iter().map(|issue| issue.comments_count), 5);
+            let bar = progress.bar(targets.len(), "議論の多いIssueのコメントを取得中");
+            source::attach_issue_comments(&source, &mut issues, 5, || bar.inc(1));
+            bar.finish_and_clear();
+
+            // 各Issueのマイルストーンとプロジェクトを取得（--roadmap指定時のみ）
+            if args.roadmap {
+                let bar = progress.bar(issues.len(), "Issueのマイルストーンとプロジェクトを取得中");
+                github::attach_issue_roadmap(&mut issues, || bar.inc(1));
+                bar.finish_and_clear();
+            }
+            // 各Issueへのリアクションを取得（--reactions指定時のみ）
+            if args.reactions {
+                let bar = progress.bar(issues.len(), "Issueへのリアクションを取得中");
+                github::attach_issue_reactions(&mut issues, || bar.inc(1));
+                bar.finish_and_clear();
+            }
+            checkpoint.issues = Some(issues.clone());
+            checkpoint.save()?;
+            issues
+        }
+    };
+    progress.message(format!("  {} 件のIssuesを取得しました", issues.len()));
+
+    // Discussions取得（--discussions指定時のみ）
+    let discussions = if args.discussions {
+        let discussions = match checkpoint.discussions.clone() {
+            Some(discussions) => discussions,
+            None => {
+                let spinner = progress.spinner("Discussionsを検索中...");
+                let discussions = github::fetch_discussions(&query)?;
+                spinner.finish_and_clear();
+                checkpoint.discussions = Some(discussions.clone());
+                checkpoint.save()?;
+                discussions
+            }
+        };
+        progress.message(format!(
+            "  {} 件のDiscussionsを取得しました",
+            discussions.len()
+        ));
+        discussions
+    } else {
+        Vec::new()
+    };
+
+    // リリース取得（--releases指定時のみ、PR/Issueのあるリポジトリが対象）
+    let releases = if args.releases {
+        let releases = match checkpoint.releases.clone() {
+            Some(releases) => releases,
+            None => {
+                let mut repositories: Vec<String> = prs
+                    .iter()
+                    .map(|pr| pr.repository.name_with_owner.clone())
+                    .chain(
+                        issues
+                            .iter()
+                            .map(|issue| issue.repository.name_with_owner.clone()),
+                    )
+                    .collect();
+                repositories.sort();
+                repositories.dedup();
+                let bar = progress.bar(repositories.len(), "リリースを取得中");
+                let releases = github::fetch_releases(&query, &repositories, || bar.inc(1));
+                bar.finish_and_clear();
+                checkpoint.releases = Some(releases.clone());
+                checkpoint.save()?;
+                releases
+            }
+        };
+        progress.message(format!("  {} 件のリリースを取得しました", releases.len()));
+        releases
+    } else {
+        Vec::new()
+    };
+
+    // コミュニティへの貢献の取得（--community指定時のみ）
+    let community = if args.community {
+        let community = match checkpoint.community.clone() {
+            Some(community) => community,
+            None => {
+                let spinner = progress.spinner("社外のOSSへのPRと公開Gistを検索中...");
+                let community = github::fetch_community(&query)?;
+                spinner.finish_and_clear();
+                checkpoint.community = Some(community.clone());
+                checkpoint.save()?;
+                community
+            }
+        };
+        progress.message(format!(
+            "  社外のOSSへのPR {} 件、公開Gist {} 件を取得しました",
+            community.oss_prs.len(),
+            community.gists.len()
+        ));
+        community
+    } else {
+        Community::default()
+    };
+
+    // Organizationでの役割と所属チームの取得（--membership指定時のみ）
+    let membership = if args.membership {
+        let membership = match checkpoint.membership.clone() {
+            Some(membership) => membership,
+            None => {
+                let spinner = progress.spinner("所属チームを取得中...");
+                let membership = github::fetch_membership(&query)?;
+                spinner.finish_and_clear();
+                checkpoint.membership = Some(membership.clone());
+                checkpoint.save()?;
+                membership
+            }
+        };
+        progress.message(format!(
+            "  所属チーム {} 件を取得しました",
+            membership.teams.len()
+        ));
+        membership
+    } else {
+        Membership::default()
+    };
+
+    // 他のメンバーのIssueでのトリアージの取得（--triage指定時のみ）
+    let triage = if args.triage {
+        let triage = match checkpoint.triage.clone() {
+            Some(triage) => triage,
+            None => {
+                let spinner = progress.spinner("トリアージしたIssueを検索中...");
+                let triage = github::fetch_triage(&query, || spinner.tick())?;
+                spinner.finish_and_clear();
+                checkpoint.triage = Some(triage.clone());
+                checkpoint.save()?;
+                triage
+            }
+        };
+        progress.message(format!(
+            "  トリアージしたIssue {} 件を取得しました",
+            triage.len()
+        ));
+        triage
+    } else {
+        Vec::new()
+    };
+
+    // メンションと他のリポジトリからの参照の取得（--mentions指定時のみ）
+    let mentions = if args.mentions {
+        let mentions = match checkpoint.mentions.clone() {
+            Some(mentions) => mentions,
+            None => {
+                let spinner = progress.spinner("メンションを検索中...");
+                let mentioned_in = github::fetch_mentions(&query)?;
+                spinner.finish_and_clear();
+                let bar = progress.bar(prs.len(), "他のリポジトリからの参照を取得中");
+                let cross_references = github::fetch_cross_references(&query, &prs, || bar.inc(1));
+                bar.finish_and_clear();
+                let mentions = Mentions {
+                    mentioned_in,
+                    cross_references,
+                };
+                checkpoint.mentions = Some(mentions.clone());
+                checkpoint.save()?;
+                mentions
+            }
+        };
+        progress.message(format!(
+            "  メンション {} 件、他のリポジトリからの参照 {} 件を取得しました",
+            mentions.mentioned_in.len(),
+            mentions.cross_references.len()
+        ));
+        mentions
+    } else {
+        Mentions::default()
+    };
+
+    // 取得が完了したので再開用の状態は不要
+    checkpoint.remove();
+
+    let mut activity = Activity {
+        query,
+        prs,
+        issues,
+        discussions,
+        releases,
+        community,
+        membership,
+        triage,
+        mentions,
+    };
+    activity.localize_timestamps();
+    Ok(activity)
+}
+
+// 再開用の状態を保存する間隔（PR数）
+const CHECKPOINT_INTERVAL: usize = 20;
+
+// PRごとの追加取得を、一定件数ごとに進み具合を保存しながら行う（取得済みの分は飛ばす）
+fn attach_resumable(
+    checkpoint: &mut Checkpoint,
+    step: &str,
+    prs: &mut [PullRequest],
+    bar: &ProgressBar,
+    mut attach: impl FnMut(&mut [PullRequest]),
+) -> Result<()> {
+    let mut done = checkpoint.progress(step).min(prs.len());
+    bar.inc(done as u64);
+    while done < prs.len() {
+        let end = (done + CHECKPOINT_INTERVAL).min(prs.len());
+        attach(&mut prs[done..end]);
+        done = end;
+        checkpoint.record(step, done, prs)?;
+    }
+    Ok(())
+}
+
+// 本文付きで送る主要なPR（--highlights指定時は上位N件、それ以外は全件）のURL
+fn highlighted_urls(prs: &[PullRequest], args: &Args, config: &Config) -> Result<HashSet<String>> {
+    let targets = match args.highlights {
+        Some(n) => scoring_weights(args, config)?.select_top(prs, n),
+        None => (0..prs.len()).collect(),
+    };
+    Ok(targets.into_iter().map(|i| prs[i].url.clone()).collect())
+}
+
+// 取得済みのデータからサマリーを生成し、出力・配信する
+async fn run(
+    args: &Args,
+    config: &Config,
+    progress: &Progress,
+    mut activity: Activity,
+) -> Result<()> {
+    // 保存済みのデータ（--feedback・snapshot load）でも期間の呼び名は指定を優先
+    if let Some(label) = &args.period_label {
+        activity.query.label = Some(label.clone());
+    }
+    let (query, prs, issues, options) = prepare(args, config, progress, activity).await?;
+
+    // tuiが指定されている場合は、TUI上で選択・生成を行う
+    if args.tui {
+        let client = llm_client(args, config, "サマリーの生成").ok();
+        return tui::run(query, prs, issues, client, args.per_repo, options).await;
+    }
 
     // show_promptsが指定されている場合は、プロンプトを表示して終了
     if args.show_prompts {
-        println!("\nOpenAIに送信するプロンプトを生成中...");
-        generate_and_show_prompt(&prs, &issues, &args)?;
+        progress.message("\nOpenAIに送信するプロンプトを生成中...");
+        show_prompts(&query, &prs, &issues, args, &options)?;
         return Ok(());
     }
 
-    // OpenAI APIキーの確認（show_promptsがfalseの場合のみ）
-    let api_key = env::var("OPENAI_API_KEY").expect("OPENAI_API_KEY環境変数が設定されていません");
+    // APIキーの確認（show_promptsがfalseの場合のみ）。--modelsの候補だけで生成する場合は
+    // 主モデルを使わないため、統合・講評・ファクトチェックなどを指定したときだけ確認する
+    let needs_primary = args.models.is_empty()
+        || args.merge_models
+        || args.refine
+        || args.fact_check
+        || args.chat
+        || args.evidence
+        || args.bilingual;
+    let mut primary = if needs_primary {
+        Some(llm_client(args, config, "サマリーの生成")?)
+    } else {
+        None
+    };
+
+    // データを整形してLLMに送信（--models指定時は複数のモデルで並行して生成）
+    let (summary, produced_by) = if args.models.is_empty() {
+        let client = primary_client(&mut primary, args, config)?;
+        let primary_model = client.model().to_string();
+        progress.message(format!("\n{primary_model}で実績サマリーを生成中..."));
+        let spinner = progress.spinner("サマリーを生成中...");
+        let summary = if args.per_repo {
+            llm::generate_per_repo_summary(client, &query, &prs, &issues, &options, |target| {
+                spinner.set_message(format!("{target} のサマリーを生成中..."));
+            })
+            .await
+            .context(Failure::Llm)?
+        } else {
+            llm::generate_summary(client, &query, &prs, &issues, &options)
+                .await
+                .context(Failure::Llm)?
+        };
+        spinner.finish_and_clear();
+
+        if client.model() != primary_model {
+            progress.warn(format!(
+                "警告: {primary_model}の呼び出しに失敗したため、{}で生成しました",
+                client.model()
+            ));
+        }
+        (summary, client.model().to_string())
+    } else {
+        let prompt = report::build_prompt(&query, &prs, &issues, &options)?;
+        let candidates = generate_with_models(args, config, progress, &options, &prompt).await?;
+        let models = candidates
+            .iter()
+            .map(|(model, _)| model.as_str())
+            .collect::<Vec<_>>()
+            .join(",");
+        if args.merge_models && candidates.len() > 1 {
+            let client = primary_client(&mut primary, args, config)?;
+            let spinner = progress.spinner(format!("{}で候補を統合中...", client.model()));
+            let merged = llm::merge_summaries(client, &query, &prs, &issues, &options, &candidates)
+                .await
+                .context(Failure::Llm)?;
+            spinner.finish_and_clear();
+            (merged, format!("{models}→{}", client.model()))
+        } else if let [(_, summary)] = candidates.as_slice() {
+            // モデルが1つだけの場合は見出しを付けない
+            (summary.clone(), models)
+        } else {
+            (report::side_by_side(&candidates), models)
+        }
+    };
+
+    // 講評を反映した最終版の生成（--refine指定時のみ）
+    let summary = if args.refine {
+        let spinner = progress.spinner("下書きを講評して最終版を生成中...");
+        let client = primary_client(&mut primary, args, config)?;
+        let refined = llm::refine(client, &query, &prs, &issues, &options, &summary)
+            .await
+            .context(Failure::Llm)?;
+        spinner.finish_and_clear();
+        refined
+    } else {
+        summary
+    };
+
+    // ファクトチェック（--fact-check指定時のみ）
+    let summary = if args.fact_check {
+        let spinner = progress.spinner("サマリーをファクトチェック中...");
+        let client = primary_client(&mut primary, args, config)?;
+        let checked = llm::fact_check(client, &query, &prs, &issues, &options, &summary)
+            .await
+            .context(Failure::Llm)?;
+        spinner.finish_and_clear();
+        for url in report::unknown_citations(&checked, &prs, &issues) {
+            progress.warn(format!(
+                "警告: 活動データに含まれないURLが引用されています: {url}"
+            ));
+        }
+        checked
+    } else {
+        summary
+    };
+
+    // 対話による追加の質問・修正（--chat指定時のみ）
+    let summary = if args.chat {
+        let client = primary_client(&mut primary, args, config)?;
+        progress.message("\n生成されたサマリー");
+        progress.message("=====================================");
+        println!("{summary}");
+        let prompt = report::build_prompt(&query, &prs, &issues, &options)?;
+        chat::ChatSession::new(client, options.tone.system_prompt(), &prompt, &summary)
+            .run(summary)
+            .await
+            .context(Failure::Llm)?
+    } else {
+        summary
+    };
+
+    // 見出しごとの根拠の付録（--evidence指定時のみ、作成できなくても出力は続ける）
+    let summary = if args.evidence {
+        let spinner = progress.spinner("根拠となるPR/Issueを整理中...");
+        let client = primary_client(&mut primary, args, config)?;
+        let result = llm::evidence(client, &query, &prs, &issues, &options, &summary).await;
+        spinner.finish_and_clear();
+        match result {
+            Ok((evidence, unknown)) => {
+                for url in unknown {
+                    progress.warn(format!(
+                        "警告: 活動データに含まれないURLを根拠から除外しました: {url}"
+                    ));
+                }
+                let appendix = evidence::appendix(&evidence, &prs, &issues, options.language());
+                if appendix.is_empty() {
+                    summary
+                } else {
+                    format!("{}\n\n{appendix}", summary.trim_end())
+                }
+            }
+            Err(e) => {
+                progress.warn(format!("警告: 根拠の付録を作成できません: {e:#}"));
+                summary
+            }
+        }
+    } else {
+        summary
+    };
+
+    // もう一方の言語への翻訳（--bilingual指定時のみ）
+    let summary = if args.bilingual {
+        let language = options.language();
+        let spinner = progress.spinner("サマリーを翻訳中...");
+        let client = primary_client(&mut primary, args, config)?;
+        let translated = llm::translate(client, &summary, language.other())
+            .await
+            .context(Failure::Llm)?;
+        spinner.finish_and_clear();
+        report::bilingual(&summary, language, &translated)
+    } else {
+        summary
+    };
 
-    // データを整形してOpenAIに送信
-    println!("\nOpenAIで実績サマリーを生成中...");
-    let summary = generate_summary(&api_key, &prs, &issues, &args).await?;
+    // 履歴への保存（--no-history指定時は保存しない、失敗しても出力は続ける）
+    if !args.no_history
+        && let Some(dir) = History::default_dir()
+    {
+        let prompt = report::build_prompt(&query, &prs, &issues, &options)?;
+        let entry = history::Entry::new(
+            &query,
+            &produced_by,
+            &prompt,
+            env::args().skip(1).collect(),
+            &summary,
+        );
+        match History::new(dir).save(entry) {
+            Ok(entry) => progress.message(format!(
+                "履歴に保存しました: {}（v{}）",
+                entry.id, entry.version
+            )),
+            Err(e) => progress.warn(format!("警告: 履歴を保存できません: {e:#}")),
+        }
+    }
 
     // 結果を出力
-    println!("\n実績サマリー");
-    println!("=====================================");
-    println!("{summary}");
+    let stats = ActivityStats {
+        incidents: IncidentStats::compute(&prs, &issues, &options.incidents),
+        work_pattern: args.work_hours.then(|| WorkPattern::compute(&prs, &issues)),
+        prs_by_area: areas::breakdown(&prs, &options.monorepo),
+        weighted_merged_prs: options.weights.weighted_merged_prs(&prs),
+        ..ActivityStats::compute(&prs, &issues, &query.author)
+    };
+    let ctx = ReportContext {
+        query: &query,
+        stats: &stats,
+        summary: &summary,
+    };
+    let mut formats: Vec<OutputFormat> = Vec::new();
+    for format in &args.format {
+        if !formats.contains(format) {
+            formats.push(*format);
+        }
+    }
+    if formats.len() > 1 && args.output.is_none() {
+        return Err(anyhow::anyhow!(
+            "複数の--formatを指定する場合は--outputで出力先を指定してください"
+        )
+        .context(Failure::Config));
+    }
+    for &format in &formats {
+        // 複数の形式を書き出す場合は、拡張子を形式ごとに変える
+        let output = args.output.as_ref().map(|path| {
+            if formats.len() > 1 {
+                path.with_extension(format.extension())
+            } else {
+                path.clone()
+            }
+        });
+        write_report(&ctx, format, output.as_deref(), progress)?;
+    }
+
+    // クリップボードへのコピー（--copy指定時のみ、失敗しても出力済みのため警告にとどめる）
+    if args.copy {
+        match clipboard::copy(&summary) {
+            Ok(()) => progress.message("サマリーをクリップボードにコピーしました"),
+            Err(e) => progress.warn(format!("警告: {e:#}")),
+        }
+    }
+
+    // GitHub Actionsのジョブサマリーへの書き出し（--ci指定時のみ）
+    if args.ci
+        && let Some(path) = env::var_os("GITHUB_STEP_SUMMARY")
+    {
+        append_step_summary(&PathBuf::from(path), &render::title(&query), &summary)?;
+        progress.message("ジョブサマリーに書き出しました");
+    }
+
+    // Gistへのアップロード
+    if args.gist {
+        let filename = format!("easyhyoka-{}-{}.md", query.author, query.file_period('-'));
+        let title = render::title(&query);
+        let content = format!("# {title}\n\n{summary}\n");
+        github::ensure_gh().context(Failure::GitHub)?;
+        let url = github::create_gist(&filename, &title, &content, !args.secret)
+            .context(Failure::GitHub)?;
+        progress.message(format!("Gistにアップロードしました: {url}"));
+    }
+
+    // GitHubのIssueとして投稿（--post-issue指定時のみ）
+    if let Some(repo) = &args.post_issue {
+        let title = format!("Evaluation {} {}", query.author, query.period());
+        github::ensure_gh().context(Failure::GitHub)?;
+        let url = github::post_issue(repo, &title, &summary).context(Failure::GitHub)?;
+        progress.message(format!("Issueに投稿しました: {url}"));
+    }
+
+    // Slackへの投稿（CLI引数が設定ファイルより優先、--replayでは設定ファイルのURLにも投稿しない）
+    if args.replay.is_none()
+        && let Some(webhook) = args
+            .slack_webhook
+            .as_ref()
+            .or(config.slack_webhook.as_ref())
+    {
+        slack::post_summary(webhook, &render::title(&query), &summary).await?;
+        progress.message("Slackにサマリーを投稿しました");
+    }
+
+    // メールでの送信（--email指定時のみ）
+    if !args.email.is_empty() {
+        let email_config = config.email.as_ref().ok_or_else(|| {
+            anyhow::anyhow!("--emailには設定ファイルの[email]が必要です").context(Failure::Config)
+        })?;
+        let report = email::Report {
+            subject: &render::title(&query),
+            text: &summary,
+            html: &render::html(&ctx),
+        };
+        email::send_report(email_config, &args.email, report).await?;
+        progress.message(format!(
+            "{}にレポートをメールで送りました",
+            args.email.join(", ")
+        ));
+    }
+
+    // Googleドキュメントの作成（--google-docs指定時のみ）
+    if args.google_docs {
+        let url = gdocs::create_document(
+            &config.google_docs,
+            &render::title(&query),
+            &render::summary_html(&ctx),
+        )
+        .await?;
+        progress.message(format!("Googleドキュメントを作成しました: {url}"));
+    }
+
+    // Confluenceへの公開（--confluence-space指定時のみ、親ページはCLI引数が設定ファイルより優先）
+    if let Some(space) = &args.confluence_space {
+        let destination = confluence::Destination {
+            space,
+            parent: args
+                .confluence_parent
+                .as_deref()
+                .or(config.confluence.parent.as_deref()),
+        };
+        let url = confluence::publish(
+            &config.confluence,
+            &destination,
+            &render::title(&query),
+            &render::markdown_to_html(&summary),
+        )
+        .await?;
+        progress.message(format!("Confluenceにページを公開しました: {url}"));
+    }
 
     Ok(())
 }
 
-// TODO: 将来的な拡張案
-// - 1000件を超える場合は日付範囲を自動分割して再帰的に取得
-// - GraphQL APIを使用してカーソルベースのページネーションを実装
-// - 並列処理で複数の期間を同時に取得
-fn fetch_prs(args: &Args) -> Result<Vec<PullRequest>> {
-    let author = args
-        .author
-        .as_ref()
-        .expect("Author should be set at this point");
-    let output = Command::new("gh")
-        .args([
-            "search",
-            "prs",
-            &format!("--owner={}", args.owner),
-            &format!("--author={author}"),
-            &format!("--created={}..{}", args.since, args.until),
-            "--limit=1000",
-            "--json=number,title,body,createdAt,state,url,repository",
-        ])
-        .output()?;
-
-    if !output.status.success() {
-        anyhow::bail!(
-            "gh command failed: {}",
-            String::from_utf8_lossy(&output.stderr)
-        );
+// 取得済みのデータを整形し、プロンプトの構築に使うPR/Issueとオプションを返す
+//
+// トピックの付与・ノイズの除去・クラスタリングなど、サマリーの生成前の処理をまとめて行う
+async fn prepare(
+    args: &Args,
+    config: &Config,
+    progress: &Progress,
+    mut activity: Activity,
+) -> Result<(SearchQuery, Vec<PullRequest>, Vec<Issue>, PromptOptions)> {
+    let removed = activity.dedupe();
+    if removed > 0 {
+        progress.message(format!("重複していた{removed}件を除外しました"));
+    }
+    let Activity {
+        query,
+        mut prs,
+        mut issues,
+        discussions,
+        releases,
+        community,
+        membership,
+        triage,
+        mentions,
+    } = activity;
+    let mut options = prompt_options(args, config).context(Failure::Config)?;
+    options.discussions = discussions;
+    options.releases = releases;
+    options.community = community;
+    options.membership = membership;
+    options.triage = triage;
+    options.mentions = mentions;
+    options.availability = config
+        .people
+        .get(&query.author)
+        .and_then(|person| Availability::compute(&query, person));
+    let mut gaps: Vec<Gap> = args.gaps.iter().chain(&config.gaps).cloned().collect();
+    gaps.extend(gaps::detect(
+        &prs,
+        &issues,
+        &query.since,
+        &query.until,
+        &gaps,
+    ));
+    options.gaps = gaps;
+
+    // トピックの付与（キーワードのルール、--classify-topics指定時は残りをモデルで分類）
+    topics::tag_activity(&mut prs, &mut issues);
+    revert::mark_reverts(&mut prs);
+    if args.classify_topics {
+        let api_key = openai_api_key("--classify-topics")?;
+        let spinner = progress.spinner("トピックを分類中...");
+        topics::classify_untagged(&openai_client(args, api_key), &mut prs, &mut issues)
+            .await
+            .context(Failure::Llm)?;
+        spinner.finish_and_clear();
     }
 
-    let mut prs: Vec<PullRequest> = serde_json::from_slice(&output.stdout)?;
+    // 画像・HTMLなどのノイズの除去（--strip指定時、または設定ファイルの[strip]）
+    if let Some(rules) = strip_rules(args, config) {
+        let changed = strip::strip_activity(&rules, &mut prs, &mut issues);
+        progress.message(format!(
+            "  {changed} 件の本文・コメントから画像やHTMLなどを取り除きました"
+        ));
+    }
 
-    // 1000件に達した場合は警告
-    if prs.len() == 1000 {
-        println!(
-            "  警告: 検索結果が1000件の上限に達しました。すべてのPRが取得できていない可能性があります。"
-        );
-        println!("      より詳細な期間指定（--since, --until）で実行することをお勧めします。");
+    // PR本文のテンプレートの整理（--template-sections指定時のみ）
+    if args.template_sections {
+        let changed = template::extract_pr_sections(&mut prs);
+        progress.message(format!(
+            "  {changed} 件のPR本文からテンプレートの定型部分を除きました"
+        ));
     }
 
-    // 各PRのコメントを取得（最新の5件のPRのみ）
-    println!("  最新のPRのコメントを取得中...");
-    for pr in prs.iter_mut().take(5) {
-        if let Ok(comments) =
-            fetch_pr_comments(&args.owner, &pr.repository.name_with_owner, pr.number)
-        {
-            pr.comments = comments;
-        }
-    }
-
-    Ok(prs)
-}
-
-fn fetch_issues(args: &Args) -> Result<Vec<Issue>> {
-    let author = args
-        .author
-        .as_ref()
-        .expect("Author should be set at this point");
-    let output = Command::new("gh")
-        .args([
-            "search",
-            "issues",
-            &format!("--owner={}", args.owner),
-            &format!("--author={author}"),
-            &format!("--created={}..{}", args.since, args.until),
-            "--limit=1000",
-            "--json=number,title,body,createdAt,state,url,repository",
-        ])
-        .output()?;
-
-    if !output.status.success() {
-        anyhow::bail!(
-            "gh command failed: {}",
-            String::from_utf8_lossy(&output.stderr)
-        );
+    // 長い本文を短くする（--summarize-bodies指定時のみ）
+    if let Some(max_chars) = args.summarize_bodies {
+        let count = condense::count_long(&prs, &issues, max_chars);
+        if count > 0 {
+            let client = match args.condense {
+                CondenseArg::Llm => {
+                    Some(openai_client(args, openai_api_key("--summarize-bodies")?))
+                }
+                CondenseArg::Extract => None,
+            };
+            let bar = progress.bar(count, "長い本文を要約中");
+            let condensed = condense::condense_long(
+                client.as_ref(),
+                args.condense.into(),
+                &mut prs,
+                &mut issues,
+                max_chars,
+                || bar.inc(1),
+            )
+            .await
+            .context(Failure::Llm)?;
+            bar.finish_and_clear();
+            progress.message(format!(
+                "  {max_chars}文字を超える本文 {condensed} 件を短くしました"
+            ));
+        }
+    }
+
+    // PRのクラスタリング（--cluster指定時のみ）
+    if args.cluster {
+        let api_key = openai_api_key("--cluster")?;
+        let spinner = progress.spinner("PRをクラスタリング中...");
+        options.clusters = cluster::cluster_prs(&openai_client(args, api_key), &prs, args.clusters)
+            .await
+            .context(Failure::Llm)?;
+        spinner.finish_and_clear();
+        progress.message(format!(
+            "  {} 件のプロジェクト候補にまとめました",
+            options.clusters.len()
+        ));
     }
 
-    let mut issues: Vec<Issue> = serde_json::from_slice(&output.stdout)?;
+    // 期間中の推移の表示
+    if let Some(format) = args.timeline {
+        let mut buckets = stats::timeline(
+            &prs,
+            &issues,
+            &query.since,
+            &query.until,
+            options.granularity,
+        );
+        stats::mark_gaps(&mut buckets, options.granularity, &options.gaps);
+        match format {
+            TimelineFormat::Ascii => {
+                progress.message(format!("\n期間中の推移（{}）", options.granularity.label()));
+                print!("{}", render::timeline_ascii(&buckets));
+            }
+            TimelineFormat::Heatmap => {
+                let weeks = stats::heatmap(
+                    &stats::daily_counts(&prs, &issues),
+                    &query.since,
+                    &query.until,
+                );
+                progress.message("\n日別の活動（PR/Issue作成数）");
+                print!("{}", render::heatmap_ascii(&weeks));
+            }
+            TimelineFormat::Json => println!("{}", serde_json::to_string_pretty(&buckets)?),
+        }
+    }
 
-    // 1000件に達した場合は警告
-    if issues.len() == 1000 {
-        println!(
-            "  警告: 検索結果が1000件の上限に達しました。すべてのIssueが取得できていない可能性があります。"
+    // 曜日・時間帯別の作業の表示（--work-hours指定時のみ）
+    if args.work_hours {
+        progress.message("\n曜日・時間帯別の活動（PR/Issue作成数）");
+        print!(
+            "{}",
+            render::work_pattern_ascii(&WorkPattern::compute(&prs, &issues))
         );
-        println!("      より詳細な期間指定（--since, --until）で実行することをお勧めします。");
     }
 
-    // 各Issueのコメントを取得（最新の5件のみ）
-    println!("  最新のIssueのコメントを取得中...");
-    for issue in issues.iter_mut().take(5) {
-        if let Ok(comments) =
-            fetch_issue_comments(&args.owner, &issue.repository.name_with_owner, issue.number)
-        {
-            issue.comments = comments;
+    // CSVの書き出し
+    if let Some(dir) = &args.export_csv {
+        export::write_csv(dir, &prs, &issues)?;
+        progress.message(format!("  CSVを書き出しました: {}", dir.display()));
+    }
+    // JSON Linesの書き出し
+    if let Some(path) = &args.export_jsonl {
+        let count = export::write_jsonl(path, &query, &prs, &issues, &options)?;
+        progress.message(format!(
+            "  {count} 件をJSON Linesで書き出しました: {}",
+            path.display()
+        ));
+    }
+
+    // トークン数の上限に合わせてデータを削る（--max-prompt-tokens指定時のみ）
+    if let Some(max_tokens) = args.max_prompt_tokens.or(config.max_prompt_tokens) {
+        let strategies: Vec<Truncation> = args.truncate.iter().map(|&t| t.into()).collect();
+        let truncated = budget::fit(
+            max_tokens,
+            &strategies,
+            &query,
+            &mut prs,
+            &mut issues,
+            &options,
+        )?;
+        if !truncated.is_empty() {
+            progress.warn(format!(
+                "警告: プロンプトが上限（{max_tokens}トークン）を超えるため、データを省略しました: {}",
+                truncated.describe()
+            ));
+        }
+        options.truncated = truncated;
+        let tokens = budget::prompt_tokens(&query, &prs, &issues, &options)?;
+        if tokens > max_tokens {
+            progress.warn(format!(
+                "警告: データを省略してもプロンプトが上限を超えています（約{tokens}トークン）"
+            ));
         }
     }
 
-    Ok(issues)
+    Ok((query, prs, issues, options))
 }
 
-fn fetch_pr_comments(_owner: &str, repo: &str, pr_number: u32) -> Result<Vec<Comment>> {
-    let output = Command::new("gh")
-        .args([
-            "api",
-            &format!("repos/{repo}/pulls/{pr_number}/comments"),
-            "--jq",
-            ".[] | {author: {login: .user.login}, body: .body, createdAt: .created_at}",
-        ])
-        .output()?;
+// GitHub Actionsのジョブサマリー（Markdown）に追記
+fn append_step_summary(path: &std::path::Path, title: &str, summary: &str) -> Result<()> {
+    use std::io::Write;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("ジョブサマリーを書き込めません: {}", path.display()))?;
+    writeln!(file, "# {title}\n\n{summary}\n")?;
+    Ok(())
+}
+
+// --period-labelの値（前後の空白を除き、空は不可）
+fn parse_period_label(value: &str) -> Result<String> {
+    let label = value.trim();
+    anyhow::ensure!(!label.is_empty(), "期間の呼び名が空です");
+    Ok(label.to_string())
+}
+
+// 対象期間（未指定の場合はモードに応じたデフォルト）
+fn period(args: &Args) -> (String, String) {
+    let (default_since, default_until) = if args.mode == ModeArg::Weekly {
+        let today = today(args);
+        let week_ago = today - chrono::Duration::days(7);
+        (week_ago.to_string(), today.to_string())
+    } else {
+        ("2025-01-01".to_string(), "2025-06-30".to_string())
+    };
+    (
+        args.since.clone().unwrap_or(default_since),
+        args.until.clone().unwrap_or(default_until),
+    )
+}
 
-    if !output.status.success() {
-        return Ok(Vec::new()); // エラーの場合は空のベクターを返す
+// 今日の日付（--tz指定時はそのタイムゾーン）
+fn today(args: &Args) -> chrono::NaiveDate {
+    match args.tz {
+        Some(tz) => chrono::Utc::now().with_timezone(&tz).date_naive(),
+        None => chrono::Local::now().date_naive(),
     }
+}
 
-    // 各行をJSONとしてパース
-    let mut comments = Vec::new();
-    for line in output.stdout.split(|&b| b == b'\n') {
-        if !line.is_empty() {
-            if let Ok(comment) = serde_json::from_slice::<Comment>(line) {
-                comments.push(comment);
-            }
+// 重要度スコアの重み（--weightsまたは設定ファイルのweightsに、設定ファイルのrepo_weightを追加）
+fn scoring_weights(args: &Args, config: &Config) -> Result<ScoringWeights> {
+    let mut weights = match args.weights.as_ref().or(config.weights.as_ref()) {
+        Some(path) => ScoringWeights::load(path)?,
+        None => ScoringWeights::default(),
+    };
+    weights.repositories.extend(config.repo_weight.clone());
+    Ok(weights)
+}
+
+// CLI引数と設定ファイルからプロンプト構築のオプションを組み立てる
+fn prompt_options(args: &Args, config: &Config) -> Result<PromptOptions> {
+    let weights = scoring_weights(args, config)?;
+    let questions = args
+        .questions
+        .as_deref()
+        .map(|path| read_text(path, "設問ファイル"))
+        .transpose()?;
+    let peer_feedback = args
+        .peer_feedback
+        .iter()
+        .map(|path| read_text(path, "ピアフィードバック"))
+        .collect::<Result<Vec<_>>>()?;
+    let self_assessment = args
+        .self_assessment
+        .as_deref()
+        .map(|path| read_text(path, "自己評価"))
+        .transpose()?;
+    Ok(PromptOptions {
+        mode: args.mode.into(),
+        tone: args.tone.into(),
+        target_level: args.target_level.clone(),
+        resume_language: args.resume_lang.into(),
+        granularity: args.bucket.into(),
+        highlights: args.highlights,
+        weights,
+        monorepo: config.monorepo.clone(),
+        metadata_only: args.metadata_only,
+        quotes: args.quotes,
+        unshipped: args.unshipped,
+        questions,
+        clusters: Vec::new(),
+        discussions: Vec::new(),
+        releases: Vec::new(),
+        community: Community::default(),
+        membership: Membership::default(),
+        triage: Vec::new(),
+        mentions: Mentions::default(),
+        availability: None,
+        gaps: Vec::new(),
+        incidents: config.incident.clone().unwrap_or_default().matcher()?,
+        peer_feedback,
+        self_assessment,
+        truncated: Truncated::default(),
+        feedback: args.feedback.clone(),
+        journal: journal_entries(args)?,
+    })
+}
+
+// compile-journalで使う、評価期間内に記録した実績の記録ファイルの項目
+fn journal_entries(args: &Args) -> Result<Vec<journal::Entry>> {
+    let Some(Commands::CompileJournal { journal: path }) = &args.command else {
+        return Ok(Vec::new());
+    };
+    let (since, until) = period(args);
+    let entries: Vec<journal::Entry> = journal::entries(path)?
+        .into_iter()
+        .filter(|entry| entry.in_period(&since, &until))
+        .collect();
+    anyhow::ensure!(
+        !entries.is_empty(),
+        "{}に{}〜{}の記録がありません",
+        path.display(),
+        since,
+        until
+    );
+    Ok(entries)
+}
+
+// --stripの指定（なければ設定ファイルの[strip]）から、ノイズを取り除くルールを決める
+fn strip_rules(args: &Args, config: &Config) -> Option<StripRules> {
+    let mut rules = if args.strip.is_empty() {
+        config.strip.clone()?
+    } else {
+        StripRules {
+            images: args.strip.contains(&StripArg::Images),
+            html: args.strip.contains(&StripArg::Html),
+            code_blocks: args.strip.contains(&StripArg::Code),
+            ..StripRules::default()
         }
+    };
+    if let Some(max_code_lines) = args.max_code_lines {
+        rules.max_code_lines = max_code_lines;
     }
+    Some(rules)
+}
 
-    Ok(comments)
+// OpenAI APIキー（未設定の場合は`purpose`に必要な旨の設定エラー）
+fn openai_api_key(purpose: &str) -> Result<String> {
+    api_key("OPENAI_API_KEY", purpose)
 }
 
-fn fetch_issue_comments(_owner: &str, repo: &str, issue_number: u32) -> Result<Vec<Comment>> {
-    let output = Command::new("gh")
-        .args([
-            "api",
-            &format!("repos/{repo}/issues/{issue_number}/comments"),
-            "--jq",
-            ".[] | {author: {login: .user.login}, body: .body, createdAt: .created_at}",
-        ])
-        .output()?;
+// キーチェーンまたは環境変数のAPIキー（--replayでは呼び出さないため不要）
+fn api_key(name: &str, purpose: &str) -> Result<String> {
+    if fixture::is_replay() {
+        return Ok(String::new());
+    }
+    keychain::api_key(name)
+        .with_context(|| {
+            format!("{purpose}には{name}環境変数（またはeasyhyoka authでの保存）が必要です")
+        })
+        .context(Failure::Config)
+}
 
-    if !output.status.success() {
-        return Ok(Vec::new()); // エラーの場合は空のベクターを返す
+// OpenAIのクライアント（--no-cache指定時以外は応答をディスクにキャッシュ）
+fn openai_client(args: &Args, api_key: String) -> OpenAiClient {
+    let mut client = OpenAiClient::new(api_key);
+    if let Some(cache) = response_cache(args) {
+        client = client.with_cache(cache);
+    }
+    if let Some(dir) = &args.save_transcript {
+        client = client.with_transcript(Transcript::new(dir));
     }
+    client
+}
 
-    // 各行をJSONとしてパース
-    let mut comments = Vec::new();
-    for line in output.stdout.split(|&b| b == b'\n') {
-        if !line.is_empty() {
-            if let Ok(comment) = serde_json::from_slice::<Comment>(line) {
-                comments.push(comment);
+// レポートを`format`で`output`（省略時は標準出力）に書き出す
+fn write_report(
+    ctx: &ReportContext,
+    format: OutputFormat,
+    output: Option<&std::path::Path>,
+    progress: &Progress,
+) -> Result<()> {
+    let rendered = match format {
+        OutputFormat::Pdf => {
+            let Some(path) = output else {
+                return Err(anyhow::anyhow!(
+                    "PDF出力には--outputで出力先ファイルを指定してください"
+                )
+                .context(Failure::Config));
+            };
+            std::fs::write(path, render::pdf(ctx)?)?;
+            progress.message(format!("\nレポートを書き出しました: {}", path.display()));
+            return Ok(());
+        }
+        OutputFormat::Md => render::markdown(ctx),
+        OutputFormat::Html => render::html(ctx),
+        OutputFormat::Json => render::json(ctx)?,
+    };
+    match output {
+        Some(path) => {
+            std::fs::write(path, &rendered)?;
+            progress.message(format!("\nレポートを書き出しました: {}", path.display()));
+        }
+        None => {
+            if format == OutputFormat::Md {
+                progress.message("\n実績サマリー");
+                progress.message("=====================================");
             }
+            println!("{rendered}");
         }
     }
-
-    Ok(comments)
+    Ok(())
 }
 
-async fn generate_summary(
-    api_key: &str,
-    prs: &[PullRequest],
-    issues: &[Issue],
+// --modelsの各モデルで並行してサマリーを生成し、（モデル名, サマリー）を指定順に返す
+//
+// 失敗したモデルは警告して除き、すべて失敗した場合のみエラーにする
+async fn generate_with_models(
     args: &Args,
-) -> Result<String> {
-    // PRの統計情報を計算
-    let total_prs = prs.len();
-    let merged_prs = prs.iter().filter(|pr| pr.state == "merged").count();
-    let open_prs = prs.iter().filter(|pr| pr.state == "open").count();
-    let closed_prs = prs.iter().filter(|pr| pr.state == "closed").count();
-
-    // リポジトリ別のPR数を集計
-    let mut repo_counts = std::collections::HashMap::new();
-    for pr in prs {
-        *repo_counts
-            .entry(&pr.repository.name_with_owner)
-            .or_insert(0) += 1;
-    }
-    let mut repo_stats: Vec<_> = repo_counts.into_iter().collect();
-    repo_stats.sort_by(|a, b| b.1.cmp(&a.1));
-
-    // Issue統計
-    let total_issues = issues.len();
-    let open_issues = issues.iter().filter(|i| i.state == "open").count();
-    let closed_issues = issues.iter().filter(|i| i.state == "closed").count();
-
-    // プロンプトを構築（JSONL形式）
-    let author = args
-        .author
-        .as_ref()
-        .expect("Author should be set at this point");
-    let mut prompt = format!(
-        "以下は{}の{}から{}までのGitHub活動データです。\n\n",
-        author, args.since, args.until
-    );
+    config: &Config,
+    progress: &Progress,
+    options: &PromptOptions,
+    prompt: &str,
+) -> Result<Vec<(String, String)>> {
+    let mut tasks = tokio::task::JoinSet::new();
+    for (index, model) in args.models.iter().enumerate() {
+        let spec = ModelSpec {
+            provider: Provider::for_model(model),
+            model: Some(model.clone()),
+        };
+        let client = provider_client(args, config, &spec, "サマリーの生成")?;
+        let system_prompt = options.tone.system_prompt();
+        let prompt = prompt.to_string();
+        tasks.spawn(async move { (index, client.chat(system_prompt, &prompt).await) });
+    }
 
-    // 統計情報
-    prompt.push_str("## 統計サマリー\n");
-    prompt.push_str(&format!(
-        "- Pull Request総数: {total_prs}件（マージ済み: {merged_prs}件、オープン: {open_prs}件、クローズ: {closed_prs}件）\n"
-    ));
-    prompt.push_str(&format!(
-        "- Issue総数: {total_issues}件（オープン: {open_issues}件、クローズ: {closed_issues}件）\n\n"
+    progress.message(format!(
+        "\n{}で実績サマリーを並行して生成中...",
+        args.models.join("・")
     ));
-
-    // 全PRをJSONL形式で送信
-    prompt.push_str("## Pull Requestデータ（JSONL形式）\n```\n");
-    for pr in prs {
-        let pr_data = serde_json::json!({
-            "url": pr.url,
-            "title": pr.title,
-            "description": pr.body.as_deref().unwrap_or(""),
-            "status": pr.state,
-            "repository": pr.repository.name_with_owner,
-            "created_at": pr.created_at,
-            "comments": pr.comments.iter().map(|c| {
-                serde_json::json!({
-                    "user": c.author.as_ref().map(|a| &a.login).unwrap_or(&"Unknown".to_string()),
-                    "comment_body": &c.body,
-                    "created_at": &c.created_at
-                })
-            }).collect::<Vec<_>>()
-        });
-        prompt.push_str(&format!("{}\n", serde_json::to_string(&pr_data)?));
-    }
-    prompt.push_str("```\n\n");
-
-    // 全IssueをJSONL形式で送信
-    prompt.push_str("## Issueデータ（JSONL形式）\n```\n");
-    for issue in issues {
-        let issue_data = serde_json::json!({
-            "url": issue.url,
-            "title": issue.title,
-            "description": issue.body.as_deref().unwrap_or(""),
-            "status": issue.state,
-            "repository": issue.repository.name_with_owner,
-            "created_at": issue.created_at,
-            "comments": issue.comments.iter().map(|c| {
-                serde_json::json!({
-                    "user": c.author.as_ref().map(|a| &a.login).unwrap_or(&"Unknown".to_string()),
-                    "comment_body": &c.body,
-                    "created_at": &c.created_at
-                })
-            }).collect::<Vec<_>>()
-        });
-        prompt.push_str(&format!("{}\n", serde_json::to_string(&issue_data)?));
+    let spinner = progress.spinner("サマリーを生成中...");
+    let mut results = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        results.push(joined?);
+    }
+    spinner.finish_and_clear();
+    results.sort_by_key(|(index, _)| *index);
+
+    let mut candidates = Vec::new();
+    let mut last_error = None;
+    for (index, result) in results {
+        let model = &args.models[index];
+        match result {
+            Ok(summary) => candidates.push((model.clone(), summary)),
+            Err(e) => {
+                progress.warn(format!("警告: {model}での生成に失敗しました: {e:#}"));
+                last_error = Some(e);
+            }
+        }
+    }
+    match last_error {
+        Some(e) if candidates.is_empty() => Err(e.context(Failure::Llm)),
+        _ => Ok(candidates),
     }
-    prompt.push_str("```\n\n");
+}
 
-    prompt.push_str("以上のJSONLデータを分析して、エンジニアの評価期間中の実績を最大限に評価するサマリーを日本語で作成してください。\n\n");
+// --providerで選んだプロバイダのクライアント（APIキーが未設定の場合は設定エラー）
+//
+// 設定ファイルに[[models]]があれば、その順に試すフォールバックの連鎖にする
+fn llm_client(args: &Args, config: &Config, purpose: &str) -> Result<ProviderClient> {
+    // --demoでAPIキーがなければ、LLMの代わりに用意済みのサマリーを使う
+    if args.demo && keychain::api_key(Provider::from(args.provider).api_key_var()).is_none() {
+        return Ok(ProviderClient::Demo(DemoClient));
+    }
+    if config.models.is_empty() {
+        let spec = ModelSpec {
+            provider: args.provider.into(),
+            model: None,
+        };
+        return provider_client(args, config, &spec, purpose);
+    }
+    let mut clients = config
+        .models
+        .iter()
+        .map(|spec| provider_client(args, config, spec, purpose))
+        .collect::<Result<Vec<_>>>()?;
+    if clients.len() == 1 {
+        return Ok(clients.remove(0));
+    }
+    FallbackChain::new(clients)
+        .map(ProviderClient::Fallback)
+        .context("設定ファイルの[[models]]が空です")
+}
 
-    prompt.push_str("【分析の観点】\n");
-    prompt.push_str("- PRのタイトルやdescriptionから、関連するPRをグループ化し、大きなプロジェクトや機能開発として認識\n");
-    prompt.push_str(
-        "- descriptionの詳細度やコメントの量から、技術的難易度やプロジェクトの重要性を推測\n",
-    );
-    prompt.push_str("- 小さなPRでも、バグ修正、リファクタリング、ドキュメント改善など、プロダクトの品質向上への貢献として評価\n");
-    prompt.push_str("- リポジトリごとの活動パターンから、どのプロジェクトでどのような役割を担っていたかを推測\n\n");
-
-    prompt.push_str("【評価サマリーに含める項目】\n");
-    prompt.push_str("1. エグゼクティブサマリー（最も印象的な成果を3-5点で箇条書き）\n");
-    prompt.push_str("2. プロジェクト別の貢献内容\n");
-    prompt.push_str("   - 各リポジトリでの主要な取り組みと成果\n");
-    prompt.push_str("   - 関連するPRをまとめて一つの成果として表現\n");
-    prompt.push_str("3. 技術的なリーダーシップ\n");
-    prompt.push_str("   - 新技術の導入、アーキテクチャの改善\n");
-    prompt.push_str("   - コードレビューでの貢献（コメントから読み取れる場合）\n");
-    prompt.push_str("4. ビジネスインパクト\n");
-    prompt.push_str("   - 機能開発によるユーザー価値の向上\n");
-    prompt.push_str("   - パフォーマンス改善や品質向上の取り組み\n");
-    prompt.push_str("5. チームへの貢献\n");
-    prompt.push_str("   - コラボレーションの姿勢\n");
-    prompt.push_str("   - ドキュメント整備やツール改善\n");
-    prompt.push_str("6. 継続的な成長と改善\n");
-    prompt.push_str("   - 期間を通じての成長や学習の形跡\n");
-    prompt.push_str("   - 新しい領域への挑戦\n");
-    prompt.push_str("7. 総合評価と今後への期待\n\n");
-
-    prompt
-        .push_str("【重要】成果を最大限にアピールし、エンジニアの価値を適切に表現してください。\n");
-
-    // OpenAI APIリクエスト
-    let client = reqwest::Client::new();
-    let request = OpenAIRequest {
-        model: "gpt-4.1-mini-2025-04-14".to_string(),
-        messages: vec![
-            Message {
-                role: "system".to_string(),
-                content: "あなたはエンジニアの評価を最大化することを目的としたAIアシスタントです。与えられたGitHubの活動データから、エンジニアの成果と貢献を包括的に分析し、その価値を最大限に表現する評価サマリーを作成します。小さなPRも大きなプロジェクトの一部として捉え、技術的な挑戦やビジネスへの影響を適切に評価してください。".to_string(),
-            },
-            Message {
-                role: "user".to_string(),
-                content: prompt,
-            },
-        ],
-        temperature: 0.7,
-    };
-
-    let response = client
-        .post("https://api.openai.com/v1/chat/completions")
-        .header("Authorization", format!("Bearer {api_key}"))
-        .json(&request)
-        .send()
-        .await?;
+// 主モデルのクライアント（まだ作っていなければここで作る）
+fn primary_client<'a>(
+    slot: &'a mut Option<ProviderClient>,
+    args: &Args,
+    config: &Config,
+) -> Result<&'a ProviderClient> {
+    let client = match slot.take() {
+        Some(client) => client,
+        None => llm_client(args, config, "サマリーの生成")?,
+    };
+    Ok(slot.insert(client))
+}
 
-    if !response.status().is_success() {
-        let error_text = response.text().await?;
-        anyhow::bail!("OpenAI API error: {}", error_text);
-    }
-
-    let openai_response: OpenAIResponse = response.json().await?;
-    let summary = openai_response
-        .choices
-        .first()
-        .ok_or_else(|| anyhow::anyhow!("No response from OpenAI"))?
-        .message
-        .content
-        .clone();
-
-    Ok(summary)
-}
-
-fn generate_and_show_prompt(prs: &[PullRequest], issues: &[Issue], args: &Args) -> Result<()> {
-    // PRの統計情報を計算
-    let total_prs = prs.len();
-    let merged_prs = prs.iter().filter(|pr| pr.state == "merged").count();
-    let open_prs = prs.iter().filter(|pr| pr.state == "open").count();
-    let closed_prs = prs.iter().filter(|pr| pr.state == "closed").count();
-
-    // リポジトリ別のPR数を集計
-    let mut repo_counts = std::collections::HashMap::new();
-    for pr in prs {
-        *repo_counts
-            .entry(&pr.repository.name_with_owner)
-            .or_insert(0) += 1;
-    }
-    let mut repo_stats: Vec<_> = repo_counts.into_iter().collect();
-    repo_stats.sort_by(|a, b| b.1.cmp(&a.1));
-
-    // Issue統計
-    let total_issues = issues.len();
-    let open_issues = issues.iter().filter(|i| i.state == "open").count();
-    let closed_issues = issues.iter().filter(|i| i.state == "closed").count();
-
-    // プロンプトを構築（JSONL形式）
-    let author = args
-        .author
-        .as_ref()
-        .expect("Author should be set at this point");
-    let mut prompt = format!(
-        "以下は{}の{}から{}までのGitHub活動データです。\n\n",
-        author, args.since, args.until
-    );
+// Batch APIで使うOpenAIのモデルと生成の設定（設定ファイルの[[models]]があれば最初のOpenAIのモデル）
+fn batch_chat_client(args: &Args, config: &Config) -> Result<OpenAiClient> {
+    let spec = match config
+        .models
+        .iter()
+        .find(|spec| spec.provider == Provider::OpenAi)
+    {
+        Some(spec) => spec.clone(),
+        None if config.models.is_empty() => ModelSpec {
+            provider: args.provider.into(),
+            model: None,
+        },
+        None => {
+            return Err(anyhow::anyhow!(
+                "Batch APIはOpenAIのみ対応しています。設定ファイルの[[models]]にOpenAIのモデルがありません"
+            )
+            .context(Failure::Config));
+        }
+    };
+    match provider_client(args, config, &spec, "Batch APIの利用")? {
+        ProviderClient::OpenAi(client) => Ok(client),
+        _ => Err(anyhow::anyhow!(
+            "Batch APIはOpenAIのみ対応しています（--provider openaiを指定してください）"
+        )
+        .context(Failure::Config)),
+    }
+}
 
-    // 統計情報
-    prompt.push_str("## 統計サマリー\n");
-    prompt.push_str(&format!(
-        "- Pull Request総数: {total_prs}件（マージ済み: {merged_prs}件、オープン: {open_prs}件、クローズ: {closed_prs}件）\n"
-    ));
-    prompt.push_str(&format!(
-        "- Issue総数: {total_issues}件（オープン: {open_issues}件、クローズ: {closed_issues}件）\n\n"
-    ));
+// 1つのモデルのクライアント
+//
+// 温度・最大トークン数はCLI引数、設定ファイルの順に優先する
+fn provider_client(
+    args: &Args,
+    config: &Config,
+    spec: &ModelSpec,
+    purpose: &str,
+) -> Result<ProviderClient> {
+    let temperature = args
+        .temperature
+        .or(config.temperature)
+        .unwrap_or(llm::DEFAULT_TEMPERATURE);
+    let max_output_tokens = args.max_output_tokens.or(config.max_output_tokens);
+    match spec.provider {
+        Provider::OpenAi => {
+            let mut client =
+                openai_client(args, openai_api_key(purpose)?).with_temperature(temperature);
+            if let Some(model) = &spec.model {
+                client = client.with_model(model);
+            }
+            if let Some(max) = max_output_tokens {
+                client = client.with_max_output_tokens(max);
+            }
+            let effort = args
+                .reasoning_effort
+                .map(ReasoningEffort::from)
+                .or(config.reasoning_effort);
+            if let Some(effort) = effort {
+                client = client.with_reasoning_effort(effort);
+            }
+            Ok(ProviderClient::OpenAi(client))
+        }
+        #[cfg(feature = "anthropic")]
+        Provider::Anthropic => {
+            let api_key = api_key("ANTHROPIC_API_KEY", purpose)?;
+            let mut client = AnthropicClient::new(api_key).with_temperature(temperature);
+            if let Some(model) = &spec.model {
+                client = client.with_model(model);
+            }
+            if let Some(max) = max_output_tokens {
+                client = client.with_max_output_tokens(max);
+            }
+            if let Some(cache) = response_cache(args) {
+                client = client.with_cache(cache);
+            }
+            if let Some(dir) = &args.save_transcript {
+                client = client.with_transcript(Transcript::new(dir));
+            }
+            Ok(ProviderClient::Anthropic(client))
+        }
+        #[cfg(not(feature = "anthropic"))]
+        Provider::Anthropic => Err(anyhow::anyhow!(
+            "Anthropicを使うには`anthropic` featureを有効にしてビルドしてください"
+        )
+        .context(Failure::Config)),
+    }
+}
 
-    // 全PRをJSONL形式で送信
-    prompt.push_str("## Pull Requestデータ（JSONL形式）\n```\n");
-    for pr in prs {
-        let pr_data = serde_json::json!({
-            "url": pr.url,
-            "title": pr.title,
-            "description": pr.body.as_deref().unwrap_or(""),
-            "status": pr.state,
-            "repository": pr.repository.name_with_owner,
-            "created_at": pr.created_at,
-            "comments": pr.comments.iter().map(|c| {
-                serde_json::json!({
-                    "user": c.author.as_ref().map(|a| &a.login).unwrap_or(&"Unknown".to_string()),
-                    "comment_body": &c.body,
-                    "created_at": &c.created_at
-                })
-            }).collect::<Vec<_>>()
-        });
-        prompt.push_str(&format!("{}\n", serde_json::to_string(&pr_data)?));
-    }
-    prompt.push_str("```\n\n");
-
-    // 全IssueをJSONL形式で送信
-    prompt.push_str("## Issueデータ（JSONL形式）\n```\n");
-    for issue in issues {
-        let issue_data = serde_json::json!({
-            "url": issue.url,
-            "title": issue.title,
-            "description": issue.body.as_deref().unwrap_or(""),
-            "status": issue.state,
-            "repository": issue.repository.name_with_owner,
-            "created_at": issue.created_at,
-            "comments": issue.comments.iter().map(|c| {
-                serde_json::json!({
-                    "user": c.author.as_ref().map(|a| &a.login).unwrap_or(&"Unknown".to_string()),
-                    "comment_body": &c.body,
-                    "created_at": &c.created_at
-                })
-            }).collect::<Vec<_>>()
-        });
-        prompt.push_str(&format!("{}\n", serde_json::to_string(&issue_data)?));
+// 応答のキャッシュ（--no-cache指定時やキャッシュディレクトリがない環境では使わない）
+//
+// --record・--replayでは、記録先のディレクトリを常にキャッシュとして使う
+fn response_cache(args: &Args) -> Option<ResponseCache> {
+    if let Some(mode) = fixture::mode() {
+        return Some(ResponseCache::new(mode.llm_dir()));
     }
-    prompt.push_str("```\n\n");
+    ResponseCache::default_dir()
+        .filter(|_| !args.no_cache)
+        .map(ResponseCache::new)
+}
 
-    prompt.push_str("以上のJSONLデータを分析して、エンジニアの評価期間中の実績を最大限に評価するサマリーを日本語で作成してください。\n\n");
+// プロンプトに含めるテキストファイルを読み込む
+fn read_text(path: &std::path::Path, kind: &str) -> Result<String> {
+    std::fs::read_to_string(path)
+        .with_context(|| format!("{kind}を読み込めません: {}", path.display()))
+}
 
-    prompt.push_str("【分析の観点】\n");
-    prompt.push_str("- PRのタイトルやdescriptionから、関連するPRをグループ化し、大きなプロジェクトや機能開発として認識\n");
-    prompt.push_str(
-        "- descriptionの詳細度やコメントの量から、技術的難易度やプロジェクトの重要性を推測\n",
-    );
-    prompt.push_str("- 小さなPRでも、バグ修正、リファクタリング、ドキュメント改善など、プロダクトの品質向上への貢献として評価\n");
-    prompt.push_str("- リポジトリごとの活動パターンから、どのプロジェクトでどのような役割を担っていたかを推測\n\n");
-
-    prompt.push_str("【評価サマリーに含める項目】\n");
-    prompt.push_str("1. エグゼクティブサマリー（最も印象的な成果を3-5点で箇条書き）\n");
-    prompt.push_str("2. プロジェクト別の貢献内容\n");
-    prompt.push_str("   - 各リポジトリでの主要な取り組みと成果\n");
-    prompt.push_str("   - 関連するPRをまとめて一つの成果として表現\n");
-    prompt.push_str("3. 技術的なリーダーシップ\n");
-    prompt.push_str("   - 新技術の導入、アーキテクチャの改善\n");
-    prompt.push_str("   - コードレビューでの貢献（コメントから読み取れる場合）\n");
-    prompt.push_str("4. ビジネスインパクト\n");
-    prompt.push_str("   - 機能開発によるユーザー価値の向上\n");
-    prompt.push_str("   - パフォーマンス改善や品質向上の取り組み\n");
-    prompt.push_str("5. チームへの貢献\n");
-    prompt.push_str("   - コラボレーションの姿勢\n");
-    prompt.push_str("   - ドキュメント整備やツール改善\n");
-    prompt.push_str("6. 継続的な成長と改善\n");
-    prompt.push_str("   - 期間を通じての成長や学習の形跡\n");
-    prompt.push_str("   - 新しい領域への挑戦\n");
-    prompt.push_str("7. 総合評価と今後への期待\n\n");
-
-    prompt
-        .push_str("【重要】成果を最大限にアピールし、エンジニアの価値を適切に表現してください。\n");
+// 検索結果が上限に達した場合は警告
+fn warn_if_limit_reached(progress: &Progress, count: usize, kind: &str) {
+    if count == github::SEARCH_LIMIT {
+        progress.warn(format!(
+            "  警告: 検索結果が{}件の上限に達しました。すべての{kind}が取得できていない可能性があります。",
+            github::SEARCH_LIMIT
+        ));
+        progress.warn("      より詳細な期間指定（--since, --until）で実行することをお勧めします。");
+    }
+}
 
+fn show_prompts(
+    query: &SearchQuery,
+    prs: &[github::PullRequest],
+    issues: &[github::Issue],
+    args: &Args,
+    options: &PromptOptions,
+) -> Result<()> {
     // プロンプトを表示
     println!("\n=== OpenAIに送信するプロンプト ===");
     println!("【システムプロンプト】");
-    println!(
-        "あなたはエンジニアの評価を最大化することを目的としたAIアシスタントです。与えられたGitHubの活動データから、エンジニアの成果と貢献を包括的に分析し、その価値を最大限に表現する評価サマリーを作成します。小さなPRも大きなプロジェクトの一部として捉え、技術的な挑戦やビジネスへの影響を適切に評価してください。"
-    );
-    println!("\n【ユーザープロンプト】");
-    println!("{prompt}");
+    println!("{}", options.tone.system_prompt());
+
+    if args.per_repo {
+        for repo in report::group_by_repository(prs, issues) {
+            let prompt = report::build_repository_prompt(query, &repo, options)?;
+            println!(
+                "\n【ユーザープロンプト: {}（推定{}トークン）】",
+                repo.name,
+                llm::estimate_tokens(&prompt)
+            );
+            println!("{prompt}");
+        }
+        println!(
+            "\n※ 全体サマリーのプロンプトは、リポジトリ別サマリーの生成結果をもとに構築されます。"
+        );
+    } else {
+        let prompt = report::build_prompt(query, prs, issues, options)?;
+        println!(
+            "\n【ユーザープロンプト（推定{}トークン）】",
+            llm::estimate_tokens(&prompt)
+        );
+        println!("{prompt}");
+    }
     println!("=================================\n");
 
     Ok(())
@@ -0,0 +1,292 @@
+//! `mcp` サブコマンド: MCP（Model Context Protocol）サーバーとして標準入出力で待ち受け、
+//! 活動データの取得・集計・サマリー生成をツールとして公開する
+//!
+//! 1行1メッセージのJSON-RPC 2.0で通信します。標準出力はプロトコル専用のため、
+//! 進捗表示は行わず、警告とログは標準エラー出力に書きます。
+
+use crate::progress::Progress;
+use crate::{Args, ModeArg, RoleArg, ToneArg};
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use easy_hyoka::config::Config;
+use easy_hyoka::github::Activity;
+use easy_hyoka::incident::IncidentStats;
+use easy_hyoka::llm;
+use easy_hyoka::stats::ActivityStats;
+use easy_hyoka::{areas, revert, topics};
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+// クライアントがバージョンを指定しなかった場合に返すプロトコルのバージョン
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+// JSON-RPCのエラーコード
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// 取得済みの活動データを保持し、ツール呼び出しに応答するサーバー
+pub struct Server<'a> {
+    args: &'a Args,
+    config: &'a Config,
+    activity: Option<Activity>,
+}
+
+impl<'a> Server<'a> {
+    /// CLI引数を各ツールの既定値として使う
+    pub fn new(args: &'a Args, config: &'a Config) -> Self {
+        Self {
+            args,
+            config,
+            activity: None,
+        }
+    }
+
+    /// 標準入力が閉じられるまでリクエストに応答する
+    pub async fn run(mut self) -> Result<()> {
+        let mut lines = BufReader::new(tokio::io::stdin()).lines();
+        let mut stdout = tokio::io::stdout();
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let response = match serde_json::from_str::<Value>(&line) {
+                Ok(request) => self.handle(request).await,
+                Err(e) => Some(error_response(Value::Null, PARSE_ERROR, &e.to_string())),
+            };
+            if let Some(response) = response {
+                stdout.write_all(format!("{response}\n").as_bytes()).await?;
+                stdout.flush().await?;
+            }
+        }
+        Ok(())
+    }
+
+    // 1件のリクエストを処理（通知には応答しない）
+    async fn handle(&mut self, request: Value) -> Option<Value> {
+        let id = request.get("id").cloned()?;
+        let method = request["method"].as_str().unwrap_or_default();
+        let params = &request["params"];
+        tracing::debug!(method, "MCPリクエスト");
+        let result = match method {
+            "initialize" => json!({
+                "protocolVersion": params["protocolVersion"].as_str().unwrap_or(PROTOCOL_VERSION),
+                "capabilities": { "tools": {} },
+                "serverInfo": {
+                    "name": "easyhyoka",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            }),
+            "ping" => json!({}),
+            "tools/list" => json!({ "tools": tool_definitions() }),
+            "tools/call" => {
+                let Some(name) = params["name"].as_str() else {
+                    return Some(error_response(id, INVALID_PARAMS, "nameがありません"));
+                };
+                let arguments = &params["arguments"];
+                match self.call_tool(name, arguments).await {
+                    Ok(Some(text)) => tool_result(&text, false),
+                    Ok(None) => {
+                        return Some(error_response(
+                            id,
+                            INVALID_PARAMS,
+                            &format!("不明なツールです: {name}"),
+                        ));
+                    }
+                    // ツールの失敗は呼び出し元のモデルが読めるよう結果として返す
+                    Err(e) => tool_result(&format!("{e:#}"), true),
+                }
+            }
+            _ => {
+                return Some(error_response(
+                    id,
+                    METHOD_NOT_FOUND,
+                    &format!("未対応のメソッドです: {method}"),
+                ));
+            }
+        };
+        Some(json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+    }
+
+    // ツールを実行して結果のテキストを返す（不明なツールは`None`）
+    async fn call_tool(&mut self, name: &str, arguments: &Value) -> Result<Option<String>> {
+        let text = match name {
+            "fetch_activity" => self.fetch_activity(arguments)?,
+            "compute_stats" => self.compute_stats()?,
+            "generate_summary" => self.generate_summary(arguments).await?,
+            _ => return Ok(None),
+        };
+        Ok(Some(text))
+    }
+
+    fn fetch_activity(&mut self, arguments: &Value) -> Result<String> {
+        let mut args = self.args.clone();
+        if let Some(owner) = arguments["owner"].as_str() {
+            args.owner = Some(owner.to_string());
+        }
+        if let Some(author) = arguments["author"].as_str() {
+            args.author = Some(author.to_string());
+        }
+        if let Some(since) = arguments["since"].as_str() {
+            args.since = Some(since.to_string());
+        }
+        if let Some(until) = arguments["until"].as_str() {
+            args.until = Some(until.to_string());
+        }
+        if let Some(role) = arguments["role"].as_str() {
+            args.role = parse_value::<RoleArg>("role", role)?;
+        }
+
+        let mut activity = crate::fetch_activity(&args, self.config, &Progress::new(true, false))?;
+        activity.dedupe();
+        topics::tag_activity(&mut activity.prs, &mut activity.issues);
+        revert::mark_reverts(&mut activity.prs);
+
+        let items: Vec<Value> = activity
+            .prs
+            .iter()
+            .map(|pr| {
+                json!({
+                    "type": "pr",
+                    "title": pr.title,
+                    "url": pr.url,
+                    "repository": pr.repository.name_with_owner,
+                    "state": pr.state,
+                    "created_at": pr.created_at,
+                })
+            })
+            .chain(activity.issues.iter().map(|issue| {
+                json!({
+                    "type": "issue",
+                    "title": issue.title,
+                    "url": issue.url,
+                    "repository": issue.repository.name_with_owner,
+                    "state": issue.state,
+                    "created_at": issue.created_at,
+                })
+            }))
+            .collect();
+        let query = &activity.query;
+        let text = format!(
+            "{}の{}から{}までの活動を取得しました（PR {}件、Issue {}件）。compute_stats・generate_summaryはこのデータを使います。\n{}",
+            query.author,
+            query.since,
+            query.until,
+            activity.prs.len(),
+            activity.issues.len(),
+            serde_json::to_string_pretty(&items)?
+        );
+        self.activity = Some(activity);
+        Ok(text)
+    }
+
+    fn compute_stats(&self) -> Result<String> {
+        let activity = self.activity()?;
+        let options = crate::prompt_options(self.args, self.config)?;
+        let stats = ActivityStats {
+            incidents: IncidentStats::compute(&activity.prs, &activity.issues, &options.incidents),
+            prs_by_area: areas::breakdown(&activity.prs, &options.monorepo),
+            weighted_merged_prs: options.weights.weighted_merged_prs(&activity.prs),
+            ..ActivityStats::compute(&activity.prs, &activity.issues, &activity.query.author)
+        };
+        Ok(serde_json::to_string_pretty(&stats)?)
+    }
+
+    async fn generate_summary(&self, arguments: &Value) -> Result<String> {
+        let activity = self.activity()?;
+        let mut args = self.args.clone();
+        if let Some(mode) = arguments["mode"].as_str() {
+            args.mode = parse_value::<ModeArg>("mode", mode)?;
+        }
+        if let Some(tone) = arguments["tone"].as_str() {
+            args.tone = parse_value::<ToneArg>("tone", tone)?;
+        }
+
+        let mut options = crate::prompt_options(&args, self.config)?;
+        options.discussions = activity.discussions.clone();
+        options.releases = activity.releases.clone();
+        options.community = activity.community.clone();
+        let client = crate::llm_client(&args, self.config, "サマリーの生成")?;
+        llm::generate_summary(
+            &client,
+            &activity.query,
+            &activity.prs,
+            &activity.issues,
+            &options,
+        )
+        .await
+    }
+
+    fn activity(&self) -> Result<&Activity> {
+        self.activity
+            .as_ref()
+            .context("活動データがありません。先にfetch_activityを呼び出してください")
+    }
+}
+
+// CLIと同じ値（大文字・小文字は区別しない）を列挙型に変換
+fn parse_value<T: ValueEnum>(name: &str, value: &str) -> Result<T> {
+    T::from_str(value, true).map_err(|_| anyhow::anyhow!("{name}の値が不正です: {value}"))
+}
+
+fn tool_result(text: &str, is_error: bool) -> Value {
+    json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": is_error,
+    })
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+fn tool_definitions() -> Value {
+    json!([
+        {
+            "name": "fetch_activity",
+            "description": "GitHubからユーザーのPR/Issueを取得する。取得したデータは以降のcompute_stats・generate_summaryで使われる。省略した引数はサーバー起動時のCLI引数の値になる。",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "owner": { "type": "string", "description": "取得対象のOrganizationまたはユーザー（省略時はGitHub全体の公開リポジトリ）" },
+                    "author": { "type": "string", "description": "対象ユーザー（省略時はghの認証ユーザー）" },
+                    "since": { "type": "string", "description": "期間の開始日（YYYY-MM-DD）" },
+                    "until": { "type": "string", "description": "期間の終了日（YYYY-MM-DD）" },
+                    "role": {
+                        "type": "string",
+                        "enum": ["author", "assignee", "reviewer", "mentions", "involves"],
+                        "description": "対象ユーザーとPR/Issueの関わり方",
+                    },
+                },
+            },
+        },
+        {
+            "name": "compute_stats",
+            "description": "取得済みの活動データを集計する（PR/Issue数、リポジトリ別・月別の件数、レビュー、所要時間、協業相手など）。",
+            "inputSchema": { "type": "object", "properties": {} },
+        },
+        {
+            "name": "generate_summary",
+            "description": "取得済みの活動データからOpenAIで実績サマリーを生成する（OPENAI_API_KEYが必要）。",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "mode": {
+                        "type": "string",
+                        "enum": ["summary", "promo", "weekly", "resume"],
+                        "description": "生成するドキュメントの種類",
+                    },
+                    "tone": {
+                        "type": "string",
+                        "enum": ["promotional", "balanced", "critical"],
+                        "description": "評価のトーン",
+                    },
+                },
+            },
+        },
+    ])
+}
@@ -0,0 +1,93 @@
+//! HTTPクライアントのプロキシ・TLSの設定（設定ファイルの`[http]`・`--ca-cert`）
+//!
+//! プロキシは`HTTPS_PROXY`・`HTTP_PROXY`・`NO_PROXY`環境変数に従い、`proxy`を指定した場合は
+//! そちらを優先します。TLSを中継する社内ネットワーク向けに、追加で信頼するCA証明書と
+//! 相互TLSのクライアント証明書を指定できます。設定は起動時に一度だけ行い、
+//! [`crate::limits::http_client`]で作るすべてのクライアントに適用します。
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+static SETTINGS: OnceLock<Settings> = OnceLock::new();
+
+/// 設定ファイルの`[http]`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct HttpConfig {
+    /// すべてのHTTPリクエストに使うプロキシのURL（省略時は環境変数に従う）
+    pub proxy: Option<String>,
+    /// 追加で信頼するCA証明書（PEM、複数の証明書を含むバンドルも可）
+    pub ca_cert: Option<PathBuf>,
+    /// 相互TLSのクライアント証明書（PEM、`client_key`と組で指定）
+    pub client_cert: Option<PathBuf>,
+    /// 相互TLSのクライアント証明書の秘密鍵（PKCS#8のPEM）
+    pub client_key: Option<PathBuf>,
+}
+
+// 読み込み済みのプロキシ・証明書
+#[derive(Clone, Default)]
+struct Settings {
+    proxy: Option<reqwest::Proxy>,
+    certificates: Vec<reqwest::Certificate>,
+    identity: Option<reqwest::Identity>,
+}
+
+/// 証明書を読み込み、プロセス全体のHTTPクライアントの設定にする（起動時に一度だけ）
+pub fn configure(config: &HttpConfig) -> Result<()> {
+    let proxy = config
+        .proxy
+        .as_deref()
+        .map(|url| {
+            reqwest::Proxy::all(url).with_context(|| format!("プロキシのURLが不正です: {url}"))
+        })
+        .transpose()?;
+    let certificates = match &config.ca_cert {
+        Some(path) => reqwest::Certificate::from_pem_bundle(&read(path, "CA証明書")?)
+            .with_context(|| format!("CA証明書の形式が不正です: {}", path.display()))?,
+        None => Vec::new(),
+    };
+    let identity = match (&config.client_cert, &config.client_key) {
+        (Some(cert), Some(key)) => Some(
+            reqwest::Identity::from_pkcs8_pem(
+                &read(cert, "クライアント証明書")?,
+                &read(key, "クライアント証明書の秘密鍵")?,
+            )
+            .context("クライアント証明書または秘密鍵の形式が不正です")?,
+        ),
+        (None, None) => None,
+        _ => anyhow::bail!("[http]のclient_certとclient_keyは組で指定してください"),
+    };
+    let _ = SETTINGS.set(Settings {
+        proxy,
+        certificates,
+        identity,
+    });
+    // 設定を適用したクライアントを起動時に一度作り、TLSの設定の誤りをここで報告する
+    apply(reqwest::Client::builder()).build().context(
+        "[http]の設定でHTTPクライアントを作成できません（プロキシ・証明書を確認してください）",
+    )?;
+    Ok(())
+}
+
+/// 設定したプロキシ・証明書を`builder`に適用
+pub fn apply(mut builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+    let Some(settings) = SETTINGS.get() else {
+        return builder;
+    };
+    if let Some(proxy) = &settings.proxy {
+        builder = builder.proxy(proxy.clone());
+    }
+    for certificate in &settings.certificates {
+        builder = builder.add_root_certificate(certificate.clone());
+    }
+    if let Some(identity) = &settings.identity {
+        builder = builder.identity(identity.clone());
+    }
+    builder
+}
+
+fn read(path: &Path, kind: &str) -> Result<Vec<u8>> {
+    std::fs::read(path).with_context(|| format!("{kind}を読み込めません: {}", path.display()))
+}
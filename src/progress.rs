@@ -0,0 +1,79 @@
+//! indicatifによる進捗表示（`--quiet`指定時は何も表示しない）
+//!
+//! `--ci`指定時はプログレスバーを使わず、各処理の開始を1行ずつ標準エラー出力に書き、
+//! 警告はGitHub Actionsのアノテーション（`::warning::`）として出力します。
+
+use indicatif::{ProgressBar, ProgressStyle};
+use std::borrow::Cow;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Progress {
+    quiet: bool,
+    ci: bool,
+}
+
+impl Progress {
+    pub fn new(quiet: bool, ci: bool) -> Self {
+        Self { quiet, ci }
+    }
+
+    /// 進捗メッセージを表示（CIでは標準出力をサマリー用に空けるため標準エラー出力）
+    pub fn message(&self, message: impl AsRef<str>) {
+        if self.quiet {
+            return;
+        }
+        if self.ci {
+            eprintln!("{}", message.as_ref());
+        } else {
+            println!("{}", message.as_ref());
+        }
+    }
+
+    /// 警告は`--quiet`でも標準エラー出力に表示
+    pub fn warn(&self, message: impl AsRef<str>) {
+        if self.ci {
+            eprintln!("::warning::{}", message.as_ref());
+        } else {
+            eprintln!("{}", message.as_ref());
+        }
+    }
+
+    /// 経過時間付きのスピナー
+    pub fn spinner(&self, message: impl Into<Cow<'static, str>>) -> ProgressBar {
+        if self.quiet {
+            return ProgressBar::hidden();
+        }
+        if self.ci {
+            self.message(message.into());
+            return ProgressBar::hidden();
+        }
+        let spinner = ProgressBar::new_spinner();
+        spinner.set_style(
+            ProgressStyle::with_template("  {spinner} {msg} [{elapsed}]")
+                .expect("progress template should be valid"),
+        );
+        spinner.set_message(message);
+        spinner.enable_steady_tick(Duration::from_millis(100));
+        spinner
+    }
+
+    /// 件数が分かっている処理のプログレスバー
+    pub fn bar(&self, len: usize, message: impl Into<Cow<'static, str>>) -> ProgressBar {
+        if self.quiet {
+            return ProgressBar::hidden();
+        }
+        if self.ci {
+            self.message(format!("{}（{len}件）", message.into()));
+            return ProgressBar::hidden();
+        }
+        let bar = ProgressBar::new(len as u64);
+        bar.set_style(
+            ProgressStyle::with_template("  {msg} [{bar:30}] {pos}/{len}")
+                .expect("progress template should be valid")
+                .progress_chars("=> "),
+        );
+        bar.set_message(message);
+        bar
+    }
+}
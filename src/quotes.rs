@@ -0,0 +1,215 @@
+//! 受け取ったコメント・レビューからの引用（`--quotes`）
+//!
+//! 本人のPR/Issueに他のメンバーが書いたコメントとレビューから、称賛（「great catch」など）の
+//! 一文や、設計についての踏み込んだ議論を短く抜き出し、原文のままの引用としてプロンプトに
+//! 含めます。LLMが言い換えたり創作したりしないよう、プロンプトでも原文であることを明示します。
+
+use crate::collab;
+use crate::github::{CommentAuthor, Issue, PullRequest};
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// プロンプトに含める引用の最大数
+pub const MAX_QUOTES: usize = 15;
+
+// 引用1件の最大文字数
+const MAX_CHARS: usize = 200;
+
+// これより短い称賛の一文は、何への称賛か分かるよう直前の一文も含める
+const MIN_SENTENCE_CHARS: usize = 20;
+
+// 設計の議論とみなすコメントの最短の文字数
+const DISCUSSION_MIN_CHARS: usize = 150;
+
+// 称賛とみなす表現（小文字で部分一致）
+const PRAISE: &[&str] = &[
+    "great catch",
+    "good catch",
+    "nice catch",
+    "great work",
+    "nice work",
+    "great job",
+    "good job",
+    "well done",
+    "awesome",
+    "excellent",
+    "brilliant",
+    "love this",
+    "thank you",
+    "thanks",
+    "素晴らしい",
+    "すばらしい",
+    "ありがとう",
+    "助かり",
+    "助かる",
+    "さすが",
+    "勉強になり",
+    "分かりやすい",
+    "わかりやすい",
+    "丁寧",
+    "ナイス",
+    "すごい",
+    "感謝",
+];
+
+// 設計の議論とみなす表現（小文字で部分一致）
+const DISCUSSION: &[&str] = &[
+    "design",
+    "architecture",
+    "trade-off",
+    "tradeoff",
+    "approach",
+    "alternative",
+    "設計",
+    "方針",
+    "トレードオフ",
+    "アーキテクチャ",
+    "代替案",
+    "懸念",
+];
+
+/// 引用の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuoteKind {
+    /// 称賛・感謝
+    Praise,
+    /// 設計などについての踏み込んだ議論
+    Discussion,
+}
+
+/// 受け取ったコメント・レビューからの引用（原文のまま）
+#[derive(Debug, Clone, Serialize)]
+pub struct Quote {
+    /// 引用元のPR/IssueのURL
+    pub url: String,
+    /// 発言者
+    pub from: String,
+    pub kind: QuoteKind,
+    pub text: String,
+    pub created_at: String,
+}
+
+/// `author`のPR/Issueで他のメンバーから受けたコメント・レビューから引用を抜き出す
+///
+/// 称賛を先に、それぞれ新しい順に並べ、最大[`MAX_QUOTES`]件を返します。
+pub fn extract(prs: &[PullRequest], issues: &[Issue], author: &str) -> Vec<Quote> {
+    let received = prs
+        .iter()
+        .filter(|pr| collab::is_own(pr.author.as_ref(), author))
+        .flat_map(|pr| {
+            pr.comments
+                .iter()
+                .map(|c| (&pr.url, &c.author, &c.body, c.created_at.as_str()))
+                .chain(pr.reviews.iter().map(|r| {
+                    let submitted = r.submitted_at.as_deref().unwrap_or(&pr.created_at);
+                    (&pr.url, &r.author, &r.body, submitted)
+                }))
+        })
+        .chain(
+            issues
+                .iter()
+                .filter(|issue| collab::is_own(issue.author.as_ref(), author))
+                .flat_map(|issue| {
+                    issue
+                        .comments
+                        .iter()
+                        .map(|c| (&issue.url, &c.author, &c.body, c.created_at.as_str()))
+                }),
+        );
+
+    let mut quotes: Vec<Quote> = Vec::new();
+    let mut seen = HashSet::new();
+    for (url, from, body, created_at) in received {
+        let Some(from) = from.as_ref().map(|a: &CommentAuthor| a.login.as_str()) else {
+            continue;
+        };
+        if from == author || collab::is_bot(from) {
+            continue;
+        }
+        let Some((kind, text)) = quote(body) else {
+            continue;
+        };
+        if seen.insert(text.clone()) {
+            quotes.push(Quote {
+                url: url.clone(),
+                from: from.to_string(),
+                kind,
+                text,
+                created_at: created_at.to_string(),
+            });
+        }
+    }
+
+    quotes.sort_by(|a, b| {
+        (a.kind != QuoteKind::Praise)
+            .cmp(&(b.kind != QuoteKind::Praise))
+            .then_with(|| b.created_at.cmp(&a.created_at))
+    });
+    quotes.truncate(MAX_QUOTES);
+    quotes
+}
+
+// 本文から引用する部分（称賛は該当する一文、議論は冒頭）
+fn quote(body: &str) -> Option<(QuoteKind, String)> {
+    let text = plain_text(body);
+    let lower = text.to_lowercase();
+    // 小文字にしても文字の区切りが変わらない場合だけ、位置をそのまま使う
+    if lower.len() == text.len()
+        && let Some(index) = PRAISE.iter().filter_map(|p| lower.find(p)).min()
+    {
+        return Some((QuoteKind::Praise, shorten(sentence_at(&text, index))));
+    }
+    if text.chars().count() >= DISCUSSION_MIN_CHARS && DISCUSSION.iter().any(|d| lower.contains(d))
+    {
+        return Some((QuoteKind::Discussion, shorten(&text)));
+    }
+    None
+}
+
+// 引用部分（`>`）とコードブロックを除き、空白を詰めた本文
+fn plain_text(body: &str) -> String {
+    let mut in_code = false;
+    let mut lines: Vec<&str> = Vec::new();
+    for line in body.lines().map(str::trim) {
+        if line.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if !in_code && !line.is_empty() && !line.starts_with('>') {
+            lines.push(line);
+        }
+    }
+    lines.join(" ")
+}
+
+// `index`のバイト位置を含む一文（短い場合は直前の一文から）
+fn sentence_at(text: &str, index: usize) -> &str {
+    let is_end = |c: char| matches!(c, '。' | '！' | '？' | '!' | '?' | '.');
+    let sentence_start = |before: usize| {
+        text[..before]
+            .char_indices()
+            .rfind(|(_, c)| is_end(*c))
+            .map_or(0, |(i, c)| i + c.len_utf8())
+    };
+    let end = text[index..]
+        .char_indices()
+        .find(|(_, c)| is_end(*c))
+        .map_or(text.len(), |(i, c)| index + i + c.len_utf8());
+    let mut start = sentence_start(index);
+    if start > 0 && text[start..end].trim().chars().count() < MIN_SENTENCE_CHARS {
+        // 直前の一文の終わりの区切りを飛ばして、その文の始まりを探す
+        let previous_end = text[..start].char_indices().last().map_or(0, |(i, _)| i);
+        start = sentence_start(previous_end);
+    }
+    text[start..end].trim()
+}
+
+fn shorten(text: &str) -> String {
+    if text.chars().count() <= MAX_CHARS {
+        return text.to_string();
+    }
+    let mut short: String = text.chars().take(MAX_CHARS).collect();
+    short.push('…');
+    short
+}
@@ -0,0 +1,707 @@
+//! サマリーと統計情報を出力形式（Markdown/HTML/PDF）にレンダリング
+
+use crate::areas::AreaStats;
+use crate::github::SearchQuery;
+use crate::stats::{self, ActivityStats, HeatmapWeek, TimeBucket, WorkPattern};
+
+/// レンダリングに必要なデータ一式
+#[derive(Debug, Clone, Copy)]
+pub struct ReportContext<'a> {
+    pub query: &'a SearchQuery,
+    pub stats: &'a ActivityStats,
+    pub summary: &'a str,
+}
+
+/// レポートのタイトル
+pub fn title(query: &SearchQuery) -> String {
+    format!("{} の実績サマリー（{}）", query.author, query.period())
+}
+
+/// 生成されたサマリー（Markdown）をそのまま返す
+pub fn markdown(ctx: &ReportContext) -> String {
+    ctx.summary.to_string()
+}
+
+/// タイトル・検索条件・統計情報・サマリーをまとめたJSON
+pub fn json(ctx: &ReportContext) -> anyhow::Result<String> {
+    Ok(serde_json::to_string_pretty(&serde_json::json!({
+        "title": title(ctx.query),
+        "query": ctx.query,
+        "stats": ctx.stats,
+        "summary": ctx.summary,
+    }))?)
+}
+
+/// タイトルとサマリー本文だけのHTML（Googleドキュメントへの変換用、グラフやスタイルを含まない）
+pub fn summary_html(ctx: &ReportContext) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"ja\">\n<head>\n<meta charset=\"utf-8\">\n</head>\n<body>\n<h1>{}</h1>\n{}</body>\n</html>\n",
+        escape(&title(ctx.query)),
+        markdown_to_html(ctx.summary)
+    )
+}
+
+/// 統計情報・グラフ・サマリーを含む単体で閲覧可能なHTMLを生成
+pub fn html(ctx: &ReportContext) -> String {
+    let title = escape(&title(ctx.query));
+    let stats = ctx.stats;
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"ja\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>{title}</title>\n"));
+    html.push_str(STYLE);
+    html.push_str("</head>\n<body>\n");
+    html.push_str(&format!("<h1>{title}</h1>\n"));
+
+    // 統計情報
+    html.push_str("<section>\n<h2>統計サマリー</h2>\n<table>\n");
+    html.push_str(
+        "<tr><th></th><th>総数</th><th>マージ済み</th><th>オープン</th><th>クローズ</th></tr>\n",
+    );
+    html.push_str(&format!(
+        "<tr><th>Pull Request</th><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+        stats.total_prs, stats.merged_prs, stats.open_prs, stats.closed_prs
+    ));
+    html.push_str(&format!(
+        "<tr><th>Issue</th><td>{}</td><td>-</td><td>{}</td><td>{}</td></tr>\n",
+        stats.total_issues, stats.open_issues, stats.closed_issues
+    ));
+    html.push_str("</table>\n");
+    if stats.draft_prs + stats.revert_prs + stats.reverted_prs > 0 {
+        html.push_str(&format!(
+            "<p>ドラフト {}件 / revert PR {}件 / revertされたPR {}件（実質的なマージ済みPR {}件）</p>\n",
+            stats.draft_prs, stats.revert_prs, stats.reverted_prs, stats.shipped_prs
+        ));
+    }
+    if let Some(weighted) = stats.weighted_merged_prs {
+        html.push_str(&format!(
+            "<p>リポジトリの重要度で重み付けしたマージ済みPR数: {weighted:.1}（重み付けなし {}件）</p>\n",
+            stats.merged_prs
+        ));
+    }
+    let incidents = &stats.incidents;
+    if !incidents.is_empty() {
+        html.push_str(&format!(
+            "<p>障害対応・オンコール: PR {}件（マージ済み {}件）/ Issue {}件（{}リポジトリ）</p>\n",
+            incidents.prs,
+            incidents.merged_prs,
+            incidents.issues,
+            incidents.repositories.len()
+        ));
+    }
+    if let Some(pattern) = &stats.work_pattern {
+        html.push_str(&format!(
+            "<p>{}</p>\n",
+            escape(&work_pattern_summary(pattern))
+        ));
+    }
+    let docs = &stats.documentation;
+    if docs.lines > 0 {
+        html.push_str(&format!(
+            "<p>ドキュメント: 主にドキュメントを変更したPR {}件（マージ済み {}件）/ 変更 {}行</p>\n",
+            docs.prs, docs.merged_prs, docs.lines
+        ));
+    }
+    let engagement = &stats.engagement;
+    if !engagement.is_empty() {
+        html.push_str(&format!(
+            "<p>反響: 👍 {}件 / 🎉 {}件 / ❤️・🚀 {}件 / 合計 {}件（PR/Issue {}件、自分のコメントへ {}件）</p>\n",
+            engagement.thumbs_up,
+            engagement.hooray,
+            engagement.heart_and_rocket,
+            engagement.total,
+            engagement.reacted_items,
+            engagement.comment_reactions
+        ));
+    }
+    let shipping = &stats.shipping;
+    if !shipping.is_empty() {
+        html.push_str(&format!(
+            "<p>CI・デプロイ: CI成功 {}件 / {}件、デプロイ成功 {}件（本番環境 {}件）</p>\n",
+            shipping.passing_prs,
+            shipping.checked_prs,
+            shipping.deployed_prs,
+            shipping.production_prs
+        ));
+    }
+    let given = &stats.reviews_given;
+    if !given.is_empty() {
+        html.push_str(&format!(
+            "<p>行ったレビュー: {}件のPR / 承認 {}件 / 変更要求 {}件 / コメント {}件、スレッド {}件中 {}件解決</p>\n",
+            given.reviewed_prs,
+            given.approvals,
+            given.changes_requested,
+            given.commented,
+            given.threads_opened,
+            given.threads_resolved
+        ));
+    }
+    let reviews = &stats.reviews_received;
+    if reviews.total() > 0 {
+        html.push_str(&format!(
+            "<p>受けたレビュー: 承認 {}件 / 変更要求 {}件 / コメント {}件（レビュアー {}人）</p>\n",
+            reviews.approvals,
+            reviews.changes_requested,
+            reviews.commented,
+            reviews.reviewers.len()
+        ));
+    }
+    let turnaround = &stats.turnaround;
+    let durations: Vec<String> = [
+        ("マージまで", turnaround.time_to_merge),
+        ("最初のレビューまで", turnaround.time_to_first_review),
+        ("レビュー応答", turnaround.review_response),
+    ]
+    .into_iter()
+    .filter_map(|(label, hours)| Some(format!("{label} {}", stats::format_hours(hours?))))
+    .collect();
+    if !durations.is_empty() {
+        html.push_str(&format!(
+            "<p>所要時間（中央値）: {}</p>\n",
+            durations.join(" / ")
+        ));
+    }
+    html.push_str("</section>\n");
+
+    // グラフ
+    html.push_str("<section>\n<h2>月別PR数</h2>\n");
+    html.push_str(&bar_chart(&stats.prs_by_month));
+    html.push_str("</section>\n<section>\n<h2>リポジトリ別PR数</h2>\n");
+    let top_repos: Vec<_> = stats.prs_by_repository.iter().take(10).cloned().collect();
+    html.push_str(&bar_chart(&top_repos));
+    html.push_str("</section>\n");
+    if !stats.prs_by_area.is_empty() {
+        html.push_str("<section>\n<h2>モノレポの領域別PR数</h2>\n");
+        html.push_str(&bar_chart(&area_counts(&stats.prs_by_area)));
+        html.push_str("</section>\n");
+    }
+    if !stats.activity_by_day.is_empty() {
+        html.push_str("<section>\n<h2>日別の活動（PR/Issue作成数）</h2>\n");
+        let weeks = stats::heatmap(&stats.activity_by_day, &ctx.query.since, &ctx.query.until);
+        html.push_str(&heatmap_svg(&weeks));
+        html.push_str("</section>\n");
+    }
+    if !stats.collaborators.is_empty() {
+        html.push_str("<section>\n<h2>主な協業相手</h2>\n<table>\n");
+        html.push_str("<tr><th></th><th>コメント</th><th>受けたレビュー</th><th>したレビュー</th><th>共同作成</th><th>リポジトリ数</th></tr>\n");
+        for c in stats.collaborators.iter().take(10) {
+            html.push_str(&format!(
+                "<tr><th>{}</th><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                escape(&c.login),
+                c.comments,
+                c.reviews_received,
+                c.reviews_given,
+                c.co_authored,
+                c.repositories.len()
+            ));
+        }
+        html.push_str("</table>\n</section>\n");
+    }
+    if !stats.tech_stack.is_empty() {
+        html.push_str("<section>\n<h2>技術スタック（変更行数）</h2>\n");
+        let top_languages: Vec<_> = stats.tech_stack.iter().take(10).cloned().collect();
+        html.push_str(&bar_chart(&top_languages));
+        html.push_str("</section>\n");
+    }
+    if !stats.topics.is_empty() {
+        html.push_str("<section>\n<h2>トピック別PR/Issue数</h2>\n");
+        html.push_str(&bar_chart(&stats.topics));
+        html.push_str("</section>\n");
+    }
+
+    // サマリー本文
+    html.push_str("<section class=\"summary\">\n");
+    html.push_str(&markdown_to_html(ctx.summary));
+    html.push_str("</section>\n</body>\n</html>\n");
+
+    html
+}
+
+const STYLE: &str = r#"<style>
+body { font-family: -apple-system, "Hiragino Sans", "Noto Sans JP", sans-serif; max-width: 960px; margin: 2em auto; padding: 0 1em; line-height: 1.7; color: #24292f; }
+h1 { border-bottom: 2px solid #d0d7de; padding-bottom: .3em; }
+h2 { border-bottom: 1px solid #d0d7de; padding-bottom: .2em; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #d0d7de; padding: .3em .8em; text-align: right; }
+th:first-child { text-align: left; }
+svg text { font-size: 12px; fill: #24292f; }
+</style>
+"#;
+
+const CHART_WIDTH: usize = 720;
+const LABEL_WIDTH: usize = 240;
+const ROW_HEIGHT: usize = 24;
+
+// 横棒グラフをインラインSVGで描画
+fn bar_chart(data: &[(String, usize)]) -> String {
+    if data.is_empty() {
+        return "<p>データがありません</p>\n".to_string();
+    }
+
+    let max = data
+        .iter()
+        .map(|(_, count)| *count)
+        .max()
+        .unwrap_or(1)
+        .max(1);
+    let bar_area = CHART_WIDTH - LABEL_WIDTH - 40;
+    let height = data.len() * ROW_HEIGHT;
+
+    let mut svg = format!(
+        "<svg width=\"{CHART_WIDTH}\" height=\"{height}\" viewBox=\"0 0 {CHART_WIDTH} {height}\" role=\"img\">\n"
+    );
+    for (i, (label, count)) in data.iter().enumerate() {
+        let y = i * ROW_HEIGHT;
+        let width = count * bar_area / max;
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>\n",
+            LABEL_WIDTH - 8,
+            y + 16,
+            escape(label)
+        ));
+        svg.push_str(&format!(
+            "<rect x=\"{LABEL_WIDTH}\" y=\"{}\" width=\"{width}\" height=\"{}\" fill=\"#2da44e\"/>\n",
+            y + 4,
+            ROW_HEIGHT - 8
+        ));
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\">{count}</text>\n",
+            LABEL_WIDTH + width + 6,
+            y + 16
+        ));
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+// 領域別のPR数（上位10件）
+fn area_counts(areas: &[AreaStats]) -> Vec<(String, usize)> {
+    areas.iter().take(10).map(|a| (a.label(), a.prs)).collect()
+}
+
+/// MarkdownをHTMLに変換
+pub fn markdown_to_html(markdown: &str) -> String {
+    let parser = pulldown_cmark::Parser::new_ext(markdown, pulldown_cmark::Options::all());
+    let mut html = String::new();
+    pulldown_cmark::html::push_html(&mut html, parser);
+    html
+}
+
+/// HTMLの特殊文字をエスケープ
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// タイトルページ・統計表・サマリー本文からなるtypstソースを生成
+pub fn typst(ctx: &ReportContext) -> String {
+    let stats = ctx.stats;
+    let title = title(ctx.query);
+
+    let mut doc = String::new();
+    doc.push_str(&format!(
+        "#set document(title: \"{}\")\n",
+        typst_string(&title)
+    ));
+    doc.push_str("#set page(paper: \"a4\", margin: 2.2cm, numbering: \"1\")\n");
+    doc.push_str("#set text(lang: \"ja\", size: 10.5pt, font: (\"Noto Sans CJK JP\", \"Hiragino Sans\", \"Yu Gothic\", \"IPAexGothic\"))\n");
+    doc.push_str("#set par(justify: true, leading: 0.8em)\n\n");
+
+    // タイトルページ
+    doc.push_str("#page(numbering: none)[\n  #align(center + horizon)[\n");
+    doc.push_str(&format!(
+        "    #text(size: 24pt, weight: \"bold\")[{}]\n",
+        typst_escape(&title)
+    ));
+    doc.push_str("    #v(2em)\n");
+    doc.push_str(&format!(
+        "    #text(size: 14pt)[{} / {}]\n",
+        typst_escape(&ctx.query.author),
+        typst_escape(&ctx.query.period())
+    ));
+    doc.push_str("    #v(1em)\n");
+    doc.push_str(&format!(
+        "    #text(size: 11pt)[作成日: {}]\n",
+        chrono::Local::now().format("%Y-%m-%d")
+    ));
+    doc.push_str("  ]\n]\n\n");
+
+    // 統計表
+    doc.push_str("= 統計サマリー\n\n");
+    doc.push_str("#table(columns: 5, align: (left, right, right, right, right),\n");
+    doc.push_str("  [], [*総数*], [*マージ済み*], [*オープン*], [*クローズ*],\n");
+    doc.push_str(&format!(
+        "  [Pull Request], [{}], [{}], [{}], [{}],\n",
+        stats.total_prs, stats.merged_prs, stats.open_prs, stats.closed_prs
+    ));
+    doc.push_str(&format!(
+        "  [Issue], [{}], [-], [{}], [{}],\n)\n\n",
+        stats.total_issues, stats.open_issues, stats.closed_issues
+    ));
+    doc.push_str("== 月別PR数\n\n");
+    doc.push_str(&typst_count_table("月", &stats.prs_by_month));
+    doc.push_str("== リポジトリ別PR数\n\n");
+    doc.push_str(&typst_count_table("リポジトリ", &stats.prs_by_repository));
+    if !stats.prs_by_area.is_empty() {
+        doc.push_str("== モノレポの領域別PR数\n\n");
+        doc.push_str(&typst_count_table("領域", &area_counts(&stats.prs_by_area)));
+    }
+
+    // サマリー本文
+    doc.push_str("#pagebreak()\n\n= 評価サマリー\n\n");
+    doc.push_str(&markdown_to_typst(ctx.summary));
+
+    doc
+}
+
+/// typst CLIでPDFを生成
+pub fn pdf(ctx: &ReportContext) -> anyhow::Result<Vec<u8>> {
+    let dir = std::env::temp_dir().join(format!("easyhyoka-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    let source = dir.join("report.typ");
+    let output = dir.join("report.pdf");
+    std::fs::write(&source, typst(ctx))?;
+
+    let result = std::process::Command::new("typst")
+        .arg("compile")
+        .arg(&source)
+        .arg(&output)
+        .output();
+    let pdf = match result {
+        Ok(result) if result.status.success() => std::fs::read(&output).map_err(Into::into),
+        Ok(result) => Err(anyhow::anyhow!(
+            "typst compile failed: {}",
+            String::from_utf8_lossy(&result.stderr)
+        )),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(anyhow::anyhow!(
+            "PDF出力にはtypst CLIが必要です（https://github.com/typst/typst）"
+        )),
+        Err(e) => Err(e.into()),
+    };
+
+    std::fs::remove_dir_all(&dir).ok();
+    pdf
+}
+
+fn typst_count_table(label: &str, data: &[(String, usize)]) -> String {
+    let mut table = String::from("#table(columns: 2, align: (left, right),\n");
+    table.push_str(&format!("  [*{label}*], [*PR数*],\n"));
+    for (key, count) in data {
+        table.push_str(&format!("  [{}], [{count}],\n", typst_escape(key)));
+    }
+    table.push_str(")\n\n");
+    table
+}
+
+// Markdownのサマリーをtypstのマークアップに変換
+fn markdown_to_typst(markdown: &str) -> String {
+    use pulldown_cmark::{Event, HeadingLevel, Tag, TagEnd};
+
+    let mut out = String::new();
+    // 入れ子のリストごとに番号付きかどうか
+    let mut lists: Vec<bool> = Vec::new();
+    let mut in_code_block = false;
+
+    for event in pulldown_cmark::Parser::new_ext(markdown, pulldown_cmark::Options::all()) {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => {
+                // 本文は「= 評価サマリー」の下に入るため、見出しは2段目以下にする
+                let depth = match level {
+                    HeadingLevel::H1 | HeadingLevel::H2 => 2,
+                    HeadingLevel::H3 => 3,
+                    _ => 4,
+                };
+                out.push_str(&"=".repeat(depth));
+                out.push(' ');
+            }
+            Event::End(TagEnd::Heading(_)) => out.push_str("\n\n"),
+            Event::End(TagEnd::Paragraph) => {
+                out.push_str(if lists.is_empty() { "\n\n" } else { "\n" });
+            }
+            Event::Start(Tag::List(first)) => {
+                if !lists.is_empty() && !out.ends_with('\n') {
+                    out.push('\n');
+                }
+                lists.push(first.is_some());
+            }
+            Event::End(TagEnd::List(_)) => {
+                lists.pop();
+                if lists.is_empty() {
+                    out.push('\n');
+                }
+            }
+            Event::Start(Tag::Item) => {
+                let indent = "  ".repeat(lists.len().saturating_sub(1));
+                let marker = if lists.last() == Some(&true) {
+                    "+"
+                } else {
+                    "-"
+                };
+                out.push_str(&format!("{indent}{marker} "));
+            }
+            Event::End(TagEnd::Item) if !out.ends_with('\n') => out.push('\n'),
+            Event::Start(Tag::Strong) | Event::End(TagEnd::Strong) => out.push('*'),
+            Event::Start(Tag::Emphasis) | Event::End(TagEnd::Emphasis) => out.push('_'),
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                out.push_str(&format!("#link(\"{}\")[", dest_url.replace('"', "\\\"")));
+            }
+            Event::End(TagEnd::Link) => out.push(']'),
+            Event::Start(Tag::CodeBlock(_)) => {
+                in_code_block = true;
+                out.push_str("```\n");
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                in_code_block = false;
+                out.push_str("```\n\n");
+            }
+            Event::Text(text) if in_code_block => out.push_str(&text),
+            Event::Text(text) => out.push_str(&typst_escape(&text)),
+            Event::Code(code) => out.push_str(&format!("`{}`", code.replace('`', "'"))),
+            Event::SoftBreak => out.push(' '),
+            Event::HardBreak => out.push_str("\\\n"),
+            Event::Rule => out.push_str("#line(length: 100%)\n\n"),
+            _ => {}
+        }
+    }
+
+    out
+}
+
+// typstのマークアップで特別な意味を持つ文字をエスケープ
+fn typst_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '#'
+                | '*'
+                | '_'
+                | '`'
+                | '$'
+                | '<'
+                | '>'
+                | '@'
+                | '['
+                | ']'
+                | '~'
+                | '/'
+                | '='
+                | '-'
+                | '+'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+// typstの文字列リテラル（`"..."`）の中に置くためのエスケープ
+fn typst_string(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+const HEATMAP_CELL: usize = 14;
+const HEATMAP_COLORS: [&str; 5] = ["#ebedf0", "#9be9a8", "#40c463", "#30a14e", "#216e39"];
+const WEEKDAYS: [&str; 7] = ["月", "火", "水", "木", "金", "土", "日"];
+
+// 作成数を0〜4の濃さに変換（最大の日を4とする）
+fn heat_level(count: usize, max: usize) -> usize {
+    if count == 0 {
+        0
+    } else {
+        (count * 4).div_ceil(max.max(1)).clamp(1, 4)
+    }
+}
+
+// 週の列に表示する月（週の最終日の月、`MM`）
+fn heatmap_month(week: &HeatmapWeek) -> String {
+    chrono::NaiveDate::parse_from_str(&week.start, "%Y-%m-%d")
+        .map(|start| (start + chrono::Duration::days(6)).format("%m").to_string())
+        .unwrap_or_default()
+}
+
+fn heatmap_max(weeks: &[HeatmapWeek]) -> usize {
+    weeks
+        .iter()
+        .flat_map(|w| w.days.iter().flatten())
+        .copied()
+        .max()
+        .unwrap_or(0)
+}
+
+// カレンダー形式のヒートマップをインラインSVGで描画（列が週、行が曜日）
+fn heatmap_svg(weeks: &[HeatmapWeek]) -> String {
+    let max = heatmap_max(weeks);
+    let left = 24;
+    let top = 16;
+    let width = left + weeks.len() * HEATMAP_CELL;
+    let height = top + 7 * HEATMAP_CELL;
+
+    let mut svg = format!(
+        "<svg width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\" role=\"img\">\n"
+    );
+    for (row, weekday) in WEEKDAYS.iter().enumerate().step_by(2) {
+        svg.push_str(&format!(
+            "<text x=\"0\" y=\"{}\">{weekday}</text>\n",
+            top + row * HEATMAP_CELL + 11
+        ));
+    }
+    let mut previous_month = String::new();
+    for (col, week) in weeks.iter().enumerate() {
+        let x = left + col * HEATMAP_CELL;
+        let month = heatmap_month(week);
+        if month != previous_month {
+            svg.push_str(&format!(
+                "<text x=\"{x}\" y=\"11\">{}月</text>\n",
+                month.trim_start_matches('0')
+            ));
+            previous_month = month;
+        }
+        let start = chrono::NaiveDate::parse_from_str(&week.start, "%Y-%m-%d").ok();
+        for (row, day) in week.days.iter().enumerate() {
+            let Some(count) = day else {
+                continue;
+            };
+            let date = start
+                .map(|s| (s + chrono::Duration::days(row as i64)).to_string())
+                .unwrap_or_default();
+            svg.push_str(&format!(
+                "<rect x=\"{x}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"2\" fill=\"{}\"><title>{date}: {count}件</title></rect>\n",
+                top + row * HEATMAP_CELL,
+                HEATMAP_CELL - 3,
+                HEATMAP_CELL - 3,
+                HEATMAP_COLORS[heat_level(*count, max)]
+            ));
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// 日別のPR/Issue数をターミナル向けのカレンダー形式のヒートマップにする
+///
+/// 列が週（月曜始まり）、行が曜日で、作成数が多い日ほど濃い文字で表します。
+pub fn heatmap_ascii(weeks: &[HeatmapWeek]) -> String {
+    const SHADES: [char; 5] = ['·', '░', '▒', '▓', '█'];
+    let max = heatmap_max(weeks);
+
+    // 月が変わる週の列に月（MM）を表示
+    let mut chart = String::from("   ");
+    let mut previous_month = String::new();
+    for week in weeks {
+        let month = heatmap_month(week);
+        if month != previous_month {
+            chart.push_str(&month);
+            previous_month = month;
+        } else {
+            chart.push_str("  ");
+        }
+    }
+    chart.push('\n');
+
+    for (row, weekday) in WEEKDAYS.iter().enumerate() {
+        chart.push_str(weekday);
+        chart.push(' ');
+        for week in weeks {
+            let shade = week.days[row].map_or(' ', |count| SHADES[heat_level(count, max)]);
+            chart.push(shade);
+            chart.push(' ');
+        }
+        chart.push('\n');
+    }
+    let total: usize = weeks.iter().flat_map(|w| w.days.iter().flatten()).sum();
+    chart.push_str(&format!(
+        "   少 {} 多（1日の最大 {}件、合計 {}件）\n",
+        SHADES
+            .iter()
+            .map(char::to_string)
+            .collect::<Vec<_>>()
+            .join(" "),
+        max,
+        total
+    ));
+    chart
+}
+
+const ASCII_BAR_WIDTH: usize = 40;
+
+/// 期間ごとのPR/Issue数をターミナル向けの横棒グラフにする
+pub fn timeline_ascii(buckets: &[TimeBucket]) -> String {
+    let max = buckets
+        .iter()
+        .map(|b| b.prs + b.issues)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let label_width = buckets.iter().map(|b| b.period.len()).max().unwrap_or(0);
+
+    let mut chart = String::new();
+    for bucket in buckets {
+        let pr_width = bucket.prs * ASCII_BAR_WIDTH / max;
+        let issue_width = (bucket.prs + bucket.issues) * ASCII_BAR_WIDTH / max - pr_width;
+        chart.push_str(&format!(
+            "{:label_width$} | {}{} PR {} / Issue {}\n",
+            bucket.period,
+            "█".repeat(pr_width),
+            "░".repeat(issue_width),
+            bucket.prs,
+            bucket.issues
+        ));
+        if let Some(gap) = &bucket.gap {
+            chart.pop();
+            chart.push_str(&format!("  ※{gap}\n"));
+        }
+    }
+    chart
+}
+
+/// 曜日・時間帯別の作成数の棒グラフ（深夜・土日の継続が見られる場合は注記）
+pub fn work_pattern_ascii(pattern: &WorkPattern) -> String {
+    let mut chart = String::new();
+    let weekdays: Vec<(String, usize)> = WEEKDAYS
+        .iter()
+        .zip(pattern.by_weekday)
+        .map(|(day, count)| (day.to_string(), count))
+        .collect();
+    let hours: Vec<(String, usize)> = pattern
+        .by_hour
+        .iter()
+        .enumerate()
+        .map(|(hour, count)| (format!("{hour:02}時"), *count))
+        .collect();
+    for (heading, rows) in [("曜日別", weekdays), ("時間帯別", hours)] {
+        let max = rows
+            .iter()
+            .map(|(_, count)| *count)
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        chart.push_str(&format!("{heading}\n"));
+        for (label, count) in rows {
+            chart.push_str(&format!(
+                "{label:>4} | {} {count}\n",
+                "█".repeat(count * ASCII_BAR_WIDTH / max)
+            ));
+        }
+    }
+    chart.push_str(&format!("{}\n", work_pattern_summary(pattern)));
+    chart
+}
+
+// 深夜・土日の件数と、継続している場合の注記
+fn work_pattern_summary(pattern: &WorkPattern) -> String {
+    let mut summary = format!(
+        "作業時間帯: 深夜（22〜5時）{}件・土日 {}件 / 全{}件",
+        pattern.late_night, pattern.weekend, pattern.total
+    );
+    if pattern.is_sustained() {
+        summary.push_str(&format!(
+            "（注意: 深夜・土日の作業が{}週続いています）",
+            pattern.longest_off_hours_streak
+        ));
+    }
+    summary
+}
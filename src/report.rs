@@ -0,0 +1,2052 @@
+//! 評価サマリー用のプロンプト構築
+
+use crate::areas::{self, MonorepoConfig};
+use crate::availability::Availability;
+use crate::budget::Truncated;
+use crate::cluster::Cluster;
+use crate::collab;
+use crate::gaps::Gap;
+use crate::github::{
+    Comment, CommentAuthor, Commit, Community, Discussion, Issue, Label, LinkedIssue, Membership,
+    Mentions, PullRequest, Reactions, Release, Review, Roadmap, Role, SearchQuery, TriageItem,
+};
+use crate::highlight::ScoringWeights;
+use crate::incident::{IncidentMatcher, IncidentStats};
+use crate::journal;
+use crate::quotes::{self, Quote};
+use crate::stats::{
+    self, ActivityStats, DiscussionStats, Granularity, MentionStats, TriageStats, TurnaroundStats,
+};
+use crate::unshipped::{self, UnshippedItem, UnshippedKind};
+use crate::{revert, techstack};
+use anyhow::Result;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+pub const SYSTEM_PROMPT: &str = "あなたはエンジニアの評価を最大化することを目的としたAIアシスタントです。与えられたGitHubの活動データから、エンジニアの成果と貢献を包括的に分析し、その価値を最大限に表現する評価サマリーを作成します。小さなPRも大きなプロジェクトの一部として捉え、技術的な挑戦やビジネスへの影響を適切に評価してください。";
+
+const BALANCED_SYSTEM_PROMPT: &str = "あなたはエンジニアの評価を支援するAIアシスタントです。与えられたGitHubの活動データから、エンジニアの成果と貢献を公平に分析し、強みと成長領域の両方を含むバランスの取れた評価サマリーを作成します。活動データで裏付けられない成果を誇張せず、評価者が判断に使える客観的な記述を心がけてください。";
+
+const CRITICAL_SYSTEM_PROMPT: &str = "あなたはエンジニアの評価を支援するAIアシスタントです。与えられたGitHubの活動データを厳しい目で分析し、成果を正当に認めつつも、改善が必要な点や期待水準に届いていない点を率直に指摘する評価サマリーを作成します。曖昧な賛辞は避け、活動データに基づく具体的な指摘を心がけてください。";
+
+pub const FACT_CHECK_SYSTEM_PROMPT: &str = "あなたはエンジニアの評価資料をレビューするファクトチェッカーです。評価サマリーの各記述が、与えられたGitHubの活動データ（PR/IssueのURL）で裏付けられているかを厳密に確認します。データにない成果や数値を記述してはいけません。";
+
+pub const EVIDENCE_SYSTEM_PROMPT: &str = "あなたはエンジニアの評価資料をレビューするアシスタントです。評価サマリーの各見出しの記述を裏付けるPR/Issueを、与えられたGitHubの活動データから正確に選び、指定されたJSON形式だけで答えます。";
+
+// PRごとにプロンプトへ含めるコミットメッセージの最大数
+const MAX_COMMIT_SUBJECTS: usize = 20;
+
+/// 評価のトーン
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Tone {
+    /// 成果を最大限にアピールする
+    #[default]
+    Promotional,
+    /// 強みと成長領域の両方を公平に記述する
+    Balanced,
+    /// 改善点を率直に指摘する
+    Critical,
+}
+
+impl Tone {
+    /// トーンに応じたシステムプロンプト
+    pub fn system_prompt(self) -> &'static str {
+        match self {
+            Tone::Promotional => SYSTEM_PROMPT,
+            Tone::Balanced => BALANCED_SYSTEM_PROMPT,
+            Tone::Critical => CRITICAL_SYSTEM_PROMPT,
+        }
+    }
+
+    // 「実績を〜サマリー」の修飾句
+    fn evaluation_phrase(self) -> &'static str {
+        match self {
+            Tone::Promotional => "最大限に評価する",
+            Tone::Balanced => "公平に評価する",
+            Tone::Critical => "厳しい目で評価する",
+        }
+    }
+}
+
+/// 生成するドキュメントの種類
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Mode {
+    /// 評価期間の実績サマリー
+    #[default]
+    Summary,
+    /// 昇格推薦資料（プロモーションパケット）
+    Promo,
+    /// 週報・1on1用の簡潔なトピック
+    Weekly,
+    /// 職務経歴書向けの実績箇条書き
+    Resume,
+    /// 自己評価・ピアフィードバック・活動データを突き合わせた360度評価
+    ThreeSixty,
+}
+
+/// 職務経歴書向けの出力言語
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResumeLanguage {
+    #[default]
+    English,
+    Japanese,
+}
+
+impl ResumeLanguage {
+    /// 二言語出力（`--bilingual`）で翻訳する先の言語
+    pub fn other(self) -> Self {
+        match self {
+            ResumeLanguage::English => ResumeLanguage::Japanese,
+            ResumeLanguage::Japanese => ResumeLanguage::English,
+        }
+    }
+
+    // 二言語出力の見出し
+    fn heading(self) -> &'static str {
+        match self {
+            ResumeLanguage::English => "English",
+            ResumeLanguage::Japanese => "日本語",
+        }
+    }
+}
+
+pub const CRITIQUE_SYSTEM_PROMPT: &str = "あなたはエンジニアの評価資料を推敲する編集者です。評価サマリーの下書きを、活動データと品質チェックリストに照らして厳しく講評し、具体的な改善点を指摘します。";
+
+/// プロンプト構築のオプション
+#[derive(Debug, Clone, Default)]
+pub struct PromptOptions {
+    /// 生成するドキュメントの種類
+    pub mode: Mode,
+    /// 評価のトーン
+    pub tone: Tone,
+    /// 昇格先のレベル（`Mode::Promo`用、例: L5）
+    pub target_level: Option<String>,
+    /// 職務経歴書の出力言語（`Mode::Resume`用）
+    pub resume_language: ResumeLanguage,
+    /// ピアフィードバックの本文（`Mode::ThreeSixty`用）
+    pub peer_feedback: Vec<String>,
+    /// 本人の自己評価の本文（`Mode::ThreeSixty`用）
+    pub self_assessment: Option<String>,
+    /// 期間中の推移を集計する単位
+    pub granularity: Granularity,
+    /// 本文・コメント付きで送るPRの件数（`None`は全件）
+    pub highlights: Option<usize>,
+    /// 主要なPRを選ぶスコアリングの重み
+    pub weights: ScoringWeights,
+    /// 変更ファイルのパスで領域別に集計するモノレポ
+    pub monorepo: MonorepoConfig,
+    /// 本文・コメントを送らず、タイトルや件数などのメタデータのみにする
+    pub metadata_only: bool,
+    /// 受けたコメント・レビューから称賛や議論を原文のまま引用する（`--metadata-only`では無効）
+    pub quotes: bool,
+    /// 出荷されなかった作業（長期間オープンのPR・動きのないIssue・マージされずにクローズしたPR）を
+    /// リスクとフォローアップとして扱う
+    pub unshipped: bool,
+    /// 内容の近いPRのクラスタ（`--cluster`で埋め込みから算出）
+    pub clusters: Vec<Cluster>,
+    /// 作成・回答したDiscussion（`--discussions`指定時のみ）
+    pub discussions: Vec<Discussion>,
+    /// 公開したリリース（`--releases`指定時のみ）
+    pub releases: Vec<Release>,
+    /// 社外のOSSへのPRと公開Gist（`--community`指定時のみ）
+    pub community: Community,
+    /// Organizationでの役割と所属チーム（`--membership`指定時のみ）
+    pub membership: Membership,
+    /// 他のメンバーのIssueでのトリアージ（`--triage`指定時のみ）
+    pub triage: Vec<TriageItem>,
+    /// メンションと他のリポジトリからの参照（`--mentions`指定時のみ）
+    pub mentions: Mentions,
+    /// 評価期間のうち稼働できた期間と割合（設定ファイルの`[people.<login>]`）
+    pub availability: Option<Availability>,
+    /// 指定した、または活動データから検出した活動のない期間
+    pub gaps: Vec<Gap>,
+    /// 障害対応のPR/Issueの判定ルール
+    pub incidents: IncidentMatcher,
+    /// 自己評価フォームの設問（指定時は評価サマリーの代わりに各設問への回答案を生成）
+    pub questions: Option<String>,
+    /// トークン数の上限に合わせて削ったデータ（`--max-prompt-tokens`指定時のみ）
+    pub truncated: Truncated,
+    /// 生成し直すときに追加する利用者の指示（`--feedback`）
+    pub feedback: Option<String>,
+    /// 期間中に書き溜めた実績の記録（`compile-journal`のみ）
+    pub journal: Vec<journal::Entry>,
+}
+
+impl PromptOptions {
+    /// 生成されるドキュメントの言語（職務経歴書以外は日本語）
+    pub fn language(&self) -> ResumeLanguage {
+        match self.mode {
+            Mode::Resume => self.resume_language,
+            _ => ResumeLanguage::Japanese,
+        }
+    }
+}
+
+/// リポジトリ単位にまとめた活動データ（`--per-repo`用）
+#[derive(Debug)]
+pub struct RepositoryActivity {
+    pub name: String,
+    pub prs: Vec<PullRequest>,
+    pub issues: Vec<Issue>,
+}
+
+impl RepositoryActivity {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            prs: Vec::new(),
+            issues: Vec::new(),
+        }
+    }
+}
+
+/// 評価サマリー生成用のユーザープロンプトを構築
+pub fn build_prompt(
+    query: &SearchQuery,
+    prs: &[PullRequest],
+    issues: &[Issue],
+    options: &PromptOptions,
+) -> Result<String> {
+    let mut prompt = build_task_prompt(query, prs, issues, options)?;
+    if !unshipped_items(query, prs, issues, options).is_empty() {
+        push_unshipped_items(&mut prompt, options);
+    }
+    push_journal(&mut prompt, options);
+    push_user_feedback(&mut prompt, options);
+    Ok(prompt)
+}
+
+// プロンプトでの期間の表記（`--period-label`指定時はその呼び名で、レポートでも使うよう指示する）
+fn period_phrase(query: &SearchQuery) -> String {
+    match &query.label {
+        Some(label) => format!("{label}（レポートでも期間は「{label}」と表記すること）"),
+        None => format!("{}から{}まで", query.since, query.until),
+    }
+}
+
+// 出荷されなかった作業（`--unshipped`指定時のみ）
+fn unshipped_items(
+    query: &SearchQuery,
+    prs: &[PullRequest],
+    issues: &[Issue],
+    options: &PromptOptions,
+) -> Vec<UnshippedItem> {
+    if !options.unshipped {
+        return Vec::new();
+    }
+    unshipped::collect(prs, issues, &query.until)
+}
+
+// リスクとフォローアップの項目（--unshipped、実績サマリー・週報・360度評価のみ）
+fn push_unshipped_items(prompt: &mut String, options: &PromptOptions) {
+    let applies = options.questions.is_none()
+        && matches!(
+            options.mode,
+            Mode::Summary | Mode::Weekly | Mode::ThreeSixty
+        );
+    if !applies {
+        return;
+    }
+    prompt.push_str("\n【リスクとフォローアップ】\n");
+    prompt.push_str("最後に「リスクとフォローアップ」の見出しを設け、出荷されなかった作業（unshipped）を次の観点でまとめてください。\n");
+    prompt.push_str("- 長期間オープンのPR（long_open_pr）: 止まっている理由として活動データから読み取れること（レビュー待ち・変更要求への対応待ち・ドラフトなど）と、完了させる・分割する・クローズするといった次の一手\n");
+    prompt.push_str("- 動きのないIssue（stale_issue）: 優先度の見直しや担当の整理が必要なもの\n");
+    prompt.push_str("- マージされずにクローズしたPR（closed_unmerged）: 方針転換や検証の結果として価値があったものと、やり直しが必要なものの区別\n");
+    prompt.push_str("- 理由を活動データから読み取れない場合は推測で断定せず、確認事項として書く。未完了の作業を怠慢と決めつけない\n");
+}
+
+// 活動データと、モードごとの作成の指示
+fn build_task_prompt(
+    query: &SearchQuery,
+    prs: &[PullRequest],
+    issues: &[Issue],
+    options: &PromptOptions,
+) -> Result<String> {
+    let mut prompt = build_activity_data(query, prs, issues, options)?;
+
+    if let Some(questions) = &options.questions {
+        push_questions(&mut prompt, questions, options.tone);
+        return Ok(prompt);
+    }
+    if options.mode == Mode::Promo {
+        prompt.push_str(&format!(
+            "以上のJSONLデータを分析して、{}への昇格推薦資料（プロモーションパケット）を日本語で作成してください。\n\n",
+            target_level_label(options)
+        ));
+        push_promo_items(&mut prompt, options);
+        return Ok(prompt);
+    }
+    if options.mode == Mode::Weekly {
+        prompt.push_str("以上のJSONLデータを分析して、週報・1on1で共有するトピックを日本語で簡潔に作成してください。\n\n");
+        push_weekly_items(&mut prompt);
+        return Ok(prompt);
+    }
+    if options.mode == Mode::Resume {
+        prompt.push_str("以上のJSONLデータを分析して、職務経歴書（CV/レジュメ）にそのまま貼り付けられる実績の箇条書きを作成してください。\n\n");
+        push_resume_items(&mut prompt, options);
+        return Ok(prompt);
+    }
+    if options.mode == Mode::ThreeSixty {
+        push_feedback(&mut prompt, options);
+        prompt.push_str("以上の活動データ・自己評価・ピアフィードバックを突き合わせて、マネージャー向けの360度評価を日本語で作成してください。\n\n");
+        push_three_sixty_items(&mut prompt);
+        return Ok(prompt);
+    }
+
+    prompt.push_str(&format!(
+        "以上のJSONLデータを分析して、エンジニアの評価期間中の実績を{}サマリーを日本語で作成してください。\n\n",
+        options.tone.evaluation_phrase()
+    ));
+
+    prompt.push_str("【分析の観点】\n");
+    if options.metadata_only {
+        prompt.push_str("- PRのタイトルやラベルから、関連するPRをグループ化し、大きなプロジェクトや機能開発として認識\n");
+        prompt.push_str("- コメント数やレビュー数から、議論の活発さやプロジェクトの重要性を推測\n");
+    } else {
+        prompt.push_str("- PRのタイトルやdescriptionから、関連するPRをグループ化し、大きなプロジェクトや機能開発として認識\n");
+        prompt.push_str(
+            "- descriptionの詳細度やコメントの量から、技術的難易度やプロジェクトの重要性を推測\n",
+        );
+    }
+    prompt.push_str("- 小さなPRでも、バグ修正、リファクタリング、ドキュメント改善など、プロダクトの品質向上への貢献として評価\n");
+    prompt.push_str("- リポジトリごとの活動パターンから、どのプロジェクトでどのような役割を担っていたかを推測\n");
+    prompt.push_str("- 期間中の推移から、継続的なデリバリーのペースや活動の山場を把握\n");
+    if !options.gaps.is_empty() {
+        prompt.push_str("- 活動のない期間は、ペースの落ち込みや不調として扱わず、推移やペースについての記述から除外する（検出しただけの期間はGitHubの外での仕事の可能性もあるため、理由を推測で書かない）\n");
+    }
+    if prs.iter().any(|pr| !pr.topics.is_empty()) || issues.iter().any(|i| !i.topics.is_empty()) {
+        prompt.push_str("- トピック別の分布から、注力した領域やスキルの幅を把握\n");
+    }
+    if !collab::network(prs, issues, &query.author).is_empty() {
+        prompt
+            .push_str("- 主な協業相手とリポジトリの広がりから、チームをまたいだ協業の様子を評価\n");
+    }
+    if prs.iter().any(|pr| !pr.files.is_empty()) {
+        prompt.push_str("- 技術スタックの内訳から、扱った言語・技術の幅と専門性を評価\n");
+    }
+    if !options.clusters.is_empty() {
+        prompt.push_str("- プロジェクト候補のクラスタを手がかりに、関連するPRを一つのプロジェクトとしてまとめる（内容が合わない場合は組み替えてよい）\n");
+    }
+    if !options.metadata_only && options.highlights.is_some_and(|n| n < prs.len()) {
+        prompt.push_str("- 主要なPRを中心に評価しつつ、メタデータのみのPRもタイトルや件数から取り組みの幅として考慮\n");
+    }
+    if prs
+        .iter()
+        .any(|pr| pr.is_draft || pr.reverted_by.is_some() || revert::is_revert(pr))
+    {
+        prompt.push_str("- ドラフトのままのPRやrevertされたPRは出荷した成果として数えず、revert PRも新たな成果とは区別して、取り組みの経緯として扱う\n");
+    }
+    if prs.iter().any(techstack::is_docs_pr) {
+        prompt.push_str("- ドキュメント中心のPR（documentation）は、コードの変更量が少なくても、設計・手順・知見を文章で残した貢献として独立して評価\n");
+    }
+    if !options.releases.is_empty() {
+        prompt
+            .push_str("- リリースの作成・公開を、変更を利用者に届ける責任を担った実績として評価\n");
+    }
+    if !options.community.is_empty() {
+        prompt.push_str("- 社外のOSSでマージされたPRや公開Gistは、社内の成果とは分けて、技術コミュニティへの還元として評価\n");
+    }
+    if options.availability.is_some() {
+        prompt.push_str("- 在籍期間・稼働率が期間全体のフルタイムとは異なるため、PR/Issueの件数を他のメンバーやフルタイムの水準と単純に比べたり、件数で順位付けしたりせず、稼働できた期間に対する成果の質と影響で評価\n");
+    }
+    if !options.membership.is_empty() {
+        prompt.push_str("- 所属チームと役割から本人の責任範囲を捉え、担当領域での成果は期待される責務をどこまで果たしたかとして、担当領域の外への貢献は越境した取り組みとして位置付けて評価\n");
+    }
+    if !received_quotes(query, prs, issues, options).is_empty() {
+        prompt.push_str("- 受け取ったコメントからの引用は第三者による評価の裏付けとして使い、サマリーで引用する場合は原文のまま「」で括って発言者を示す（言い換えた文や新たに作った文を引用として書かない）\n");
+    }
+    if !options.triage.is_empty() {
+        prompt.push_str("- 他のメンバーのIssueへのラベル付け・重複や対応しないものの整理・最初の応答を、コードに現れにくいトリアージとサポートの貢献として評価\n");
+    }
+    if !options.mentions.is_empty() {
+        prompt.push_str("- 他のメンバーからのメンションや他のリポジトリからの参照を、相談先として頼られている裏付けとして評価し、繰り返し参照しているリポジトリ・人を挙げて影響の広がりを示す\n");
+    }
+    if !options.discussions.is_empty() {
+        prompt.push_str("- Discussionsでの質問への回答（特に採用された回答）や議論の立ち上げを、チームへの知識共有の貢献として評価\n");
+    }
+    if prs.iter().any(|pr| !options.monorepo.areas(pr).is_empty()) {
+        prompt.push_str("- モノレポのPRは変更した領域（areas）ごとに担当範囲を捉え、リポジトリ全体を一つの成果としてまとめずに、領域ごとの貢献として評価\n");
+    }
+    if prs.iter().any(|pr| {
+        options
+            .weights
+            .repository_weight(&pr.repository.name_with_owner)
+            != 1.0
+    }) {
+        prompt.push_str("- リポジトリの重要度が設定されているため、件数の多さより重要度の高いリポジトリでの成果を優先して取り上げる\n");
+    }
+    if prs.iter().any(|pr| !pr.deployments.is_empty()) {
+        prompt.push_str("- デプロイの記録（deployed_to）があるPRは、実際に出荷した成果として明示し、本番環境へのデプロイは特に重みを付けて評価\n");
+    }
+    if prs.iter().any(|pr| !pr.closing_issues.is_empty()) {
+        prompt.push_str("- PRで解決したIssue（closes/fixes）のタイトル・ラベルから、コードの変更とそれが解決した課題を結び付けて、バグ修正や課題解決への貢献を評価（他のメンバーが起票したIssueの解決も含む）\n");
+    }
+    if prs
+        .iter()
+        .any(|pr| !pr.assignees.is_empty() || !pr.requested_reviewers.is_empty())
+        || issues.iter().any(|issue| !issue.assignees.is_empty())
+    {
+        prompt.push_str("- 担当者（assignees）とレビュー依頼先（requested_reviewers）から、作成者としてだけでなく担当を任された仕事や、他のメンバーへの依頼・委任の状況も評価\n");
+    }
+    if !stats::EngagementStats::compute(prs, issues, &query.author).is_empty() {
+        prompt.push_str("- リアクション（reactions）の多いPR/Issueは、チームメンバーに歓迎・感謝された仕事の目安として補助的に考慮（数だけで評価しない）\n");
+    }
+    if prs.iter().any(|pr| !pr.commits.is_empty()) {
+        prompt.push_str("- PRのコミット数とコミットメッセージ（commits）から、大きな変更を段階的に進めた過程や試行錯誤、作業の範囲を読み取って評価\n");
+    }
+    if prs.iter().any(|pr| !pr.roadmap.is_empty())
+        || issues.iter().any(|issue| !issue.roadmap.is_empty())
+    {
+        prompt.push_str("- マイルストーン・プロジェクト別のまとまりを手がかりに、ロードマップ上の取り組み単位で成果を整理して評価\n");
+    }
+    if !TurnaroundStats::compute(prs, &query.author).is_empty() {
+        prompt.push_str("- マージまでの時間やレビューの応答時間から、デリバリーの速さやチームへの応答性を効率面の根拠として評価\n");
+    }
+    if !stats::ReviewerStats::compute(prs, &query.author).is_empty() {
+        prompt.push_str("- 他の人のPRへのレビューの承認率・変更要求の割合と、指摘したスレッドの解決率から、レビュアーとしての指摘の的確さや品質への関与を評価\n");
+    }
+    if prs.iter().any(|pr| !pr.reviews.is_empty()) {
+        prompt.push_str("- 受けたレビュー（承認・変更要求・レビューコメント）の内容や傾向から、コード品質やレビューでのコラボレーションの様子を評価\n");
+    }
+    prompt.push('\n');
+
+    let reliability = !IncidentStats::compute(prs, issues, &options.incidents).is_empty();
+    let community = !options.community.is_empty();
+    push_evaluation_items(&mut prompt, options.tone, reliability, community);
+
+    Ok(prompt)
+}
+
+// 統計情報とPR/IssueのJSONLデータからなる、プロンプトの共通部分を構築
+fn build_activity_data(
+    query: &SearchQuery,
+    prs: &[PullRequest],
+    issues: &[Issue],
+    options: &PromptOptions,
+) -> Result<String> {
+    let stats = ActivityStats {
+        weighted_merged_prs: options.weights.weighted_merged_prs(prs),
+        ..ActivityStats::compute(prs, issues, &query.author)
+    };
+
+    // プロンプトを構築（JSONL形式）
+    let mut prompt = format!(
+        "以下は{}の{}のGitHub活動データです。\n\n",
+        query.author,
+        period_phrase(query)
+    );
+    if query.role != Role::Author {
+        prompt.push_str(&format!(
+            "対象は{}が{}PR/Issueです。作成者（author）は本人とは限らないため、{}の役割に即して評価してください。\n\n",
+            query.author,
+            query.role.description(),
+            query.author
+        ));
+    }
+    if !options.truncated.is_empty() {
+        prompt.push_str(&format!(
+            "データ量の上限のため、一部のデータを省略しています（{}）。省略された内容を推測で補わないでください。\n\n",
+            options.truncated.describe()
+        ));
+    }
+    push_membership(&mut prompt, query, &options.membership);
+    if let Some(availability) = &options.availability {
+        push_availability(&mut prompt, availability, &stats);
+    }
+    if options.metadata_only {
+        prompt.push_str("データ共有の制約により、PR/Issueの本文やコメントは含まれていません。タイトル・ラベル・日付・状態・件数から読み取れる範囲で評価し、具体的な実装内容や議論の中身については推測で断定しないでください。\n\n");
+    }
+
+    // 統計情報
+    prompt.push_str("## 統計サマリー\n");
+    prompt.push_str(&format!(
+        "- Pull Request総数: {}件（マージ済み: {}件、オープン: {}件、クローズ: {}件）\n",
+        stats.total_prs, stats.merged_prs, stats.open_prs, stats.closed_prs
+    ));
+    if stats.draft_prs + stats.revert_prs + stats.reverted_prs > 0 {
+        prompt.push_str(&format!(
+            "- ドラフト・revert: ドラフトのままのPR {}件、revert PR {}件、マージ後にrevertされたPR {}件（これらを除いた実質的なマージ済みPR: {}件）\n",
+            stats.draft_prs, stats.revert_prs, stats.reverted_prs, stats.shipped_prs
+        ));
+    }
+    if let Some(weighted) = stats.weighted_merged_prs {
+        let mut repositories: Vec<(&String, &f64)> = options.weights.repositories.iter().collect();
+        repositories.sort_by(|a, b| b.1.total_cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        let repositories: Vec<String> = repositories
+            .iter()
+            .map(|(repo, weight)| format!("{repo} ×{weight}"))
+            .collect();
+        prompt.push_str(&format!(
+            "- リポジトリの重要度で重み付けしたマージ済みPR数: {weighted:.1}（重み付けなし: {}件、重要度: {}、その他 ×1）\n",
+            stats.merged_prs,
+            repositories.join("、")
+        ));
+    }
+    prompt.push_str(&format!(
+        "- Issue総数: {}件（オープン: {}件、クローズ: {}件）\n",
+        stats.total_issues, stats.open_issues, stats.closed_issues
+    ));
+    let unshipped = unshipped_items(query, prs, issues, options);
+    if !unshipped.is_empty() {
+        prompt.push_str(&format!(
+            "- 出荷されなかった作業: {}日以上オープンのPR {}件、{}日以上動きのないIssue {}件、マージされずにクローズしたPR {}件\n",
+            unshipped::LONG_OPEN_DAYS,
+            unshipped::count(&unshipped, UnshippedKind::LongOpenPr),
+            unshipped::STALE_ISSUE_DAYS,
+            unshipped::count(&unshipped, UnshippedKind::StaleIssue),
+            unshipped::count(&unshipped, UnshippedKind::ClosedUnmerged)
+        ));
+    }
+    if !options.discussions.is_empty() {
+        let discussions = DiscussionStats::compute(&options.discussions, &query.author);
+        prompt.push_str(&format!(
+            "- Discussions: 作成 {}件、他のメンバーのDiscussionへの回答・コメント {}件（採用された回答: {}件）\n",
+            discussions.created, discussions.participated, discussions.accepted_answers
+        ));
+    }
+    if !options.releases.is_empty() {
+        let repositories: HashSet<&str> = options
+            .releases
+            .iter()
+            .map(|r| r.repository.as_str())
+            .collect();
+        prompt.push_str(&format!(
+            "- 公開したリリース: {}件（{}リポジトリ）\n",
+            options.releases.len(),
+            repositories.len()
+        ));
+    }
+    if !options.triage.is_empty() {
+        let triage = TriageStats::compute(&options.triage);
+        prompt.push_str(&format!(
+            "- トリアージ: 他のメンバーのIssue {}件（ラベル付け {}件、クローズ {}件〈うち重複 {}件、対応しない {}件〉、コメント {}件〈うち最初の応答 {}件、{:.0}%〉）\n",
+            triage.issues,
+            triage.labels_applied,
+            triage.closed,
+            triage.closed_as_duplicate,
+            triage.closed_as_not_planned,
+            triage.commented,
+            triage.first_responses,
+            triage.first_response_rate() * 100.0
+        ));
+    }
+    if !options.mentions.is_empty() {
+        let mentions = MentionStats::compute(&options.mentions);
+        prompt.push_str(&format!(
+            "- メンション・参照: 他のメンバーのPR/Issueでのメンション {}件、他のリポジトリからの参照 {}件（参照された本人のPR {}件）\n",
+            mentions.mentioned, mentions.cross_referenced, mentions.referenced_prs
+        ));
+        let describe = |counts: &[(String, usize)]| {
+            counts
+                .iter()
+                .map(|(name, count)| format!("{name}（{count}件）"))
+                .collect::<Vec<_>>()
+                .join("、")
+        };
+        if !mentions.top_repositories.is_empty() {
+            prompt.push_str(&format!(
+                "  - 参照の多いリポジトリ: {}\n",
+                describe(&mentions.top_repositories)
+            ));
+        }
+        if !mentions.top_people.is_empty() {
+            prompt.push_str(&format!(
+                "  - 参照の多い人: {}\n",
+                describe(&mentions.top_people)
+            ));
+        }
+    }
+    let community = &options.community;
+    if !community.is_empty() {
+        let repositories: HashSet<&str> = community
+            .oss_prs
+            .iter()
+            .map(|pr| pr.repository.name_with_owner.as_str())
+            .collect();
+        prompt.push_str(&format!(
+            "- コミュニティへの貢献: 社外のOSSでマージされたPR {}件（{}リポジトリ）、公開Gist {}件\n",
+            community.oss_prs.len(),
+            repositories.len(),
+            community.gists.len()
+        ));
+    }
+    let given = &stats.reviews_given;
+    if !given.is_empty() {
+        prompt.push_str(&format!(
+            "- 他の人のPRへのレビュー: {}件のPR（承認: {}件、変更要求: {}件、コメント: {}件{}）、開始したレビュースレッド: {}件（解決済み: {}件{}）\n",
+            given.reviewed_prs,
+            given.approvals,
+            given.changes_requested,
+            given.commented,
+            given
+                .approval_rate()
+                .map_or(String::new(), |r| format!("、承認率 {r:.0}%")),
+            given.threads_opened,
+            given.threads_resolved,
+            given
+                .resolution_rate()
+                .map_or(String::new(), |r| format!("、解決率 {r:.0}%"))
+        ));
+    }
+    let reviews = &stats.reviews_received;
+    if reviews.total() > 0 {
+        prompt.push_str(&format!(
+            "- 受けたレビュー: {}件のPRに{}件（承認: {}件、変更要求: {}件、コメント: {}件、レビュアー: {}人）\n",
+            reviews.reviewed_prs,
+            reviews.total(),
+            reviews.approvals,
+            reviews.changes_requested,
+            reviews.commented,
+            reviews.reviewers.len()
+        ));
+    }
+    let incidents = IncidentStats::compute(prs, issues, &options.incidents);
+    if !incidents.is_empty() {
+        prompt.push_str(&format!(
+            "- 障害対応・オンコール: PR {}件（マージ済み: {}件）、Issue {}件（{}リポジトリ）\n",
+            incidents.prs,
+            incidents.merged_prs,
+            incidents.issues,
+            incidents.repositories.len()
+        ));
+    }
+    let docs = &stats.documentation;
+    if docs.lines > 0 {
+        prompt.push_str(&format!(
+            "- ドキュメントへの貢献: 主にドキュメントを変更したPR {}件（マージ済み: {}件）、ドキュメントの変更行数 {}行\n",
+            docs.prs, docs.merged_prs, docs.lines
+        ));
+    }
+    let engagement = &stats.engagement;
+    if !engagement.is_empty() {
+        prompt.push_str(&format!(
+            "- 反響（リアクション）: 👍 {}件、🎉 {}件、❤️・🚀 {}件、合計 {}件（リアクションが付いたPR/Issue {}件、自分のコメントへのリアクション {}件）\n",
+            engagement.thumbs_up,
+            engagement.hooray,
+            engagement.heart_and_rocket,
+            engagement.total,
+            engagement.reacted_items,
+            engagement.comment_reactions
+        ));
+    }
+    let shipping = &stats.shipping;
+    if !shipping.is_empty() {
+        prompt.push_str(&format!(
+            "- マージ済みPRのCI・デプロイ: CIが全て成功 {}件／{}件、デプロイ成功 {}件（うち本番環境 {}件）\n",
+            shipping.passing_prs,
+            shipping.checked_prs,
+            shipping.deployed_prs,
+            shipping.production_prs
+        ));
+    }
+    let resolved = &stats.resolved_issues;
+    if resolved.resolved > 0 {
+        prompt.push_str(&format!(
+            "- マージ済みPRで解決したIssue: {}件（うち他のメンバーが起票: {}件）\n",
+            resolved.resolved, resolved.filed_by_others
+        ));
+    }
+    if !stats.topics.is_empty() {
+        let topics: Vec<String> = stats
+            .topics
+            .iter()
+            .map(|(topic, count)| format!("{topic} {count}件"))
+            .collect();
+        prompt.push_str(&format!(
+            "- トピック別のPR/Issue数: {}\n",
+            topics.join("、")
+        ));
+    }
+    if !stats.tech_stack.is_empty() {
+        let total: usize = stats.tech_stack.iter().map(|(_, lines)| lines).sum();
+        let languages: Vec<String> = stats
+            .tech_stack
+            .iter()
+            .take(8)
+            .map(|(language, lines)| format!("{language} {}%", lines * 100 / total))
+            .collect();
+        prompt.push_str(&format!(
+            "- 変更行数による技術スタック: {}\n",
+            languages.join("、")
+        ));
+    }
+    let prs_by_area = areas::breakdown(prs, &options.monorepo);
+    if !prs_by_area.is_empty() {
+        let top: Vec<String> = prs_by_area
+            .iter()
+            .take(10)
+            .map(|a| format!("{} {}件（{}行）", a.label(), a.prs, a.lines))
+            .collect();
+        prompt.push_str(&format!(
+            "- モノレポの領域別のPR数（変更ファイルのパス、上位10件）: {}\n",
+            top.join("、")
+        ));
+    }
+    let impact = stats::impact_by_repository(prs, &options.weights);
+    if impact.len() > 1 {
+        let top: Vec<String> = impact
+            .iter()
+            .take(5)
+            .map(|(repo, score)| format!("{repo}（{score:.1}）"))
+            .collect();
+        prompt.push_str(&format!(
+            "- リポジトリ別の重要度スコア（上位5件）: {}\n",
+            top.join("、")
+        ));
+    }
+    let turnaround = &stats.turnaround;
+    if let Some(hours) = turnaround.time_to_merge {
+        prompt.push_str(&format!(
+            "- 作成からマージまでの時間（中央値）: {}（{}件のPR）\n",
+            stats::format_hours(hours),
+            turnaround.merged_samples
+        ));
+    }
+    if let Some(hours) = turnaround.time_to_first_review {
+        prompt.push_str(&format!(
+            "- 作成から最初のレビューを受けるまでの時間（中央値）: {}（{}件のPR）\n",
+            stats::format_hours(hours),
+            turnaround.first_review_samples
+        ));
+    }
+    if let Some(hours) = turnaround.review_response {
+        prompt.push_str(&format!(
+            "- 他のメンバーのPRへのレビュー応答時間（中央値）: {}（{}件のPR）\n",
+            stats::format_hours(hours),
+            turnaround.review_response_samples
+        ));
+    }
+    prompt.push('\n');
+
+    // 期間中の推移
+    let mut timeline =
+        stats::timeline(prs, issues, &query.since, &query.until, options.granularity);
+    stats::mark_gaps(&mut timeline, options.granularity, &options.gaps);
+    if !timeline.is_empty() {
+        prompt.push_str(&format!(
+            "## 期間中の推移（{}）\n",
+            options.granularity.label()
+        ));
+        for bucket in &timeline {
+            let gap = bucket
+                .gap
+                .as_ref()
+                .map(|gap| format!("（{gap}のため推移の評価から除外）"))
+                .unwrap_or_default();
+            prompt.push_str(&format!(
+                "- {}: PR {}件、Issue {}件{gap}\n",
+                bucket.period, bucket.prs, bucket.issues
+            ));
+        }
+        prompt.push('\n');
+    }
+    if !options.gaps.is_empty() {
+        prompt.push_str("## 活動のない期間\n");
+        for gap in &options.gaps {
+            prompt.push_str(&format!("- {}\n", gap.describe()));
+        }
+        prompt.push('\n');
+    }
+
+    // 障害対応のPR/Issue
+    if !incidents.is_empty() {
+        prompt.push_str("## 障害対応・オンコールの作業（ラベル・タイトルから判定）\n");
+        let items = prs
+            .iter()
+            .filter(|pr| options.incidents.is_incident(&pr.title, &pr.labels))
+            .map(|pr| (&pr.url, &pr.title, &pr.state))
+            .chain(
+                issues
+                    .iter()
+                    .filter(|issue| options.incidents.is_incident(&issue.title, &issue.labels))
+                    .map(|issue| (&issue.url, &issue.title, &issue.state)),
+            );
+        for (url, title, state) in items {
+            prompt.push_str(&format!("- {title}（{state}）: {url}\n"));
+        }
+        prompt.push('\n');
+    }
+
+    // ドキュメント中心のPR
+    if stats.documentation.prs > 0 {
+        prompt.push_str("## ドキュメントへの貢献（変更行数の過半がドキュメントのPR）\n");
+        for pr in prs.iter().filter(|pr| techstack::is_docs_pr(pr)) {
+            prompt.push_str(&format!("- {}（{}）: {}\n", pr.title, pr.state, pr.url));
+        }
+        prompt.push('\n');
+    }
+
+    // 協業相手
+    if !stats.collaborators.is_empty() {
+        prompt.push_str("## 主な協業相手（やり取りの多い順、上位10人）\n");
+        for c in stats.collaborators.iter().take(10) {
+            let mut kinds = Vec::new();
+            for (label, count) in [
+                ("コメント", c.comments),
+                ("受けたレビュー", c.reviews_received),
+                ("したレビュー", c.reviews_given),
+                ("共同作成", c.co_authored),
+            ] {
+                if count > 0 {
+                    kinds.push(format!("{label} {count}"));
+                }
+            }
+            prompt.push_str(&format!(
+                "- {}: {}回（{}）／リポジトリ: {}\n",
+                c.login,
+                c.total(),
+                kinds.join("、"),
+                c.repositories
+                    .iter()
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        prompt.push('\n');
+    }
+
+    // 内容の近いPRのクラスタ（このプロンプトに含まれるPRのみ）
+    let pr_urls: HashSet<&str> = prs.iter().map(|pr| pr.url.as_str()).collect();
+    let clusters: Vec<(&str, Vec<&str>)> = options
+        .clusters
+        .iter()
+        .map(|c| {
+            let urls = c
+                .pr_urls
+                .iter()
+                .map(String::as_str)
+                .filter(|url| pr_urls.contains(url))
+                .collect::<Vec<_>>();
+            (c.label.as_str(), urls)
+        })
+        .filter(|(_, urls)| !urls.is_empty())
+        .collect();
+    if !clusters.is_empty() {
+        prompt.push_str("## プロジェクト候補（内容の近いPRのクラスタ）\n");
+        for (label, urls) in &clusters {
+            prompt.push_str(&format!(
+                "- {label}（{}件）: {}\n",
+                urls.len(),
+                urls.join(" ")
+            ));
+        }
+        prompt.push('\n');
+    }
+
+    // マイルストーン・プロジェクトごとのPR/Issue（--roadmap指定時のみ）
+    let groups = roadmap_groups(prs, issues);
+    if !groups.is_empty() {
+        prompt.push_str("## マイルストーン・プロジェクト別のPR/Issue\n");
+        for (group, urls) in &groups {
+            prompt.push_str(&format!(
+                "- {group}（{}件）: {}\n",
+                urls.len(),
+                urls.join(" ")
+            ));
+        }
+        prompt.push('\n');
+    }
+
+    for section in item_sections(query, prs, issues, options) {
+        push_jsonl(&mut prompt, &section.heading, section.items.into_iter())?;
+    }
+
+    Ok(prompt)
+}
+
+// プロンプトに含めるPR/Issueなどの一覧（見出しと、1件ずつのJSON）
+struct ItemSection {
+    heading: String,
+    /// `--export-jsonl`で付ける種類（pr/issue/release/discussion/quote/triage/mention/oss_pr/gistなど）
+    kind: &'static str,
+    items: Vec<serde_json::Value>,
+}
+
+impl ItemSection {
+    fn new(
+        kind: &'static str,
+        heading: impl Into<String>,
+        items: impl Iterator<Item = serde_json::Value>,
+    ) -> Self {
+        Self {
+            heading: heading.into(),
+            kind,
+            items: items.collect(),
+        }
+    }
+}
+
+fn item_sections(
+    query: &SearchQuery,
+    prs: &[PullRequest],
+    issues: &[Issue],
+    options: &PromptOptions,
+) -> Vec<ItemSection> {
+    let mut sections = Vec::new();
+    let pr_detail_json = |pr| with_areas(pr_detail_json(query, pr), pr, &options.monorepo);
+    let pr_metadata_json = |pr| with_areas(pr_metadata_json(query, pr), pr, &options.monorepo);
+
+    // PRをJSONL形式で送信（--highlights指定時は上位のみ詳細、--metadata-only指定時は全件メタデータのみ）
+    if options.metadata_only {
+        sections.push(ItemSection::new(
+            "pr",
+            "## Pull Requestデータ（JSONL形式、メタデータのみ）",
+            prs.iter().map(pr_metadata_json),
+        ));
+    } else if let Some(n) = options.highlights.filter(|n| *n < prs.len()) {
+        let top = options.weights.select_top(prs, n);
+        sections.push(ItemSection::new(
+            "pr",
+            format!(
+                "## 主要なPull Requestデータ（JSONL形式、重要度の高い{}件）",
+                top.len()
+            ),
+            prs.iter()
+                .enumerate()
+                .filter(|(i, _)| top.contains(i))
+                .map(|(_, pr)| pr_detail_json(pr)),
+        ));
+        sections.push(ItemSection::new(
+            "pr",
+            "## その他のPull Requestデータ（JSONL形式、メタデータのみ）",
+            prs.iter()
+                .enumerate()
+                .filter(|(i, _)| !top.contains(i))
+                .map(|(_, pr)| pr_metadata_json(pr)),
+        ));
+    } else {
+        sections.push(ItemSection::new(
+            "pr",
+            "## Pull Requestデータ（JSONL形式）",
+            prs.iter().map(pr_detail_json),
+        ));
+    }
+
+    // 全IssueをJSONL形式で送信
+    if options.metadata_only {
+        sections.push(ItemSection::new(
+            "issue",
+            "## Issueデータ（JSONL形式、メタデータのみ）",
+            issues.iter().map(|issue| issue_metadata_json(query, issue)),
+        ));
+    } else {
+        sections.push(ItemSection::new(
+            "issue",
+            "## Issueデータ（JSONL形式）",
+            issues.iter().map(|issue| issue_detail_json(query, issue)),
+        ));
+    }
+
+    // 出荷されなかった作業はタイトルと日数のみ送信
+    let unshipped = unshipped_items(query, prs, issues, options);
+    if !unshipped.is_empty() {
+        sections.push(ItemSection::new(
+            "unshipped",
+            "## 出荷されなかった作業（JSONL形式、daysは種類ごとの経過日数）",
+            unshipped
+                .into_iter()
+                .map(|item| serde_json::to_value(item).unwrap_or_default()),
+        ));
+    }
+
+    // リリースはタグとタイトルのみ送信
+    if !options.releases.is_empty() {
+        sections.push(ItemSection::new(
+            "release",
+            "## リリースデータ（JSONL形式）",
+            options.releases.iter().map(|r| {
+                serde_json::json!({
+                    "url": r.url,
+                    "repository": r.repository,
+                    "tag": r.tag_name,
+                    "title": r.name.as_deref().unwrap_or(""),
+                    "published_at": r.published_at
+                })
+            }),
+        ));
+    }
+
+    // Discussionはタイトル・件数などのメタデータのみ送信
+    if !options.discussions.is_empty() {
+        sections.push(ItemSection::new(
+            "discussion",
+            "## Discussionデータ（JSONL形式）",
+            options
+                .discussions
+                .iter()
+                .map(|d| discussion_json(&query.author, d)),
+        ));
+    }
+
+    // 受けたコメント・レビューからの引用（メタデータのみの場合は本文を送らない）
+    let quotes = received_quotes(query, prs, issues, options);
+    if !quotes.is_empty() {
+        sections.push(ItemSection::new(
+            "quote",
+            "## 受け取ったコメントからの引用（JSONL形式、quoteは原文のまま）",
+            quotes.into_iter().map(|q| {
+                serde_json::json!({
+                    "url": q.url,
+                    "from": q.from,
+                    "kind": q.kind,
+                    "quote": q.text,
+                    "created_at": q.created_at
+                })
+            }),
+        ));
+    }
+
+    // トリアージはタイトルと行った操作のみ送信
+    if !options.triage.is_empty() {
+        sections.push(ItemSection::new(
+            "triage",
+            "## トリアージしたIssue（JSONL形式）",
+            options.triage.iter().map(|item| {
+                serde_json::json!({
+                    "url": item.url,
+                    "title": item.title,
+                    "repository": item.repository.name_with_owner,
+                    "filed_by": item.author.as_ref().map(|a| a.login.as_str()),
+                    "labels_applied": item.labels_applied,
+                    "closed_as": item.closed_as,
+                    "first_responder": item.first_responder
+                })
+            }),
+        ));
+    }
+
+    // メンション・参照は参照元のタイトルと相手のみ送信
+    let mentions = &options.mentions;
+    if !mentions.is_empty() {
+        sections.push(ItemSection::new(
+            "mention",
+            "## メンション・他のリポジトリからの参照（JSONL形式）",
+            mentions
+                .mentioned_in
+                .iter()
+                .map(|r| ("mention", r))
+                .chain(
+                    mentions
+                        .cross_references
+                        .iter()
+                        .map(|r| ("cross_reference", r)),
+                )
+                .map(|(kind, r)| {
+                    serde_json::json!({
+                        "kind": kind,
+                        "url": r.url,
+                        "title": r.title,
+                        "repository": r.repository.name_with_owner,
+                        "by": r.author.as_ref().map(|a| a.login.as_str()),
+                        "referenced_pr": r.referenced,
+                        "created_at": r.created_at
+                    })
+                }),
+        ));
+    }
+
+    // コミュニティへの貢献は社内の活動と分けて送信
+    let community = &options.community;
+    if !community.oss_prs.is_empty() {
+        sections.push(ItemSection::new(
+            "oss_pr",
+            "## コミュニティへの貢献: 社外のOSSでマージされたPR（JSONL形式）",
+            community.oss_prs.iter().map(|pr| {
+                serde_json::json!({
+                    "url": pr.url,
+                    "title": pr.title,
+                    "repository": pr.repository.name_with_owner,
+                    "merged_at": pr.closed_at,
+                    "labels": labels_json(&pr.labels)
+                })
+            }),
+        ));
+    }
+    if !community.gists.is_empty() {
+        sections.push(ItemSection::new(
+            "gist",
+            "## コミュニティへの貢献: 公開Gist（JSONL形式）",
+            community.gists.iter().map(|gist| {
+                serde_json::json!({
+                    "url": gist.url,
+                    "description": gist.description.as_deref().unwrap_or(""),
+                    "files": gist.files,
+                    "created_at": gist.created_at
+                })
+            }),
+        ));
+    }
+
+    sections
+}
+
+// 受けたコメント・レビューからの引用（`--quotes`指定時のみ、メタデータのみの場合は含めない）
+fn received_quotes(
+    query: &SearchQuery,
+    prs: &[PullRequest],
+    issues: &[Issue],
+    options: &PromptOptions,
+) -> Vec<Quote> {
+    if !options.quotes || options.metadata_only {
+        return Vec::new();
+    }
+    quotes::extract(prs, issues, &query.author)
+}
+
+// モノレポのPRに変更した領域（areas）を加える
+fn with_areas(
+    mut pr_data: serde_json::Value,
+    pr: &PullRequest,
+    monorepo: &MonorepoConfig,
+) -> serde_json::Value {
+    let areas = monorepo.areas(pr);
+    if !areas.is_empty() {
+        pr_data["areas"] = areas.into();
+    }
+    pr_data
+}
+
+/// プロンプトに含めるものと同じ1件ずつのJSONに、種類（`type`）を加えたもの
+///
+/// `--export-jsonl`で書き出し、スクリプトやノートブックで再利用するためのものです。
+pub fn activity_items(
+    query: &SearchQuery,
+    prs: &[PullRequest],
+    issues: &[Issue],
+    options: &PromptOptions,
+) -> Vec<serde_json::Value> {
+    item_sections(query, prs, issues, options)
+        .into_iter()
+        .flat_map(|section| {
+            section.items.into_iter().map(move |mut item| {
+                if let Some(object) = item.as_object_mut() {
+                    object.insert("type".to_string(), section.kind.into());
+                }
+                item
+            })
+        })
+        .collect()
+}
+
+// マイルストーン・プロジェクトごとのPR/IssueのURL（件数の多い順）
+fn roadmap_groups<'a>(prs: &'a [PullRequest], issues: &'a [Issue]) -> Vec<(String, Vec<&'a str>)> {
+    let items = prs.iter().map(|pr| (&pr.roadmap, pr.url.as_str())).chain(
+        issues
+            .iter()
+            .map(|issue| (&issue.roadmap, issue.url.as_str())),
+    );
+    let mut groups: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    for (roadmap, url) in items {
+        if let Some(milestone) = &roadmap.milestone {
+            groups
+                .entry(format!("マイルストーン「{milestone}」"))
+                .or_default()
+                .push(url);
+        }
+        for project in &roadmap.projects {
+            groups
+                .entry(format!("プロジェクト「{project}」"))
+                .or_default()
+                .push(url);
+        }
+    }
+    let mut groups: Vec<_> = groups.into_iter().collect();
+    groups.sort_by_key(|(_, urls)| std::cmp::Reverse(urls.len()));
+    groups
+}
+
+// 見出しとJSONLのコードブロックを追加
+fn push_jsonl(
+    prompt: &mut String,
+    heading: &str,
+    items: impl Iterator<Item = serde_json::Value>,
+) -> Result<()> {
+    prompt.push_str(&format!("{heading}\n```\n"));
+    for item in items {
+        prompt.push_str(&format!("{}\n", serde_json::to_string(&item)?));
+    }
+    prompt.push_str("```\n\n");
+    Ok(())
+}
+
+fn pr_detail_json(query: &SearchQuery, pr: &PullRequest) -> serde_json::Value {
+    let mut pr_data = serde_json::json!({
+        "url": pr.url,
+        "title": pr.title,
+        "description": pr.body.as_deref().unwrap_or(""),
+        "status": pr.state,
+        "repository": pr.repository.name_with_owner,
+        "created_at": pr.created_at,
+        "comments": comments_json(&pr.comments),
+        "reviews": reviews_json(&pr.reviews),
+        "closes": closing_issues_json(&pr.closing_issues)
+    });
+    if !pr.topics.is_empty() {
+        pr_data["topics"] = pr.topics.clone().into();
+    }
+    push_assignment_fields(&mut pr_data, &pr.assignees, &pr.requested_reviewers);
+    push_roadmap_fields(&mut pr_data, &pr.roadmap);
+    push_reaction_fields(&mut pr_data, &pr.reactions);
+    push_revert_fields(&mut pr_data, pr);
+    push_ship_fields(&mut pr_data, pr);
+    if techstack::is_docs_pr(pr) {
+        pr_data["documentation"] = true.into();
+    }
+    if let Some(author) = item_author(query, pr.author.as_ref()) {
+        pr_data["author"] = author.into();
+    }
+    if !pr.commits.is_empty() {
+        pr_data["commits_count"] = pr.commits.len().into();
+        pr_data["commits"] = commits_json(&pr.commits).into();
+    }
+    pr_data
+}
+
+// コミットメッセージの1行目（多い場合は最初と最後の一部のみ）
+fn commits_json(commits: &[Commit]) -> Vec<&str> {
+    let subjects: Vec<&str> = commits.iter().map(Commit::subject).collect();
+    if subjects.len() <= MAX_COMMIT_SUBJECTS {
+        return subjects;
+    }
+    let half = MAX_COMMIT_SUBJECTS / 2;
+    subjects[..half]
+        .iter()
+        .chain(&["…"])
+        .chain(&subjects[subjects.len() - half..])
+        .copied()
+        .collect()
+}
+
+// 本文・コメントを含まないPRのメタデータ
+fn pr_metadata_json(query: &SearchQuery, pr: &PullRequest) -> serde_json::Value {
+    let mut pr_data = serde_json::json!({
+        "url": pr.url,
+        "title": pr.title,
+        "status": pr.state,
+        "repository": pr.repository.name_with_owner,
+        "created_at": pr.created_at,
+        "labels": labels_json(&pr.labels),
+        "comments_count": pr.comments_count.max(pr.comments.len()),
+        "reviews_count": pr.reviews.len(),
+        "closes_count": pr.closing_issues.len()
+    });
+    if !pr.commits.is_empty() {
+        pr_data["commits_count"] = pr.commits.len().into();
+    }
+    if !pr.topics.is_empty() {
+        pr_data["topics"] = pr.topics.clone().into();
+    }
+    push_assignment_fields(&mut pr_data, &pr.assignees, &pr.requested_reviewers);
+    push_roadmap_fields(&mut pr_data, &pr.roadmap);
+    push_reaction_fields(&mut pr_data, &pr.reactions);
+    push_revert_fields(&mut pr_data, pr);
+    push_ship_fields(&mut pr_data, pr);
+    if techstack::is_docs_pr(pr) {
+        pr_data["documentation"] = true.into();
+    }
+    if let Some(author) = item_author(query, pr.author.as_ref()) {
+        pr_data["author"] = author.into();
+    }
+    pr_data
+}
+
+// 担当者とレビュー依頼先（いる場合のみ）
+fn push_assignment_fields(
+    data: &mut serde_json::Value,
+    assignees: &[CommentAuthor],
+    requested_reviewers: &[String],
+) {
+    if !assignees.is_empty() {
+        data["assignees"] = assignees
+            .iter()
+            .map(|a| a.login.as_str())
+            .collect::<Vec<_>>()
+            .into();
+    }
+    if !requested_reviewers.is_empty() {
+        data["requested_reviewers"] = requested_reviewers.into();
+    }
+}
+
+// マイルストーンとプロジェクト（属している場合のみ）
+fn push_roadmap_fields(data: &mut serde_json::Value, roadmap: &Roadmap) {
+    if let Some(milestone) = &roadmap.milestone {
+        data["milestone"] = milestone.clone().into();
+    }
+    if !roadmap.projects.is_empty() {
+        data["projects"] = roadmap.projects.clone().into();
+    }
+}
+
+// リアクションの数（付いている場合のみ）
+fn push_reaction_fields(data: &mut serde_json::Value, reactions: &Reactions) {
+    if reactions.total > 0 {
+        data["reactions"] = serde_json::json!({
+            "+1": reactions.thumbs_up,
+            "hooray": reactions.hooray,
+            "total": reactions.total
+        });
+    }
+}
+
+// ドラフト・revertの情報（該当するPRのみ）
+fn push_revert_fields(pr_data: &mut serde_json::Value, pr: &PullRequest) {
+    if pr.is_draft {
+        pr_data["draft"] = true.into();
+    }
+    if let Some(url) = &pr.reverts {
+        pr_data["reverts"] = url.clone().into();
+    }
+    if let Some(url) = &pr.reverted_by {
+        pr_data["reverted_by"] = url.clone().into();
+    }
+}
+
+// マージコミットのCI結果と、デプロイに成功した環境（取得したPRのみ）
+fn push_ship_fields(pr_data: &mut serde_json::Value, pr: &PullRequest) {
+    if !pr.checks.is_empty() {
+        let failed: Vec<&str> = pr
+            .checks
+            .iter()
+            .filter(|c| !c.passed())
+            .map(|c| c.name.as_str())
+            .collect();
+        pr_data["ci"] = if failed.is_empty() {
+            "passed".into()
+        } else {
+            format!("failed: {}", failed.join(", ")).into()
+        };
+    }
+    let mut environments: Vec<&str> = pr
+        .deployments
+        .iter()
+        .filter(|d| d.succeeded())
+        .map(|d| d.environment.as_str())
+        .collect();
+    environments.sort_unstable();
+    environments.dedup();
+    if !environments.is_empty() {
+        pr_data["deployed_to"] = environments.into();
+    }
+}
+
+fn issue_detail_json(query: &SearchQuery, issue: &Issue) -> serde_json::Value {
+    let mut issue_data = serde_json::json!({
+        "url": issue.url,
+        "title": issue.title,
+        "description": issue.body.as_deref().unwrap_or(""),
+        "status": issue.state,
+        "repository": issue.repository.name_with_owner,
+        "created_at": issue.created_at,
+        "comments": comments_json(&issue.comments)
+    });
+    if !issue.topics.is_empty() {
+        issue_data["topics"] = issue.topics.clone().into();
+    }
+    push_assignment_fields(&mut issue_data, &issue.assignees, &[]);
+    push_roadmap_fields(&mut issue_data, &issue.roadmap);
+    push_reaction_fields(&mut issue_data, &issue.reactions);
+    if let Some(author) = item_author(query, issue.author.as_ref()) {
+        issue_data["author"] = author.into();
+    }
+    issue_data
+}
+
+// 本文・コメントを含まないIssueのメタデータ
+fn issue_metadata_json(query: &SearchQuery, issue: &Issue) -> serde_json::Value {
+    let mut issue_data = serde_json::json!({
+        "url": issue.url,
+        "title": issue.title,
+        "status": issue.state,
+        "repository": issue.repository.name_with_owner,
+        "created_at": issue.created_at,
+        "labels": labels_json(&issue.labels),
+        "comments_count": issue.comments_count.max(issue.comments.len())
+    });
+    if !issue.topics.is_empty() {
+        issue_data["topics"] = issue.topics.clone().into();
+    }
+    push_assignment_fields(&mut issue_data, &issue.assignees, &[]);
+    push_roadmap_fields(&mut issue_data, &issue.roadmap);
+    push_reaction_fields(&mut issue_data, &issue.reactions);
+    if let Some(author) = item_author(query, issue.author.as_ref()) {
+        issue_data["author"] = author.into();
+    }
+    issue_data
+}
+
+fn discussion_json(author: &str, discussion: &Discussion) -> serde_json::Value {
+    serde_json::json!({
+        "url": discussion.url,
+        "title": discussion.title,
+        "repository": discussion.repository.name_with_owner,
+        "category": discussion.category,
+        "created_at": discussion.created_at,
+        "comments_count": discussion.comments_count,
+        "created_by_author": discussion.author.as_ref().is_some_and(|a| a.login == author),
+        "accepted_answer_by_author": discussion.answer_author.as_deref() == Some(author)
+    })
+}
+
+fn labels_json(labels: &[Label]) -> Vec<&str> {
+    labels.iter().map(|l| l.name.as_str()).collect()
+}
+
+// 作成者以外の関わり方で検索した場合のみ、PR/Issueの作成者を含める
+fn item_author<'a>(query: &SearchQuery, author: Option<&'a CommentAuthor>) -> Option<&'a str> {
+    if query.role == Role::Author {
+        return None;
+    }
+    Some(author.map(|a| a.login.as_str()).unwrap_or("Unknown"))
+}
+
+fn comments_json(comments: &[Comment]) -> Vec<serde_json::Value> {
+    comments
+        .iter()
+        .map(|c| {
+            serde_json::json!({
+                "user": c.author.as_ref().map(|a| a.login.as_str()).unwrap_or("Unknown"),
+                "comment_body": &c.body,
+                "created_at": &c.created_at
+            })
+        })
+        .collect()
+}
+
+fn reviews_json(reviews: &[Review]) -> Vec<serde_json::Value> {
+    reviews
+        .iter()
+        .map(|r| {
+            serde_json::json!({
+                "reviewer": r.author.as_ref().map(|a| a.login.as_str()).unwrap_or("Unknown"),
+                "state": &r.state,
+                "review_body": &r.body
+            })
+        })
+        .collect()
+}
+
+fn closing_issues_json(issues: &[LinkedIssue]) -> Vec<serde_json::Value> {
+    issues
+        .iter()
+        .map(|i| {
+            let mut issue = serde_json::json!({
+                "url": &i.url,
+                "title": i.title.as_deref().unwrap_or(""),
+                "filed_by": i.author.as_deref().unwrap_or("Unknown")
+            });
+            if !i.labels.is_empty() {
+                issue["labels"] = i.labels.clone().into();
+            }
+            issue
+        })
+        .collect()
+}
+
+fn target_level_label(options: &PromptOptions) -> String {
+    options
+        .target_level
+        .as_deref()
+        .map_or("上位レベル".to_string(), |level| {
+            format!("{level}レベル")
+        })
+}
+
+// 昇格推薦資料の構成（昇格基準ごとにエビデンスを示す）
+fn push_promo_items(prompt: &mut String, options: &PromptOptions) {
+    let level = target_level_label(options);
+
+    prompt.push_str("【推薦資料の構成】\n");
+    prompt.push_str(&format!(
+        "1. 推薦サマリー（{level}に相応しい理由を3点で箇条書き）\n"
+    ));
+    prompt.push_str("2. スコープ\n");
+    prompt.push_str("   - 担当した領域の広さ、複数リポジトリやチームにまたがる取り組み\n");
+    prompt.push_str("3. インパクト\n");
+    prompt.push_str("   - プロダクト・ビジネス・チームにもたらした成果\n");
+    prompt.push_str("4. 技術的な深さ\n");
+    prompt.push_str("   - 難易度の高い設計・実装、技術的な意思決定\n");
+    prompt.push_str("5. リーダーシップ\n");
+    prompt.push_str("   - レビューやメンタリング、課題の推進、他のメンバーへの支援\n");
+    prompt.push_str(&format!(
+        "6. {level}として期待される役割と今後の伸びしろ\n\n"
+    ));
+
+    prompt.push_str("【ルール】\n");
+    prompt.push_str(
+        "- 各基準の主張ごとに、根拠となるPR/IssueのURLをエビデンスとして箇条書きで明示する\n",
+    );
+    prompt.push_str(&format!(
+        "- {level}に一般的に期待される水準と照らし、各基準をどの程度満たしているかを記述する\n"
+    ));
+    prompt.push_str("- 関連するPRはまとめて一つの取り組みとして表現する\n\n");
+
+    prompt.push_str("【重要】推薦者が審査の場でそのまま使えるよう、主張とエビデンスを明確に対応させてください。\n");
+}
+
+// 週報・1on1用トピックの構成
+fn push_weekly_items(prompt: &mut String) {
+    prompt.push_str("【構成】\n");
+    prompt.push_str("1. リリース済み（マージされたPR・クローズしたIssue）\n");
+    prompt.push_str("2. 進行中（オープンなPR・Issue）\n");
+    prompt.push_str("3. ブロッカー・相談したいこと\n");
+    prompt.push_str("   - 変更要求を受けたまま、またはレビュー待ちで止まっているPR、議論が長引いているIssueなどから推測\n\n");
+
+    prompt.push_str("【ルール】\n");
+    prompt.push_str("- 各項目は1行の箇条書きとし、PR/IssueのURLを添える\n");
+    prompt.push_str("- 関連するPRは1行にまとめる\n");
+    prompt.push_str("- 該当するものがない項目は「なし」と記載する\n");
+    prompt.push_str("- 前置きや総評は不要\n");
+}
+
+// 職務経歴書向けの実績箇条書きの形式
+fn push_resume_items(prompt: &mut String, options: &PromptOptions) {
+    prompt.push_str("【形式】\n");
+    prompt.push_str("- 関連するPRをまとめ、1つの実績につき1行の箇条書きにする（全体で5〜10行）\n");
+    prompt.push_str("- STAR（状況・課題・行動・結果）を凝縮し、「動詞で始まる行動 + 技術 + 成果・インパクト」の順で書く\n");
+    prompt.push_str("- PR数・対応Issue数・対象リポジトリ数など、活動データから数えられる数値で可能な限り定量化する（データにない数値は作らない）\n");
+    prompt.push_str("- 社内固有の名称やURLは含めず、社外の読み手にも伝わる表現にする\n");
+    prompt.push_str("- 箇条書き以外の前置きや総評は不要\n\n");
+
+    match options.resume_language {
+        ResumeLanguage::English => prompt.push_str(
+            "【重要】箇条書きは英語で作成してください（例: \"Led ... , reducing ... by ...\"）。\n",
+        ),
+        ResumeLanguage::Japanese => prompt
+            .push_str("【重要】箇条書きは日本語で作成し、体言止めで簡潔にまとめてください。\n"),
+    }
+}
+
+// 自己評価とピアフィードバック（匿名化のため番号で区別する）
+fn push_feedback(prompt: &mut String, options: &PromptOptions) {
+    if let Some(self_assessment) = &options.self_assessment {
+        prompt.push_str("## 本人の自己評価\n");
+        prompt.push_str(&format!("{}\n\n", self_assessment.trim()));
+    }
+    for (i, feedback) in options.peer_feedback.iter().enumerate() {
+        prompt.push_str(&format!("## ピアフィードバック{}\n", i + 1));
+        prompt.push_str(&format!("{}\n\n", feedback.trim()));
+    }
+}
+
+// 360度評価の構成
+fn push_three_sixty_items(prompt: &mut String) {
+    prompt.push_str("【評価の構成】\n");
+    prompt.push_str("1. 総括（強みと期待を3点程度で）\n");
+    prompt.push_str("2. 強み\n");
+    prompt.push_str("   - 複数の情報源で一致している強みを優先し、フィードバックの記述と裏付けとなるPR/IssueのURLを対応させる\n");
+    prompt.push_str("3. 成長領域\n");
+    prompt.push_str("   - フィードバックで指摘された課題と、活動データから読み取れる傾向\n");
+    prompt.push_str("4. 認識のギャップ\n");
+    prompt.push_str("   - 自己評価・ピアフィードバック・活動データの間で食い違う点（例: 本人の認識より周囲の評価が高い領域、活動データで裏付けられない主張）\n");
+    prompt.push_str("5. 次の評価期間に向けた提案\n\n");
+
+    prompt.push_str("【ルール】\n");
+    prompt.push_str(
+        "- ピアフィードバックは「ピアフィードバック1」のように番号で参照し、書き手を推測しない\n",
+    );
+    prompt.push_str("- 定性的なフィードバックは、可能な限り具体的なPR/Issueで裏付ける。裏付けがないものはその旨を明記する\n");
+    prompt.push_str("- 成果の誇張も過小評価もせず、公平な記述にする\n");
+}
+
+// 期間中に書き溜めた実績の記録（compile-journal）。PRのメタデータより優先する一次情報として扱わせる
+fn push_journal(prompt: &mut String, options: &PromptOptions) {
+    if options.journal.is_empty() {
+        return;
+    }
+    prompt.push_str("\n## 期間中に書き溜めた実績の記録\n");
+    prompt.push_str("各時点で書かれた記録と、本人が後から書き足したメモです。\n\n");
+    for entry in &options.journal {
+        prompt.push_str(&format!(
+            "【{}（{}まで）】\n{}\n\n",
+            entry.heading, entry.until, entry.body
+        ));
+    }
+    prompt.push_str("以上の記録を、期間全体の評価文書の主な根拠として次のように扱ってください。\n");
+    prompt.push_str("- 記録に書かれた成果・背景・判断を優先し、PRのメタデータ（件数・タイトル・ラベル）は裏付けと補足に使う\n");
+    prompt.push_str(
+        "- 記録とJSONLデータが食い違う場合は記録の内容を採り、食い違いがあることを明記する\n",
+    );
+    prompt.push_str("- 複数の記録にまたがる取り組みは1つの成果としてまとめ、期間を通じた変化が分かるように書く\n");
+    prompt.push_str("- 記録にない成果は、JSONLデータで裏付けられる範囲で補う\n");
+}
+
+// 生成し直すときの利用者の指示（--feedback）
+fn push_user_feedback(prompt: &mut String, options: &PromptOptions) {
+    if let Some(feedback) = &options.feedback {
+        prompt.push_str("\n## 利用者からの追加の指示\n");
+        prompt.push_str(&format!("{}\n\n", feedback.trim()));
+        prompt.push_str("以上の指示を、活動データで裏付けられる範囲で最優先に反映してください。\n");
+    }
+}
+
+// 自己評価フォームの設問に回答させる指示
+fn push_questions(prompt: &mut String, questions: &str, tone: Tone) {
+    prompt.push_str("## 自己評価フォーム\n");
+    prompt.push_str(&format!("{}\n\n", questions.trim()));
+
+    prompt.push_str("以上のJSONLデータをもとに、自己評価フォームの各設問に対する回答案を本人の視点（一人称）で日本語で作成してください。\n\n");
+    prompt.push_str("【ルール】\n");
+    prompt.push_str("- 設問の順序と見出しを維持し、各設問の直後に回答を記載する\n");
+    prompt.push_str(
+        "- 回答は活動データに基づく具体的な取り組みで裏付け、根拠となるPR/IssueのURLを添える\n",
+    );
+    prompt.push_str("- 文字数や形式の指定が設問にある場合はそれに従う\n");
+    prompt.push_str("- 活動データから回答できない設問は、推測で埋めずに「（活動データから判断できないため、本人が記入）」と記載する\n\n");
+
+    prompt.push_str(match tone {
+        Tone::Promotional => "【重要】成果を最大限にアピールしつつ、事実に基づいた回答にしてください。\n",
+        Tone::Balanced => "【重要】強みと成長領域の両方を、活動データに基づいて公平に回答してください。誇張は避けてください。\n",
+        Tone::Critical => "【重要】成果は正当に認めつつ、改善が必要な点も具体的な根拠とともに率直に回答してください。\n",
+    });
+}
+
+// 在籍期間・稼働率と、それで換算した件数の目安
+fn push_availability(prompt: &mut String, availability: &Availability, stats: &ActivityStats) {
+    prompt.push_str("## 稼働状況\n");
+    prompt.push_str(&format!(
+        "- 在籍: {}〜{}（評価期間{}日のうち{}日）\n",
+        availability.active_since,
+        availability.active_until,
+        availability.period_days,
+        availability.active_days()
+    ));
+    if availability.capacity < 1.0 {
+        prompt.push_str(&format!(
+            "- 稼働率: {:.0}%（パートタイム・兼務など）\n",
+            availability.capacity * 100.0
+        ));
+    }
+    let ratio = availability.ratio();
+    prompt.push_str(&format!(
+        "- 期間全体をフルタイムで稼働した場合に対する割合: {:.0}%\n",
+        ratio * 100.0
+    ));
+    if ratio > 0.0 {
+        prompt.push_str(&format!(
+            "- フルタイム・期間全体に換算した件数の目安: マージ済みPR {:.1}件、Issue {:.1}件（比較のための参考値であり、実績として記載しない）\n",
+            stats.merged_prs as f32 / ratio,
+            stats.total_issues as f32 / ratio
+        ));
+    }
+    prompt.push('\n');
+}
+
+// Organizationでの役割と所属チーム
+fn push_membership(prompt: &mut String, query: &SearchQuery, membership: &Membership) {
+    if membership.is_empty() {
+        return;
+    }
+    prompt.push_str("## 組織での役割\n");
+    if let Some(role) = &membership.role {
+        let role = match role.as_str() {
+            "admin" => "管理者（admin）",
+            "member" => "メンバー（member）",
+            other => other,
+        };
+        prompt.push_str(&format!(
+            "- {}での役割: {role}\n",
+            query.owner.as_deref().unwrap_or("Organization")
+        ));
+    }
+    for team in &membership.teams {
+        let role = match team.role.as_deref() {
+            Some("MAINTAINER") => "（メンテナー）",
+            _ => "",
+        };
+        let description = team
+            .description
+            .as_deref()
+            .filter(|d| !d.is_empty())
+            .map(|d| format!(": {d}"))
+            .unwrap_or_default();
+        prompt.push_str(&format!("- 所属チーム: {}{role}{description}\n", team.name));
+    }
+    prompt.push('\n');
+}
+
+fn push_evaluation_items(prompt: &mut String, tone: Tone, reliability: bool, community: bool) {
+    prompt.push_str("【評価サマリーに含める項目】\n");
+    prompt.push_str("1. エグゼクティブサマリー（最も印象的な成果を3-5点で箇条書き）\n");
+    prompt.push_str("2. プロジェクト別の貢献内容\n");
+    prompt.push_str("   - 各リポジトリでの主要な取り組みと成果\n");
+    prompt.push_str("   - 関連するPRをまとめて一つの成果として表現\n");
+    prompt.push_str("3. 技術的なリーダーシップ\n");
+    prompt.push_str("   - 新技術の導入、アーキテクチャの改善\n");
+    prompt.push_str("   - コードレビューでの貢献（コメントから読み取れる場合）\n");
+    prompt.push_str("4. ビジネスインパクト\n");
+    prompt.push_str("   - 機能開発によるユーザー価値の向上\n");
+    prompt.push_str("   - パフォーマンス改善や品質向上の取り組み\n");
+    if reliability {
+        prompt.push_str("   - 信頼性への貢献（障害対応・オンコールでの対応内容と復旧への貢献を、独立した小見出しで記述）\n");
+    }
+    prompt.push_str("5. チームへの貢献\n");
+    prompt.push_str("   - コラボレーションの姿勢\n");
+    prompt.push_str("   - ドキュメント整備やツール改善\n");
+    if community {
+        prompt.push_str("   - コミュニティへの貢献（社外のOSSへのPRや公開Gistを、社内の成果とは別の小見出しで記述）\n");
+    }
+    prompt.push_str("6. 継続的な成長と改善\n");
+    prompt.push_str("   - 期間を通じての成長や学習の形跡\n");
+    prompt.push_str("   - 新しい領域への挑戦\n");
+    match tone {
+        Tone::Promotional => {
+            prompt.push_str("7. 総合評価と今後への期待\n\n");
+            prompt.push_str(
+                "【重要】成果を最大限にアピールし、エンジニアの価値を適切に表現してください。\n",
+            );
+        }
+        Tone::Balanced => {
+            prompt.push_str("7. 成長領域（活動データから読み取れる課題と伸ばしたい点）\n");
+            prompt.push_str("8. 総合評価と今後への期待\n\n");
+            prompt.push_str("【重要】強みと成長領域の両方を、活動データに基づいて公平に記述してください。誇張は避けてください。\n");
+        }
+        Tone::Critical => {
+            prompt.push_str(
+                "7. 改善が必要な点（期待水準との差、活動データから読み取れるリスクや偏り）\n",
+            );
+            prompt.push_str("8. 総合評価と次の期間に求めること\n\n");
+            prompt.push_str("【重要】成果は正当に認めつつ、改善が必要な点を具体的な根拠とともに率直に指摘してください。\n");
+        }
+    }
+}
+
+/// 生成済みサマリーのファクトチェック用プロンプトを構築
+///
+/// 活動データと照合し、各記述にPR/IssueのURLを引用として付けたサマリーを出力させます。
+pub fn build_fact_check_prompt(
+    query: &SearchQuery,
+    prs: &[PullRequest],
+    issues: &[Issue],
+    options: &PromptOptions,
+    summary: &str,
+) -> Result<String> {
+    let mut prompt = build_activity_data(query, prs, issues, options)?;
+
+    prompt.push_str("## 検証対象の評価サマリー\n");
+    prompt.push_str(&format!("{summary}\n\n"));
+
+    prompt.push_str("以上の評価サマリーを、活動データと照合してファクトチェックしてください。\n\n");
+    prompt.push_str("【ルール】\n");
+    prompt.push_str("- 成果や取り組みに関する各記述の末尾に、根拠となるPR/IssueのURLを `[#番号](URL)` の形式で引用として付ける\n");
+    prompt.push_str("- 引用できるのは活動データに含まれるURLのみ。URLを推測・生成しない\n");
+    prompt.push_str("- 活動データで裏付けられない記述や数値は、裏付けられる範囲に書き換えるか、書き換えられない場合は削除する\n");
+    prompt.push_str("- 総合評価や今後への期待など、個別の成果ではない記述には引用は不要\n");
+    prompt.push_str("- 構成と日本語の文体は元のサマリーを維持する\n\n");
+    prompt.push_str("出力は修正後の評価サマリー本文のみとし、最後に「## ファクトチェック結果」として、書き換え・削除した記述とその理由を箇条書きで記載してください（なければ「修正なし」）。\n");
+
+    Ok(prompt)
+}
+
+/// 生成済みサマリーの見出しごとに、根拠となるPR/IssueのURLを答えさせるプロンプトを構築（`--evidence`）
+pub fn build_evidence_prompt(
+    query: &SearchQuery,
+    prs: &[PullRequest],
+    issues: &[Issue],
+    options: &PromptOptions,
+    summary: &str,
+    sections: &[String],
+) -> Result<String> {
+    let mut prompt = build_activity_data(query, prs, issues, options)?;
+
+    prompt.push_str("## 評価サマリー\n");
+    prompt.push_str(&format!("{summary}\n\n"));
+
+    prompt.push_str("以上の評価サマリーの次の見出しそれぞれについて、その見出しの下の記述を裏付けるPR/IssueのURLを活動データから選んでください。\n");
+    for section in sections {
+        prompt.push_str(&format!("- {section}\n"));
+    }
+    prompt.push_str("\n【ルール】\n");
+    prompt.push_str("- 選べるのは活動データに含まれるURLのみ。URLを推測・生成しない\n");
+    prompt.push_str("- 記述に直接関係するものだけを、重要なものから順に最大10件まで選ぶ\n");
+    prompt.push_str("- 総合評価など、個別の成果に基づかない見出しは空の配列にする\n");
+    prompt.push_str("- `section`には上の見出しをそのまま書く\n\n");
+    prompt.push_str("出力は次の形式のJSON配列のみとしてください（例: [{\"section\": \"主な成果\", \"urls\": [\"https://github.com/...\"]}]）。\n");
+
+    Ok(prompt)
+}
+
+pub const TRANSLATION_SYSTEM_PROMPT: &str = "あなたはエンジニアの評価資料を扱うプロの翻訳者です。人事評価の委員会で読まれる文書として、意味を正確に保ちつつ、訳文の言語として自然な表現に翻訳します。";
+
+/// 生成済みのサマリーを`to`の言語に翻訳させるプロンプトを構築（`--bilingual`）
+pub fn build_translation_prompt(summary: &str, to: ResumeLanguage) -> String {
+    let mut prompt = String::new();
+    prompt.push_str("## 翻訳対象の評価サマリー\n");
+    prompt.push_str(&format!("{summary}\n\n"));
+
+    let language = match to {
+        ResumeLanguage::English => "英語",
+        ResumeLanguage::Japanese => "日本語",
+    };
+    prompt.push_str(&format!(
+        "以上の評価サマリーを{language}に翻訳してください。\n\n"
+    ));
+    prompt.push_str("【ルール】\n");
+    prompt.push_str("- 見出し・箇条書き・表などのMarkdownの構成は元のまま維持する\n");
+    prompt.push_str("- URL・PR/Issueの番号・リポジトリ名・コード・数値は翻訳せずそのまま残す\n");
+    prompt.push_str("- 内容の追加・省略・要約はしない\n\n");
+    prompt.push_str("出力は翻訳後の本文のみとしてください。\n");
+    prompt
+}
+
+/// 元のサマリーと翻訳を、言語ごとの見出しを付けて並べる（`--bilingual`）
+pub fn bilingual(original: &str, language: ResumeLanguage, translated: &str) -> String {
+    format!(
+        "# {}\n\n{}\n\n# {}\n\n{}\n",
+        language.heading(),
+        original.trim_end(),
+        language.other().heading(),
+        translated.trim_end()
+    )
+}
+
+/// 複数のモデルで生成したサマリーを、モデルごとの見出しを付けて並べる（`--models`）
+pub fn side_by_side(candidates: &[(String, String)]) -> String {
+    candidates
+        .iter()
+        .map(|(model, summary)| format!("# {model}\n\n{}\n", summary.trim_end()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 複数のモデルで生成したサマリーを1つに統合させるプロンプトを構築（`--merge-models`）
+pub fn build_merge_prompt(
+    query: &SearchQuery,
+    prs: &[PullRequest],
+    issues: &[Issue],
+    options: &PromptOptions,
+    candidates: &[(String, String)],
+) -> Result<String> {
+    let mut prompt = build_activity_data(query, prs, issues, options)?;
+
+    for (i, (_, summary)) in candidates.iter().enumerate() {
+        // モデル名で優劣を判断しないよう、番号で区別する
+        prompt.push_str(&format!("## 候補{}\n{summary}\n\n", i + 1));
+    }
+
+    prompt.push_str("以上は同じ活動データから別々に作成した評価サマリーの候補です。候補を比較し、1つの評価サマリーに統合してください。\n\n");
+    prompt.push_str("【ルール】\n");
+    prompt.push_str(
+        "- 各候補から、活動データで裏付けられる最も具体的で説得力のある記述を選んで組み合わせる\n",
+    );
+    prompt.push_str("- 候補の間で食い違う記述は、活動データと照合して正しい方を採用する\n");
+    prompt.push_str("- どの候補にもない成果や数値を加えない\n");
+    prompt.push_str("- 構成は最も読みやすい候補に合わせ、同じ成果を重複して書かない\n\n");
+    prompt.push_str("出力は統合後の評価サマリー本文のみとしてください。\n");
+
+    Ok(prompt)
+}
+
+/// 下書きを品質チェックリストに照らして講評させるプロンプトを構築（`--refine`の2段階目）
+pub fn build_critique_prompt(
+    query: &SearchQuery,
+    prs: &[PullRequest],
+    issues: &[Issue],
+    options: &PromptOptions,
+    draft: &str,
+) -> Result<String> {
+    let mut prompt = build_activity_data(query, prs, issues, options)?;
+
+    prompt.push_str("## 下書き\n");
+    prompt.push_str(&format!("{draft}\n\n"));
+
+    prompt.push_str(
+        "以上の下書きを、活動データと次の品質チェックリストに照らして講評してください。\n\n",
+    );
+    push_quality_checklist(&mut prompt);
+    prompt.push_str("チェックリストの項目ごとに、問題のある箇所の引用と具体的な改善案を箇条書きで挙げてください。下書きの書き直しは不要です。\n");
+
+    Ok(prompt)
+}
+
+/// 下書きと講評をもとに最終版を書かせるプロンプトを構築（`--refine`の3段階目）
+pub fn build_revision_prompt(
+    query: &SearchQuery,
+    prs: &[PullRequest],
+    issues: &[Issue],
+    options: &PromptOptions,
+    draft: &str,
+    critique: &str,
+) -> Result<String> {
+    let mut prompt = build_activity_data(query, prs, issues, options)?;
+
+    prompt.push_str("## 下書き\n");
+    prompt.push_str(&format!("{draft}\n\n"));
+    prompt.push_str("## 講評\n");
+    prompt.push_str(&format!("{critique}\n\n"));
+
+    prompt.push_str("以上の講評を反映して、下書きを最終版に書き直してください。\n\n");
+    push_quality_checklist(&mut prompt);
+    prompt.push_str("下書きの構成と言語は維持し、出力は最終版の本文のみとしてください。\n");
+
+    Ok(prompt)
+}
+
+fn push_quality_checklist(prompt: &mut String) {
+    prompt.push_str("【品質チェックリスト】\n");
+    prompt
+        .push_str("- 具体性: 抽象的な表現ではなく、取り組みの内容や対象が具体的に書かれているか\n");
+    prompt.push_str("- 根拠: 各成果が活動データのPR/Issueで裏付けられ、URLで参照できるか\n");
+    prompt.push_str("- 誇張の排除: 活動データにない成果・数値・役割を主張していないか\n");
+    prompt.push_str(
+        "- 網羅性: 活動量の少ないリポジトリや地味な改善など、重要な貢献が漏れていないか\n",
+    );
+    prompt.push_str("- 読みやすさ: 冗長な繰り返しがなく、評価者が短時間で要点を把握できるか\n\n");
+}
+
+/// サマリー中のGitHub URLのうち、活動データに含まれないもの
+pub fn unknown_citations(text: &str, prs: &[PullRequest], issues: &[Issue]) -> Vec<String> {
+    static PATTERN: std::sync::LazyLock<regex::Regex> = std::sync::LazyLock::new(|| {
+        regex::Regex::new(r"https://github\.com/[\w.-]+/[\w.-]+/(?:pull|issues)/\d+")
+            .expect("citation pattern should be valid")
+    });
+
+    let known: HashSet<&str> = prs
+        .iter()
+        .map(|pr| pr.url.as_str())
+        .chain(issues.iter().map(|issue| issue.url.as_str()))
+        .chain(
+            prs.iter()
+                .flat_map(|pr| &pr.closing_issues)
+                .map(|issue| issue.url.as_str()),
+        )
+        .collect();
+
+    let mut unknown: Vec<String> = Vec::new();
+    for url in PATTERN.find_iter(text).map(|m| m.as_str()) {
+        if !known.contains(url) && !unknown.iter().any(|u| u == url) {
+            unknown.push(url.to_string());
+        }
+    }
+    unknown
+}
+
+/// リポジトリごとにPR/Issueをまとめる（活動量の多い順）
+pub fn group_by_repository(prs: &[PullRequest], issues: &[Issue]) -> Vec<RepositoryActivity> {
+    let mut groups: Vec<RepositoryActivity> = Vec::new();
+    let mut index = HashMap::new();
+
+    for pr in prs {
+        let name = &pr.repository.name_with_owner;
+        let i = *index.entry(name.clone()).or_insert_with(|| {
+            groups.push(RepositoryActivity::new(name));
+            groups.len() - 1
+        });
+        groups[i].prs.push(pr.clone());
+    }
+    for issue in issues {
+        let name = &issue.repository.name_with_owner;
+        let i = *index.entry(name.clone()).or_insert_with(|| {
+            groups.push(RepositoryActivity::new(name));
+            groups.len() - 1
+        });
+        groups[i].issues.push(issue.clone());
+    }
+
+    groups.sort_by_key(|g| std::cmp::Reverse(g.prs.len() + g.issues.len()));
+    groups
+}
+
+/// 1リポジトリ分の深掘りサマリー用プロンプトを構築
+pub fn build_repository_prompt(
+    query: &SearchQuery,
+    repo: &RepositoryActivity,
+    options: &PromptOptions,
+) -> Result<String> {
+    // Discussion・リリースはこのリポジトリのものに絞る
+    let options = &PromptOptions {
+        discussions: options
+            .discussions
+            .iter()
+            .filter(|d| d.repository.name_with_owner == repo.name)
+            .cloned()
+            .collect(),
+        releases: options
+            .releases
+            .iter()
+            .filter(|r| r.repository == repo.name)
+            .cloned()
+            .collect(),
+        community: Community::default(),
+        ..options.clone()
+    };
+    let mut prompt = build_activity_data(query, &repo.prs, &repo.issues, options)?;
+
+    prompt.push_str(&format!(
+        "以上は{}リポジトリでの活動データです。このリポジトリでの貢献に絞って、深掘りした評価サマリーを日本語で作成してください。\n\n",
+        repo.name
+    ));
+
+    prompt.push_str("【評価サマリーに含める項目】\n");
+    prompt.push_str("1. このリポジトリでの役割と担当領域\n");
+    prompt.push_str("2. 主要な取り組みと成果（関連するPRをまとめて一つの成果として表現）\n");
+    prompt.push_str("3. 技術的な難易度や工夫\n");
+    prompt.push_str("4. プロダクトやチームへのインパクト\n\n");
+
+    prompt.push_str(
+        "【重要】活動量が少なくても、戦略的に重要な取り組みであればその価値を明確に表現してください。\n",
+    );
+
+    Ok(prompt)
+}
+
+/// リポジトリ別サマリーを統合する全体サマリー用プロンプトを構築
+pub fn build_overview_prompt(
+    query: &SearchQuery,
+    prs: &[PullRequest],
+    issues: &[Issue],
+    repo_summaries: &[(String, String)],
+    options: &PromptOptions,
+) -> String {
+    let mut prompt = format!(
+        "以下は{}の{}のGitHub活動について、リポジトリごとに作成した評価サマリーです。\n",
+        query.author,
+        period_phrase(query)
+    );
+    prompt.push_str(&format!(
+        "対象: {}リポジトリ、Pull Request {}件、Issue {}件\n\n",
+        repo_summaries.len(),
+        prs.len(),
+        issues.len()
+    ));
+
+    for (repo, summary) in repo_summaries {
+        prompt.push_str(&format!("## {repo}\n{summary}\n\n"));
+    }
+
+    match options.mode {
+        Mode::Summary => prompt.push_str(&format!(
+            "以上のリポジトリ別サマリーを統合して、エンジニアの評価期間中の実績を{}全体サマリーを日本語で作成してください。\n",
+            options.tone.evaluation_phrase()
+        )),
+        Mode::Promo => prompt.push_str(&format!(
+            "以上のリポジトリ別サマリーを統合して、{}への昇格推薦資料（プロモーションパケット）を日本語で作成してください。\n",
+            target_level_label(options)
+        )),
+        Mode::Weekly => prompt.push_str("以上のリポジトリ別サマリーを統合して、週報・1on1で共有するトピックを日本語で簡潔に作成してください。\n"),
+
+        Mode::Resume => prompt.push_str("以上のリポジトリ別サマリーを統合して、職務経歴書（CV/レジュメ）にそのまま貼り付けられる実績の箇条書きを作成してください。\n"),
+
+        Mode::ThreeSixty => {
+            push_feedback(&mut prompt, options);
+            prompt.push_str("以上のリポジトリ別サマリー・自己評価・ピアフィードバックを突き合わせて、マネージャー向けの360度評価を日本語で作成してください。\n");
+        }
+    }
+    prompt.push_str("活動量の多いリポジトリだけでなく、活動量は少なくても戦略的に重要なリポジトリでの貢献も埋もれないように扱ってください。\n\n");
+
+    match options.mode {
+        Mode::Summary => push_evaluation_items(
+            &mut prompt,
+            options.tone,
+            !IncidentStats::compute(prs, issues, &options.incidents).is_empty(),
+            false,
+        ),
+        Mode::Promo => push_promo_items(&mut prompt, options),
+        Mode::Weekly => push_weekly_items(&mut prompt),
+        Mode::Resume => push_resume_items(&mut prompt, options),
+        Mode::ThreeSixty => push_three_sixty_items(&mut prompt),
+    }
+    push_user_feedback(&mut prompt, options);
+
+    prompt
+}
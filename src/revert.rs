@@ -0,0 +1,64 @@
+//! revert PRとrevertされたPRの検出
+//!
+//! GitHubの「Revert」ボタンで作成されたPR（タイトル`Revert "..."`、本文`Reverts owner/repo#N`）を
+//! revert PRとみなし、取得済みのPRのうち取り消されたものに印を付けます。
+
+use crate::github::PullRequest;
+use std::sync::LazyLock;
+
+/// revert PRのタイトルから、取り消し対象のPRのタイトルを取り出す
+pub fn reverted_title(title: &str) -> Option<&str> {
+    title
+        .strip_prefix("Revert \"")
+        .and_then(|rest| rest.strip_suffix('"'))
+}
+
+/// revert PRの本文の`Reverts owner/repo#N`から、取り消し対象のPRのURLを取り出す
+///
+/// リポジトリ名を省略した参照（`Reverts #N`）は`repo`のPRとして扱います。
+pub fn reverted_url(body: &str, repo: &str) -> Option<String> {
+    static PATTERN: LazyLock<regex::Regex> = LazyLock::new(|| {
+        regex::Regex::new(r"(?mi)^reverts\s+([\w.-]+/[\w.-]+)?#(\d+)")
+            .expect("revert pattern should be valid")
+    });
+
+    let caps = PATTERN.captures(body)?;
+    let repository = caps.get(1).map_or(repo, |m| m.as_str());
+    Some(format!("https://github.com/{repository}/pull/{}", &caps[2]))
+}
+
+/// revert PRに取り消し対象を、取り消されたPRにrevert PRを設定
+///
+/// 取り消し対象は本文の参照を優先し、なければ同じリポジトリ内のタイトルの一致で探します。
+/// マージされていないrevert PRは取り消しとして扱いません。
+pub fn mark_reverts(prs: &mut [PullRequest]) {
+    let mut links = Vec::new();
+    for pr in prs.iter() {
+        let Some(title) = reverted_title(&pr.title) else {
+            continue;
+        };
+        let repo = &pr.repository.name_with_owner;
+        let target = reverted_url(pr.body.as_deref().unwrap_or(""), repo).or_else(|| {
+            prs.iter()
+                .find(|other| other.repository.name_with_owner == *repo && other.title == title)
+                .map(|other| other.url.clone())
+        });
+        if let Some(target) = target {
+            links.push((pr.url.clone(), target, pr.state == "merged"));
+        }
+    }
+
+    for (revert_url, target, merged) in links {
+        if let Some(pr) = prs.iter_mut().find(|pr| pr.url == revert_url) {
+            pr.reverts = Some(target.clone());
+        }
+        if merged && let Some(pr) = prs.iter_mut().find(|pr| pr.url == target) {
+            pr.reverted_by = Some(revert_url);
+        }
+    }
+}
+
+/// revert PRか（タイトルで判定）
+pub fn is_revert(pr: &PullRequest) -> bool {
+    reverted_title(&pr.title).is_some()
+}
@@ -0,0 +1,224 @@
+//! cron形式（5フィールド）のスケジュール
+//!
+//! `分 時 日 月 曜日`の各フィールドで`*`・数値・範囲（`1-5`）・リスト（`1,15`）・
+//! 間隔（`*/15`）と、月・曜日の英語名（`JAN`、`MON`など）に対応します。
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDateTime, TimeZone, Timelike};
+
+const MONTH_NAMES: &[&str] = &[
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+
+const WEEKDAY_NAMES: &[&str] = &["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+// 次・前回の実行日時を探す範囲（分）。2月29日のみの指定などにも対応できるよう5年分
+const SEARCH_LIMIT_MINUTES: i64 = 5 * 366 * 24 * 60;
+
+/// 実行日時のスケジュール
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: Vec<u32>,
+    hours: Vec<u32>,
+    days: Vec<u32>,
+    months: Vec<u32>,
+    /// 0が日曜日
+    weekdays: Vec<u32>,
+    // 日と曜日の両方が指定された場合はどちらかに一致すればよい（cronの慣例）
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl CronSchedule {
+    /// `"0 9 * * MON"`のような5フィールドの式を解析
+    pub fn parse(expression: &str) -> Result<Self> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            anyhow::bail!(
+                "cron式は「分 時 日 月 曜日」の5フィールドで指定してください: {expression}"
+            );
+        };
+
+        let parse = |field: &str, min: u32, max: u32, names: &[&str], offset: u32| {
+            parse_field(field, min, max, names, offset)
+                .with_context(|| format!("cron式のフィールドが不正です: {field}"))
+        };
+        let mut weekdays = parse(weekday, 0, 7, WEEKDAY_NAMES, 0)?;
+        // 7も日曜日として扱う
+        for w in &mut weekdays {
+            *w %= 7;
+        }
+        weekdays.sort_unstable();
+        weekdays.dedup();
+
+        Ok(Self {
+            minutes: parse(minute, 0, 59, &[], 0)?,
+            hours: parse(hour, 0, 23, &[], 0)?,
+            days: parse(day, 1, 31, &[], 0)?,
+            months: parse(month, 1, 12, MONTH_NAMES, 1)?,
+            weekdays,
+            any_day: day == "*",
+            any_weekday: weekday == "*",
+        })
+    }
+
+    /// `after`より後（分単位）で最初に一致する日時
+    pub fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let mut naive = start.naive_local();
+        for _ in 0..SEARCH_LIMIT_MINUTES {
+            if self.matches(&naive)
+                // 夏時間の切り替えで存在しない時刻は飛ばす
+                && let Some(time) = Local.from_local_datetime(&naive).earliest()
+            {
+                return Some(time);
+            }
+            naive += Duration::minutes(1);
+        }
+        None
+    }
+
+    /// `before`より前（分単位）で最後に一致する日時
+    pub fn previous_before(&self, before: DateTime<Local>) -> Option<DateTime<Local>> {
+        let start = before.with_second(0)?.with_nanosecond(0)? - Duration::minutes(1);
+        let mut naive = start.naive_local();
+        for _ in 0..SEARCH_LIMIT_MINUTES {
+            if self.matches(&naive)
+                && let Some(time) = Local.from_local_datetime(&naive).earliest()
+            {
+                return Some(time);
+            }
+            naive -= Duration::minutes(1);
+        }
+        None
+    }
+
+    fn matches(&self, time: &NaiveDateTime) -> bool {
+        if !self.minutes.contains(&time.minute()) || !self.hours.contains(&time.hour()) {
+            return false;
+        }
+        let day_matches = self.days.contains(&time.day());
+        let weekday_matches = self
+            .weekdays
+            .contains(&time.weekday().num_days_from_sunday());
+        let day_ok = match (self.any_day, self.any_weekday) {
+            (true, true) => true,
+            (true, false) => weekday_matches,
+            (false, true) => day_matches,
+            (false, false) => day_matches || weekday_matches,
+        };
+        day_ok && self.months.contains(&time.month())
+    }
+}
+
+// 1フィールドを値のリストに展開（`names`は`offset`から始まる値の別名）
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str], offset: u32) -> Result<Vec<u32>> {
+    let value = |s: &str| -> Result<u32> {
+        let upper = s.to_uppercase();
+        if let Some(i) = names.iter().position(|name| *name == upper) {
+            return Ok(i as u32 + offset);
+        }
+        let n: u32 = s
+            .parse()
+            .with_context(|| format!("数値ではありません: {s}"))?;
+        anyhow::ensure!((min..=max).contains(&n), "{n}は{min}〜{max}の範囲外です");
+        Ok(n)
+    };
+
+    let mut values = Vec::new();
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().context("間隔が不正です")?),
+            None => (part, 1),
+        };
+        anyhow::ensure!(step > 0, "間隔は1以上で指定してください");
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (value(start)?, value(end)?)
+        } else {
+            let start = value(range)?;
+            // `5/15`は5から最大値までの間隔指定
+            (start, if part.contains('/') { max } else { start })
+        };
+        anyhow::ensure!(start <= end, "範囲の開始が終了より後です: {range}");
+        values.extend((start..=end).step_by(step as usize));
+    }
+    values.sort_unstable();
+    values.dedup();
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    #[test]
+    fn parse_expands_ranges_lists_steps_and_names() {
+        let schedule = CronSchedule::parse("*/15 9-11 1,15 JAN-MAR MON,fri").unwrap();
+        assert_eq!(schedule.minutes, vec![0, 15, 30, 45]);
+        assert_eq!(schedule.hours, vec![9, 10, 11]);
+        assert_eq!(schedule.days, vec![1, 15]);
+        assert_eq!(schedule.months, vec![1, 2, 3]);
+        assert_eq!(schedule.weekdays, vec![1, 5]);
+    }
+
+    #[test]
+    fn parse_treats_7_as_sunday() {
+        let schedule = CronSchedule::parse("0 0 * * 0,7").unwrap();
+        assert_eq!(schedule.weekdays, vec![0]);
+    }
+
+    #[test]
+    fn parse_rejects_invalid_expressions() {
+        for expression in [
+            "0 9 * *",
+            "60 9 * * *",
+            "0 9 * * MON-SUN",
+            "*/0 * * * *",
+            "a * * * *",
+        ] {
+            assert!(
+                CronSchedule::parse(expression).is_err(),
+                "{expression} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn next_after_finds_the_next_matching_minute() {
+        // 2025-01-06は月曜日
+        let schedule = CronSchedule::parse("0 9 * * MON").unwrap();
+        assert_eq!(
+            schedule.next_after(local(2025, 1, 6, 8, 59)),
+            Some(local(2025, 1, 6, 9, 0))
+        );
+        assert_eq!(
+            schedule.next_after(local(2025, 1, 6, 9, 0)),
+            Some(local(2025, 1, 13, 9, 0))
+        );
+    }
+
+    #[test]
+    fn next_after_matches_either_day_or_weekday_when_both_are_given() {
+        // 2025-01-03は金曜日
+        let schedule = CronSchedule::parse("0 0 15 * FRI").unwrap();
+        assert_eq!(
+            schedule.next_after(local(2025, 1, 1, 0, 0)),
+            Some(local(2025, 1, 3, 0, 0))
+        );
+    }
+
+    #[test]
+    fn previous_before_finds_the_last_matching_minute() {
+        let schedule = CronSchedule::parse("0 9 * * MON").unwrap();
+        assert_eq!(
+            schedule.previous_before(local(2025, 1, 13, 9, 0)),
+            Some(local(2025, 1, 6, 9, 0))
+        );
+    }
+}
@@ -0,0 +1,116 @@
+//! SlackのIncoming Webhookへのサマリー投稿
+
+use crate::limits;
+use anyhow::Result;
+
+// sectionブロックのtextは3000文字まで
+const SECTION_TEXT_LIMIT: usize = 3000;
+// 1メッセージあたりのブロック数の上限
+const MAX_BLOCKS: usize = 50;
+
+/// サマリーをheader + sectionブロックに整形してWebhookに投稿
+///
+/// 1メッセージのブロック数の上限を超える場合は、見出しに`(1/2)`のような番号を付けて
+/// 複数のメッセージに分けて投稿します。
+pub async fn post_summary(webhook_url: &str, title: &str, summary: &str) -> Result<()> {
+    let payloads = build_payloads(title, summary);
+    if payloads.len() > 1 {
+        tracing::warn!(
+            messages = payloads.len(),
+            "サマリーが長いため、Slackに複数のメッセージに分けて投稿します"
+        );
+    }
+
+    let client = limits::http_client();
+    for payload in payloads {
+        let response = client.post(webhook_url).json(&payload).send().await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            anyhow::bail!("Slack webhook error: {}", error_text);
+        }
+    }
+
+    Ok(())
+}
+
+fn build_payloads(title: &str, summary: &str) -> Vec<serde_json::Value> {
+    let sections = split_sections(&to_mrkdwn(summary));
+    // 見出しのheaderブロックの分を除く
+    let chunks: Vec<&[String]> = sections.chunks(MAX_BLOCKS - 1).collect();
+    let total = chunks.len().max(1);
+
+    (0..total)
+        .map(|i| {
+            let heading = if total > 1 {
+                format!("{title} ({}/{total})", i + 1)
+            } else {
+                title.to_string()
+            };
+            let mut blocks = vec![serde_json::json!({
+                "type": "header",
+                "text": { "type": "plain_text", "text": truncate(&heading, 150), "emoji": true }
+            })];
+            for chunk in chunks.get(i).copied().unwrap_or_default() {
+                blocks.push(serde_json::json!({
+                    "type": "section",
+                    "text": { "type": "mrkdwn", "text": chunk }
+                }));
+            }
+
+            serde_json::json!({
+                "text": heading,
+                "blocks": blocks,
+            })
+        })
+        .collect()
+}
+
+// Markdownの見出し・太字をSlackのmrkdwn記法に変換
+fn to_mrkdwn(markdown: &str) -> String {
+    markdown
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            let heading = trimmed.trim_start_matches('#');
+            if heading.len() != trimmed.len() && heading.starts_with(' ') {
+                format!("*{}*", heading.trim().replace("**", ""))
+            } else {
+                line.replace("**", "*")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// 段落単位でsectionブロックの文字数上限に収まるよう分割
+fn split_sections(text: &str) -> Vec<String> {
+    let mut sections = Vec::new();
+    let mut current = String::new();
+
+    for paragraph in text.split("\n\n") {
+        if !current.is_empty()
+            && current.chars().count() + paragraph.chars().count() + 2 > SECTION_TEXT_LIMIT
+        {
+            sections.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push_str("\n\n");
+        }
+        current.push_str(&truncate(paragraph, SECTION_TEXT_LIMIT));
+    }
+    if !current.trim().is_empty() {
+        sections.push(current);
+    }
+
+    sections
+}
+
+fn truncate(text: &str, limit: usize) -> String {
+    if text.chars().count() <= limit {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(limit - 1).collect();
+    truncated.push('…');
+    truncated
+}
@@ -0,0 +1,59 @@
+//! 取得した活動データのスナップショット（JSON）の保存と読み込み
+//!
+//! GitHubへアクセスできる環境でデータを取得・保存し、別の環境でオフラインのまま
+//! サマリーを生成するために使います。
+
+use crate::github::Activity;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// スナップショットの形式のバージョン
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub version: u32,
+    /// 取得日時（RFC 3339）
+    pub fetched_at: String,
+    #[serde(flatten)]
+    pub activity: Activity,
+}
+
+impl Snapshot {
+    pub fn new(activity: Activity) -> Self {
+        Self {
+            version: SNAPSHOT_VERSION,
+            fetched_at: chrono::Local::now().to_rfc3339(),
+            activity,
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, json)
+            .with_context(|| format!("スナップショットを書き込めません: {}", path.display()))
+    }
+
+    /// 直近の取得結果の保存先（`~/.cache/easyhyoka/last-activity.json`など、`--feedback`用）
+    pub fn last_path() -> Option<PathBuf> {
+        dirs::cache_dir().map(|dir| dir.join("easyhyoka").join("last-activity.json"))
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("スナップショットを読み込めません: {}", path.display()))?;
+        let snapshot: Self = serde_json::from_str(&json)
+            .with_context(|| format!("スナップショットの形式が不正です: {}", path.display()))?;
+
+        if snapshot.version > SNAPSHOT_VERSION {
+            anyhow::bail!(
+                "スナップショットのバージョン{}には対応していません（対応: {}以下）",
+                snapshot.version,
+                SNAPSHOT_VERSION
+            );
+        }
+
+        Ok(snapshot)
+    }
+}
@@ -0,0 +1,127 @@
+//! 活動データの取得元
+//!
+//! PR/Issueの検索とコメント・レビューの取得を[`DataSource`]として抽象化します。
+//! GitHub（[`crate::github::GitHub`]）が最初の実装で、GitLabやJiraなどの取得元は
+//! このトレイトを実装するモジュールとして、featureで有効化する形で追加します。
+
+use crate::github::{Comment, Issue, PullRequest, Review, SearchQuery};
+use crate::limits;
+use anyhow::Result;
+use std::cmp::Reverse;
+use std::collections::HashSet;
+
+/// コメントを取得する対象の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemKind {
+    PullRequest,
+    Issue,
+}
+
+/// PR/Issueとそのコメント・レビューの取得元
+pub trait DataSource: Sync {
+    /// 取得元の名前（進捗表示用）
+    fn name(&self) -> &'static str;
+
+    /// 取得元を使えるか確認し、使えない場合は対処方法を含むエラーを返す
+    fn ensure_ready(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// 認証中のユーザー名（対象ユーザーが指定されていない場合に使う）
+    fn current_user(&self) -> Result<String>;
+
+    /// 期間内のPRを検索
+    fn fetch_prs(&self, query: &SearchQuery) -> Result<Vec<PullRequest>>;
+
+    /// 期間内のIssueを検索
+    fn fetch_issues(&self, query: &SearchQuery) -> Result<Vec<Issue>>;
+
+    /// PRに付いたレビュー
+    fn fetch_reviews(&self, repo: &str, number: u32) -> Result<Vec<Review>>;
+
+    /// PR/Issueに付いたコメント
+    fn fetch_comments(&self, kind: ItemKind, repo: &str, number: u32) -> Result<Vec<Comment>>;
+}
+
+/// コメント数の多い順に最大`count`件（コメントのないものは除く）のインデックス
+///
+/// 議論の多いスレッドほどレビューでの主導ぶりが表れるため、コメントを取得する対象に使います。
+/// 同数の場合は先（検索結果では新しい方）を優先します。
+pub fn most_discussed(comment_counts: impl Iterator<Item = usize>, count: usize) -> HashSet<usize> {
+    let mut ranked: Vec<(usize, usize)> = comment_counts
+        .enumerate()
+        .filter(|(_, comments)| *comments > 0)
+        .collect();
+    ranked.sort_by_key(|(_, comments)| Reverse(*comments));
+    ranked.into_iter().take(count).map(|(i, _)| i).collect()
+}
+
+/// コメント数の多い`count`件のPRにコメントを取得して設定
+///
+/// `on_progress`は1件取得するごとに呼ばれます。
+pub fn attach_pr_comments(
+    source: &dyn DataSource,
+    prs: &mut [PullRequest],
+    count: usize,
+    on_progress: impl FnMut(),
+) {
+    let targets = most_discussed(prs.iter().map(|pr| pr.comments_count), count);
+    let prs = prs
+        .iter_mut()
+        .enumerate()
+        .filter(|(i, _)| targets.contains(i))
+        .map(|(_, pr)| pr);
+    limits::for_each_concurrent(prs, on_progress, |pr| {
+        if let Ok(comments) = source.fetch_comments(
+            ItemKind::PullRequest,
+            &pr.repository.name_with_owner,
+            pr.number,
+        ) {
+            pr.comments = comments;
+        }
+    });
+}
+
+/// コメント数の多い`count`件のIssueにコメントを取得して設定
+///
+/// `on_progress`は1件取得するごとに呼ばれます。
+pub fn attach_issue_comments(
+    source: &dyn DataSource,
+    issues: &mut [Issue],
+    count: usize,
+    on_progress: impl FnMut(),
+) {
+    let targets = most_discussed(issues.iter().map(|issue| issue.comments_count), count);
+    let issues = issues
+        .iter_mut()
+        .enumerate()
+        .filter(|(i, _)| targets.contains(i))
+        .map(|(_, issue)| issue);
+    limits::for_each_concurrent(issues, on_progress, |issue| {
+        if let Ok(comments) = source.fetch_comments(
+            ItemKind::Issue,
+            &issue.repository.name_with_owner,
+            issue.number,
+        ) {
+            issue.comments = comments;
+        }
+    });
+}
+
+/// 各PRのレビューを取得し、受けたレビューと`author`自身によるレビューに分けて設定
+///
+/// `on_progress`は1件取得するごとに呼ばれます。
+pub fn attach_pr_reviews(
+    source: &dyn DataSource,
+    prs: &mut [PullRequest],
+    author: &str,
+    on_progress: impl FnMut(),
+) {
+    limits::for_each_concurrent(prs.iter_mut(), on_progress, |pr| {
+        if let Ok(reviews) = source.fetch_reviews(&pr.repository.name_with_owner, pr.number) {
+            (pr.authored_reviews, pr.reviews) = reviews
+                .into_iter()
+                .partition(|r| r.author.as_ref().is_some_and(|a| a.login == author));
+        }
+    });
+}
@@ -0,0 +1,910 @@
+//! PR/Issueの統計情報
+
+use crate::areas::AreaStats;
+use crate::collab::{self, Collaborator};
+use crate::gaps::Gap;
+use crate::github::{Discussion, Issue, Mentions, PullRequest, Review, TriageItem};
+use crate::highlight::ScoringWeights;
+use crate::incident::IncidentStats;
+use crate::{revert, techstack};
+use chrono::{DateTime, Datelike, Months, NaiveDate, Timelike};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// 活動データの集計結果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ActivityStats {
+    pub total_prs: usize,
+    pub merged_prs: usize,
+    pub open_prs: usize,
+    pub closed_prs: usize,
+    /// ドラフトのままのPR数
+    pub draft_prs: usize,
+    /// revert PR数
+    pub revert_prs: usize,
+    /// マージ後にrevertされたPR数
+    pub reverted_prs: usize,
+    /// revert PRとrevertされたPRを除いたマージ済みPR数
+    pub shipped_prs: usize,
+    /// リポジトリの重要度で重み付けしたマージ済みPR数（重みに依存するため、
+    /// `ScoringWeights::weighted_merged_prs`で別途設定）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weighted_merged_prs: Option<f64>,
+    pub total_issues: usize,
+    pub open_issues: usize,
+    pub closed_issues: usize,
+    /// リポジトリ別のPR数（多い順）
+    pub prs_by_repository: Vec<(String, usize)>,
+    /// モノレポの領域別のPR数と変更行数（設定に依存するため、`areas::breakdown`で別途設定）
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub prs_by_area: Vec<AreaStats>,
+    /// 月別（YYYY-MM）のPR数（古い順）
+    pub prs_by_month: Vec<(String, usize)>,
+    /// 日別（YYYY-MM-DD）のPR/Issue作成数（古い順、活動のあった日のみ）
+    pub activity_by_day: Vec<(String, usize)>,
+    /// 自分のPRが受けたレビュー
+    pub reviews_received: ReviewStats,
+    /// 他の人のPRに対して行ったレビュー
+    pub reviews_given: ReviewerStats,
+    /// マージ済みPRで解決したIssue
+    pub resolved_issues: ResolutionStats,
+    /// マージやレビューまでの所要時間
+    pub turnaround: TurnaroundStats,
+    /// マージ済みPRのCI結果とデプロイ（取得時のみ）
+    pub shipping: ShipStats,
+    /// トピック別のPR/Issue数（多い順、トピック付与後のみ）
+    pub topics: Vec<(String, usize)>,
+    /// 言語・技術別の変更行数（多い順、変更ファイル取得時のみ）
+    pub tech_stack: Vec<(String, usize)>,
+    /// 主にドキュメントを変更したPR（変更ファイル取得時のみ）
+    pub documentation: DocsStats,
+    /// PR/Issue・コメントへのリアクション（取得時のみ）
+    pub engagement: EngagementStats,
+    /// 協業相手（やり取りの多い順）
+    pub collaborators: Vec<Collaborator>,
+    /// 障害対応の作業（判定ルールが設定に依存するため、`IncidentStats::compute`で別途設定）
+    pub incidents: IncidentStats,
+    /// 曜日・時間帯別の作成数（`--work-hours`指定時のみ`WorkPattern::compute`で別途設定）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub work_pattern: Option<WorkPattern>,
+}
+
+/// 深夜とみなす時間帯（22時〜翌5時前）
+const LATE_NIGHT_HOURS: [u32; 7] = [22, 23, 0, 1, 2, 3, 4];
+
+/// 時間外の作業が何週続いたら継続的とみなすか
+pub const SUSTAINED_WEEKS: usize = 3;
+
+/// 曜日・時間帯別のPR/Issue作成数（作業負荷の把握用）
+///
+/// 時刻は`createdAt`のタイムゾーン（`--tz`指定時はそのタイムゾーン、それ以外はUTC）で数えます。
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WorkPattern {
+    /// 月曜〜日曜の作成数
+    pub by_weekday: [usize; 7],
+    /// 0〜23時の作成数
+    pub by_hour: [usize; 24],
+    pub total: usize,
+    /// 深夜（22時〜翌5時前）の作成数
+    pub late_night: usize,
+    /// 土日の作成数
+    pub weekend: usize,
+    /// 深夜・土日の作成が続いた最長の週数
+    pub longest_off_hours_streak: usize,
+}
+
+impl WorkPattern {
+    pub fn compute(prs: &[PullRequest], issues: &[Issue]) -> Self {
+        let mut pattern = Self::default();
+        let mut off_hours_weeks = Vec::new();
+        let times = prs
+            .iter()
+            .map(|pr| pr.created_at.as_str())
+            .chain(issues.iter().map(|issue| issue.created_at.as_str()))
+            .filter_map(|time| DateTime::parse_from_rfc3339(time).ok());
+        for time in times {
+            let weekday = time.weekday().num_days_from_monday() as usize;
+            let hour = time.hour();
+            pattern.by_weekday[weekday] += 1;
+            pattern.by_hour[hour as usize] += 1;
+            pattern.total += 1;
+            let late_night = LATE_NIGHT_HOURS.contains(&hour);
+            let weekend = weekday >= 5;
+            pattern.late_night += usize::from(late_night);
+            pattern.weekend += usize::from(weekend);
+            if late_night || weekend {
+                off_hours_weeks.push(Granularity::Week.bucket_start(time.date_naive()));
+            }
+        }
+
+        off_hours_weeks.sort();
+        off_hours_weeks.dedup();
+        let mut streak = 0;
+        for (i, week) in off_hours_weeks.iter().enumerate() {
+            let continued = i > 0 && Granularity::Week.next(off_hours_weeks[i - 1]) == *week;
+            streak = if continued { streak + 1 } else { 1 };
+            pattern.longest_off_hours_streak = pattern.longest_off_hours_streak.max(streak);
+        }
+        pattern
+    }
+
+    /// 深夜・土日の作業が`SUSTAINED_WEEKS`週以上続いたか
+    pub fn is_sustained(&self) -> bool {
+        self.longest_off_hours_streak >= SUSTAINED_WEEKS
+    }
+}
+
+/// マージやレビューまでの所要時間（中央値、時間単位）
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TurnaroundStats {
+    /// 自分のPRの作成からマージまで
+    pub time_to_merge: Option<f64>,
+    /// 集計対象のマージ済みPR数
+    pub merged_samples: usize,
+    /// 自分のPRの作成から最初のレビューを受けるまで
+    pub time_to_first_review: Option<f64>,
+    /// 集計対象のレビューを受けたPR数
+    pub first_review_samples: usize,
+    /// 他のメンバーのPRの作成から自分が最初にレビューするまで
+    pub review_response: Option<f64>,
+    /// 集計対象のレビューしたPR数
+    pub review_response_samples: usize,
+}
+
+impl TurnaroundStats {
+    pub fn compute(prs: &[PullRequest], author: &str) -> Self {
+        let is_own = |pr: &&PullRequest| pr.author.as_ref().is_none_or(|a| a.login == author);
+
+        let merge_hours: Vec<f64> = prs
+            .iter()
+            .filter(is_own)
+            .filter(|pr| pr.state == "merged")
+            .filter_map(|pr| hours_between(&pr.created_at, pr.closed_at.as_deref()?))
+            .collect();
+        let review_wait_hours: Vec<f64> = prs
+            .iter()
+            .filter(is_own)
+            .filter_map(|pr| first_review_hours(&pr.created_at, &pr.reviews))
+            .collect();
+        let response_hours: Vec<f64> = prs
+            .iter()
+            .filter(|pr| !is_own(pr))
+            .filter_map(|pr| first_review_hours(&pr.created_at, &pr.authored_reviews))
+            .collect();
+
+        Self {
+            merged_samples: merge_hours.len(),
+            time_to_merge: median(merge_hours),
+            first_review_samples: review_wait_hours.len(),
+            time_to_first_review: median(review_wait_hours),
+            review_response_samples: response_hours.len(),
+            review_response: median(response_hours),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.time_to_merge.is_none()
+            && self.time_to_first_review.is_none()
+            && self.review_response.is_none()
+    }
+}
+
+/// 時間数を「3.5時間」「2.1日」のような読みやすい表記にする
+pub fn format_hours(hours: f64) -> String {
+    if hours < 24.0 {
+        format!("{hours:.1}時間")
+    } else {
+        format!("{:.1}日", hours / 24.0)
+    }
+}
+
+// PR作成から最初のレビューまでの時間
+fn first_review_hours(created_at: &str, reviews: &[Review]) -> Option<f64> {
+    reviews
+        .iter()
+        .filter_map(|r| hours_between(created_at, r.submitted_at.as_deref()?))
+        .min_by(|a, b| a.total_cmp(b))
+}
+
+// RFC 3339の日時2つの差（時間）
+fn hours_between(from: &str, to: &str) -> Option<f64> {
+    let from = DateTime::parse_from_rfc3339(from).ok()?;
+    let to = DateTime::parse_from_rfc3339(to).ok()?;
+    let seconds = (to - from).num_seconds();
+    (seconds >= 0).then(|| seconds as f64 / 3600.0)
+}
+
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    let mid = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    })
+}
+
+/// マージ済みPRのCI結果とデプロイの集計
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ShipStats {
+    /// チェック結果を取得できたPR数
+    pub checked_prs: usize,
+    /// すべてのチェックが通ったPR数
+    pub passing_prs: usize,
+    /// デプロイに成功したPR数
+    pub deployed_prs: usize,
+    /// 本番環境へのデプロイに成功したPR数
+    pub production_prs: usize,
+}
+
+impl ShipStats {
+    pub fn compute(prs: &[PullRequest]) -> Self {
+        let mut stats = Self::default();
+        for pr in prs.iter().filter(|pr| pr.state == "merged") {
+            if !pr.checks.is_empty() {
+                stats.checked_prs += 1;
+                if pr.checks.iter().all(|c| c.passed()) {
+                    stats.passing_prs += 1;
+                }
+            }
+            if pr.deployments.iter().any(|d| d.succeeded()) {
+                stats.deployed_prs += 1;
+            }
+            if pr
+                .deployments
+                .iter()
+                .any(|d| d.succeeded() && d.is_production())
+            {
+                stats.production_prs += 1;
+            }
+        }
+        stats
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.checked_prs == 0 && self.deployed_prs == 0
+    }
+}
+
+/// ドキュメントへの貢献の集計
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DocsStats {
+    /// 主にドキュメントを変更したPR数
+    pub prs: usize,
+    pub merged_prs: usize,
+    /// ドキュメントの変更行数（追加+削除、全PR）
+    pub lines: usize,
+}
+
+impl DocsStats {
+    pub fn compute(prs: &[PullRequest]) -> Self {
+        let docs_prs: Vec<&PullRequest> =
+            prs.iter().filter(|pr| techstack::is_docs_pr(pr)).collect();
+        Self {
+            prs: docs_prs.len(),
+            merged_prs: docs_prs.iter().filter(|pr| pr.state == "merged").count(),
+            lines: prs
+                .iter()
+                .flat_map(|pr| &pr.files)
+                .filter(|file| techstack::is_documentation(&file.path))
+                .map(|file| file.additions + file.deletions)
+                .sum(),
+        }
+    }
+}
+
+/// 他の人のPRに対して`author`が行ったレビューの集計（レビューの質の目安）
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReviewerStats {
+    /// レビューした他の人のPR数
+    pub reviewed_prs: usize,
+    pub approvals: usize,
+    pub changes_requested: usize,
+    pub commented: usize,
+    /// 自分が始めたレビュースレッド数（スレッドを取得したPRのみ）
+    pub threads_opened: usize,
+    /// そのうち解決済みのスレッド数
+    pub threads_resolved: usize,
+}
+
+impl ReviewerStats {
+    pub fn compute(prs: &[PullRequest], author: &str) -> Self {
+        let mut stats = Self::default();
+        let others = prs
+            .iter()
+            .filter(|pr| pr.author.as_ref().is_none_or(|a| a.login != author));
+        for pr in others {
+            if !pr.authored_reviews.is_empty() {
+                stats.reviewed_prs += 1;
+            }
+            for review in &pr.authored_reviews {
+                match review.state.as_str() {
+                    "APPROVED" => stats.approvals += 1,
+                    "CHANGES_REQUESTED" => stats.changes_requested += 1,
+                    "COMMENTED" => stats.commented += 1,
+                    _ => {}
+                }
+            }
+            for thread in &pr.review_threads {
+                if thread.author.as_deref() == Some(author) {
+                    stats.threads_opened += 1;
+                    if thread.is_resolved {
+                        stats.threads_resolved += 1;
+                    }
+                }
+            }
+        }
+        stats
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.reviewed_prs == 0
+    }
+
+    /// 承認と変更要求のうち承認の割合（%）
+    pub fn approval_rate(&self) -> Option<f64> {
+        ratio(self.approvals, self.approvals + self.changes_requested)
+    }
+
+    /// 自分が始めたスレッドのうち解決済みの割合（%）
+    pub fn resolution_rate(&self) -> Option<f64> {
+        ratio(self.threads_resolved, self.threads_opened)
+    }
+}
+
+fn ratio(part: usize, total: usize) -> Option<f64> {
+    (total > 0).then(|| part as f64 / total as f64 * 100.0)
+}
+
+/// 自分のPR/Issueと、自分のコメントへのリアクションの集計
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EngagementStats {
+    /// リアクションが付いたPR/Issue数
+    pub reacted_items: usize,
+    /// 👍の数
+    pub thumbs_up: usize,
+    /// 🎉の数
+    pub hooray: usize,
+    /// ❤️・🚀の数
+    pub heart_and_rocket: usize,
+    /// すべてのリアクションの数
+    pub total: usize,
+    /// 取得したコメントのうち、自分のコメントへのリアクションの数
+    pub comment_reactions: usize,
+}
+
+impl EngagementStats {
+    pub fn compute(prs: &[PullRequest], issues: &[Issue], author: &str) -> Self {
+        let mut stats = Self::default();
+        let items = prs.iter().map(|pr| (pr.reactions, &pr.comments)).chain(
+            issues
+                .iter()
+                .map(|issue| (issue.reactions, &issue.comments)),
+        );
+        for (reactions, comments) in items {
+            if reactions.total > 0 {
+                stats.reacted_items += 1;
+            }
+            let own_comments = comments
+                .iter()
+                .filter(|c| c.author.as_ref().is_some_and(|a| a.login == author))
+                .map(|c| c.reactions);
+            for r in std::iter::once(reactions).chain(own_comments.clone()) {
+                stats.thumbs_up += r.thumbs_up;
+                stats.hooray += r.hooray;
+                stats.heart_and_rocket += r.heart + r.rocket;
+                stats.total += r.total;
+            }
+            stats.comment_reactions += own_comments.map(|r| r.total).sum::<usize>();
+        }
+        stats
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total == 0
+    }
+}
+
+/// Discussionsでの知識共有の集計
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DiscussionStats {
+    /// 作成したDiscussion数
+    pub created: usize,
+    /// 他のメンバーのDiscussionにコメント（回答）した数
+    pub participated: usize,
+    /// 回答として採用された数
+    pub accepted_answers: usize,
+}
+
+impl DiscussionStats {
+    pub fn compute(discussions: &[Discussion], author: &str) -> Self {
+        let is_own = |d: &&Discussion| d.author.as_ref().is_some_and(|a| a.login == author);
+        Self {
+            created: discussions.iter().filter(is_own).count(),
+            participated: discussions.iter().filter(|d| !is_own(d)).count(),
+            accepted_answers: discussions
+                .iter()
+                .filter(|d| d.answer_author.as_deref() == Some(author))
+                .count(),
+        }
+    }
+}
+
+/// 他のメンバーのIssueでのトリアージの集計（`--triage`指定時のみ）
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TriageStats {
+    /// トリアージしたIssue数
+    pub issues: usize,
+    /// 付けたラベルの延べ数
+    pub labels_applied: usize,
+    /// クローズしたIssue数
+    pub closed: usize,
+    /// うち重複（duplicate）としてクローズした数
+    pub closed_as_duplicate: usize,
+    /// うち対応しない（wontfix）としてクローズした数
+    pub closed_as_not_planned: usize,
+    /// コメントしたIssue数
+    pub commented: usize,
+    /// 起票者以外で最初にコメントしたIssue数
+    pub first_responses: usize,
+}
+
+impl TriageStats {
+    pub fn compute(items: &[TriageItem]) -> Self {
+        let closed_as = |reason: &str| {
+            items
+                .iter()
+                .filter(|item| item.closed_as.as_deref() == Some(reason))
+                .count()
+        };
+        Self {
+            issues: items.len(),
+            labels_applied: items.iter().map(|item| item.labels_applied.len()).sum(),
+            closed: items.iter().filter(|item| item.closed_as.is_some()).count(),
+            closed_as_duplicate: closed_as("DUPLICATE"),
+            closed_as_not_planned: closed_as("NOT_PLANNED"),
+            commented: items.iter().filter(|item| item.commented).count(),
+            first_responses: items.iter().filter(|item| item.first_responder).count(),
+        }
+    }
+
+    /// コメントしたIssueのうち、最初の応答だった割合（0〜1）
+    pub fn first_response_rate(&self) -> f64 {
+        if self.commented == 0 {
+            return 0.0;
+        }
+        self.first_responses as f64 / self.commented as f64
+    }
+}
+
+/// メンションと他のリポジトリからの参照の集計（`--mentions`指定時のみ）
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MentionStats {
+    /// メンションされたPR/Issue数
+    pub mentioned: usize,
+    /// 他のリポジトリから参照された回数
+    pub cross_referenced: usize,
+    /// 他のリポジトリから参照された本人のPR数
+    pub referenced_prs: usize,
+    /// メンション・参照の多いリポジトリ（件数の多い順、上位`MENTION_TOP`件）
+    pub top_repositories: Vec<(String, usize)>,
+    /// メンション・参照の多い人（件数の多い順、上位`MENTION_TOP`件）
+    pub top_people: Vec<(String, usize)>,
+}
+
+/// `MentionStats`で挙げるリポジトリ・人の数
+pub const MENTION_TOP: usize = 5;
+
+impl MentionStats {
+    pub fn compute(mentions: &Mentions) -> Self {
+        let references = || {
+            mentions
+                .mentioned_in
+                .iter()
+                .chain(&mentions.cross_references)
+        };
+        let referenced_prs: HashSet<&str> = mentions
+            .cross_references
+            .iter()
+            .filter_map(|r| r.referenced.as_deref())
+            .collect();
+        Self {
+            mentioned: mentions.mentioned_in.len(),
+            cross_referenced: mentions.cross_references.len(),
+            referenced_prs: referenced_prs.len(),
+            top_repositories: top_counts(
+                references().map(|r| r.repository.name_with_owner.as_str()),
+            ),
+            top_people: top_counts(
+                references().filter_map(|r| r.author.as_ref().map(|a| a.login.as_str())),
+            ),
+        }
+    }
+}
+
+// 出現回数の多い順（同数は名前順）に上位`MENTION_TOP`件
+fn top_counts<'a>(names: impl Iterator<Item = &'a str>) -> Vec<(String, usize)> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for name in names {
+        *counts.entry(name).or_default() += 1;
+    }
+    let mut counts: Vec<(String, usize)> = counts
+        .into_iter()
+        .map(|(name, count)| (name.to_string(), count))
+        .collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts.truncate(MENTION_TOP);
+    counts
+}
+
+/// マージ済みPRの "closes #N" などで解決したIssueの集計
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ResolutionStats {
+    /// 解決したIssue数（重複なし）
+    pub resolved: usize,
+    /// うち他のメンバーが起票したIssue数
+    pub filed_by_others: usize,
+}
+
+impl ResolutionStats {
+    pub fn compute(prs: &[PullRequest]) -> Self {
+        let mut seen = HashSet::new();
+        let mut stats = Self::default();
+
+        for issue in prs
+            .iter()
+            .filter(|pr| pr.state == "merged")
+            .flat_map(|pr| &pr.closing_issues)
+        {
+            if !seen.insert(&issue.url) {
+                continue;
+            }
+            stats.resolved += 1;
+            if issue.author.is_some() && !issue.filed_by_author {
+                stats.filed_by_others += 1;
+            }
+        }
+
+        stats
+    }
+}
+
+/// 受けたレビューの集計
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReviewStats {
+    /// 1件以上レビューを受けたPR数
+    pub reviewed_prs: usize,
+    pub approvals: usize,
+    pub changes_requested: usize,
+    pub commented: usize,
+    /// レビュアー別のレビュー数（多い順）
+    pub reviewers: Vec<(String, usize)>,
+}
+
+impl ReviewStats {
+    pub fn compute(prs: &[PullRequest]) -> Self {
+        let mut stats = Self::default();
+        let mut reviewer_counts: HashMap<&str, usize> = HashMap::new();
+
+        for pr in prs {
+            if !pr.reviews.is_empty() {
+                stats.reviewed_prs += 1;
+            }
+            for review in &pr.reviews {
+                match review.state.as_str() {
+                    "APPROVED" => stats.approvals += 1,
+                    "CHANGES_REQUESTED" => stats.changes_requested += 1,
+                    "COMMENTED" => stats.commented += 1,
+                    _ => {}
+                }
+                if let Some(author) = &review.author {
+                    *reviewer_counts.entry(&author.login).or_insert(0) += 1;
+                }
+            }
+        }
+
+        stats.reviewers = sorted_counts(reviewer_counts);
+        stats
+    }
+
+    pub fn total(&self) -> usize {
+        self.approvals + self.changes_requested + self.commented
+    }
+}
+
+// 件数の多い順（同数は名前順）に並べる
+fn sorted_counts(counts: HashMap<&str, usize>) -> Vec<(String, usize)> {
+    let mut sorted: Vec<_> = counts
+        .into_iter()
+        .map(|(key, count)| (key.to_string(), count))
+        .collect();
+    sorted.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    sorted
+}
+
+impl ActivityStats {
+    pub fn compute(prs: &[PullRequest], issues: &[Issue], author: &str) -> Self {
+        // リポジトリ別のPR数を集計
+        let mut repo_counts: HashMap<&str, usize> = HashMap::new();
+        for pr in prs {
+            *repo_counts
+                .entry(&pr.repository.name_with_owner)
+                .or_insert(0) += 1;
+        }
+        let prs_by_repository = sorted_counts(repo_counts);
+
+        // 月別のPR数を集計（createdAtの先頭7文字 = YYYY-MM）
+        let mut month_counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for pr in prs {
+            let month = pr.created_at.get(..7).unwrap_or(&pr.created_at);
+            *month_counts.entry(month).or_insert(0) += 1;
+        }
+        let prs_by_month = month_counts
+            .into_iter()
+            .map(|(month, count)| (month.to_string(), count))
+            .collect();
+
+        // トピック別のPR/Issue数を集計
+        let mut topic_counts: HashMap<&str, usize> = HashMap::new();
+        let all_topics = prs
+            .iter()
+            .flat_map(|pr| &pr.topics)
+            .chain(issues.iter().flat_map(|issue| &issue.topics));
+        for topic in all_topics {
+            *topic_counts.entry(topic).or_insert(0) += 1;
+        }
+
+        Self {
+            total_prs: prs.len(),
+            merged_prs: prs.iter().filter(|pr| pr.state == "merged").count(),
+            open_prs: prs.iter().filter(|pr| pr.state == "open").count(),
+            closed_prs: prs.iter().filter(|pr| pr.state == "closed").count(),
+            draft_prs: prs.iter().filter(|pr| pr.is_draft).count(),
+            revert_prs: prs.iter().filter(|pr| revert::is_revert(pr)).count(),
+            reverted_prs: prs
+                .iter()
+                .filter(|pr| pr.state == "merged" && pr.reverted_by.is_some())
+                .count(),
+            shipped_prs: prs
+                .iter()
+                .filter(|pr| {
+                    pr.state == "merged" && pr.reverted_by.is_none() && !revert::is_revert(pr)
+                })
+                .count(),
+            weighted_merged_prs: None,
+            total_issues: issues.len(),
+            open_issues: issues.iter().filter(|i| i.state == "open").count(),
+            closed_issues: issues.iter().filter(|i| i.state == "closed").count(),
+            prs_by_repository,
+            prs_by_area: Vec::new(),
+            prs_by_month,
+            activity_by_day: daily_counts(prs, issues),
+            reviews_received: ReviewStats::compute(prs),
+            reviews_given: ReviewerStats::compute(prs, author),
+            resolved_issues: ResolutionStats::compute(prs),
+            turnaround: TurnaroundStats::compute(prs, author),
+            shipping: ShipStats::compute(prs),
+            topics: sorted_counts(topic_counts),
+            tech_stack: techstack::breakdown(prs),
+            documentation: DocsStats::compute(prs),
+            engagement: EngagementStats::compute(prs, issues, author),
+            collaborators: collab::network(prs, issues, author),
+            incidents: IncidentStats::default(),
+            work_pattern: None,
+        }
+    }
+}
+
+/// リポジトリ別のPR重要度スコアの合計（高い順）
+pub fn impact_by_repository(prs: &[PullRequest], weights: &ScoringWeights) -> Vec<(String, f64)> {
+    let mut scores: HashMap<&str, f64> = HashMap::new();
+    for pr in prs {
+        *scores.entry(&pr.repository.name_with_owner).or_insert(0.0) += weights.score(pr);
+    }
+    let mut sorted: Vec<_> = scores
+        .into_iter()
+        .map(|(repo, score)| (repo.to_string(), score))
+        .collect();
+    sorted.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    sorted
+}
+
+/// 推移を集計する単位
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Granularity {
+    Week,
+    #[default]
+    Month,
+}
+
+impl Granularity {
+    pub fn label(self) -> &'static str {
+        match self {
+            Granularity::Week => "週別",
+            Granularity::Month => "月別",
+        }
+    }
+
+    // 日付を含む期間の開始日（週は月曜始まり）
+    fn bucket_start(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Granularity::Week => {
+                date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64)
+            }
+            Granularity::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    fn next(self, start: NaiveDate) -> NaiveDate {
+        match self {
+            Granularity::Week => start + chrono::Duration::days(7),
+            Granularity::Month => start + Months::new(1),
+        }
+    }
+
+    // `format`の逆変換
+    fn parse(self, period: &str) -> Option<NaiveDate> {
+        match self {
+            Granularity::Week => parse_date(period),
+            Granularity::Month => parse_date(&format!("{period}-01")),
+        }
+    }
+
+    fn format(self, start: NaiveDate) -> String {
+        match self {
+            Granularity::Week => start.format("%Y-%m-%d").to_string(),
+            Granularity::Month => start.format("%Y-%m").to_string(),
+        }
+    }
+}
+
+/// 週または月ごとのPR/Issue作成数
+#[derive(Debug, Clone, Serialize)]
+pub struct TimeBucket {
+    /// 期間の開始（月別は`YYYY-MM`、週別は月曜日の`YYYY-MM-DD`）
+    pub period: String,
+    pub prs: usize,
+    pub issues: usize,
+    /// 指定した活動のない期間が大半を占める場合、その理由（推移の評価から除外する）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gap: Option<String>,
+}
+
+/// `since`〜`until`を`granularity`ごとに区切り、PR/Issueの作成数を集計
+///
+/// 活動がなかった期間も0件として含めます。
+pub fn timeline(
+    prs: &[PullRequest],
+    issues: &[Issue],
+    since: &str,
+    until: &str,
+    granularity: Granularity,
+) -> Vec<TimeBucket> {
+    let pr_dates: Vec<NaiveDate> = prs
+        .iter()
+        .filter_map(|pr| parse_date(&pr.created_at))
+        .collect();
+    let issue_dates: Vec<NaiveDate> = issues
+        .iter()
+        .filter_map(|issue| parse_date(&issue.created_at))
+        .collect();
+
+    let all_dates = || pr_dates.iter().chain(&issue_dates).copied();
+    let (Some(first), Some(last)) = (
+        parse_date(since).or_else(|| all_dates().min()),
+        parse_date(until).or_else(|| all_dates().max()),
+    ) else {
+        return Vec::new();
+    };
+
+    let mut counts: BTreeMap<NaiveDate, (usize, usize)> = BTreeMap::new();
+    let mut start = granularity.bucket_start(first);
+    while start <= last {
+        counts.insert(start, (0, 0));
+        start = granularity.next(start);
+    }
+    for date in &pr_dates {
+        if let Some(count) = counts.get_mut(&granularity.bucket_start(*date)) {
+            count.0 += 1;
+        }
+    }
+    for date in &issue_dates {
+        if let Some(count) = counts.get_mut(&granularity.bucket_start(*date)) {
+            count.1 += 1;
+        }
+    }
+
+    counts
+        .into_iter()
+        .map(|(start, (prs, issues))| TimeBucket {
+            period: granularity.format(start),
+            prs,
+            issues,
+            gap: None,
+        })
+        .collect()
+}
+
+/// 指定した活動のない期間（`Gap::declared`）が半分以上を占める期間に、除外する理由を付ける
+pub fn mark_gaps(buckets: &mut [TimeBucket], granularity: Granularity, gaps: &[Gap]) {
+    for bucket in buckets {
+        let Some(start) = granularity.parse(&bucket.period) else {
+            continue;
+        };
+        let end = granularity.next(start) - chrono::Duration::days(1);
+        let days = (end - start).num_days() + 1;
+        bucket.gap = gaps
+            .iter()
+            .filter(|gap| gap.declared)
+            .find(|gap| gap.overlap_days(start, end) * 2 >= days)
+            .map(|gap| gap.label.clone().unwrap_or_else(|| "休止期間".to_string()));
+    }
+}
+
+/// 日別（YYYY-MM-DD）のPR/Issue作成数（古い順、活動のあった日のみ）
+pub fn daily_counts(prs: &[PullRequest], issues: &[Issue]) -> Vec<(String, usize)> {
+    let mut counts: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+    let dates = prs
+        .iter()
+        .map(|pr| pr.created_at.as_str())
+        .chain(issues.iter().map(|issue| issue.created_at.as_str()))
+        .filter_map(parse_date);
+    for date in dates {
+        *counts.entry(date).or_insert(0) += 1;
+    }
+    counts
+        .into_iter()
+        .map(|(date, count)| (date.format("%Y-%m-%d").to_string(), count))
+        .collect()
+}
+
+/// カレンダー形式のヒートマップの1週（月曜始まり）
+#[derive(Debug, Clone, Serialize)]
+pub struct HeatmapWeek {
+    /// 週の開始日（月曜日の`YYYY-MM-DD`）
+    pub start: String,
+    /// 月曜〜日曜の作成数（期間外の日は`None`）
+    pub days: [Option<usize>; 7],
+}
+
+/// 日別の作成数を`since`〜`until`の週ごとに並べる
+///
+/// 活動がなかった日も0件として含めます。
+pub fn heatmap(activity_by_day: &[(String, usize)], since: &str, until: &str) -> Vec<HeatmapWeek> {
+    let counts: HashMap<NaiveDate, usize> = activity_by_day
+        .iter()
+        .filter_map(|(date, count)| Some((parse_date(date)?, *count)))
+        .collect();
+    let (Some(first), Some(last)) = (
+        parse_date(since).or_else(|| counts.keys().min().copied()),
+        parse_date(until).or_else(|| counts.keys().max().copied()),
+    ) else {
+        return Vec::new();
+    };
+
+    let mut weeks = Vec::new();
+    let mut start = Granularity::Week.bucket_start(first);
+    while start <= last {
+        let mut days = [None; 7];
+        for (offset, day) in days.iter_mut().enumerate() {
+            let date = start + chrono::Duration::days(offset as i64);
+            if first <= date && date <= last {
+                *day = Some(counts.get(&date).copied().unwrap_or(0));
+            }
+        }
+        weeks.push(HeatmapWeek {
+            start: Granularity::Week.format(start),
+            days,
+        });
+        start = Granularity::Week.next(start);
+    }
+    weeks
+}
+
+// `YYYY-MM-DD`またはRFC 3339の日時から日付部分を取り出す
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.get(..10)?, "%Y-%m-%d").ok()
+}
@@ -0,0 +1,182 @@
+//! 本文・コメントから画像やHTMLなどのノイズを取り除く（`--strip`、設定ファイルの`[strip]`）
+//!
+//! スクリーンショットの多いPRの本文は、埋め込み画像（base64を含む）やHTMLのマークアップ、
+//! 長いログのコードブロックでプロンプトが膨らむため、プロンプトの構築前に短い目印へ置き換えます。
+
+use crate::github::{Issue, PullRequest};
+use serde::Deserialize;
+use std::sync::LazyLock;
+
+/// 取り除く対象（設定ファイルの`[strip]`）
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct StripRules {
+    /// Markdown・HTMLの画像とbase64のデータURIを`[画像: 代替テキスト]`に置き換える
+    pub images: bool,
+    /// HTMLコメントを除き、HTMLタグは中のテキストだけを残す
+    pub html: bool,
+    /// `max_code_lines`行を超えるコードブロックを省略する
+    pub code_blocks: bool,
+    pub max_code_lines: usize,
+}
+
+impl Default for StripRules {
+    fn default() -> Self {
+        Self {
+            images: true,
+            html: true,
+            code_blocks: true,
+            max_code_lines: 40,
+        }
+    }
+}
+
+static MARKDOWN_IMAGE: LazyLock<regex::Regex> = LazyLock::new(|| {
+    regex::Regex::new(r"!\[([^\]]*)\]\([^)]*\)").expect("image pattern should be valid")
+});
+static HTML_IMAGE: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"(?is)<img\b[^>]*>").expect("img pattern should be valid"));
+static IMAGE_ALT: LazyLock<regex::Regex> = LazyLock::new(|| {
+    regex::Regex::new(r#"(?i)\balt\s*=\s*"([^"]*)""#).expect("alt pattern should be valid")
+});
+static DATA_URI: LazyLock<regex::Regex> = LazyLock::new(|| {
+    regex::Regex::new(r"data:[\w/+.-]+;base64,[A-Za-z0-9+/=]+")
+        .expect("data uri pattern should be valid")
+});
+/// `<!-- ... -->`（複数行を含み、閉じていなければ末尾まで）
+pub(crate) static HTML_COMMENT: LazyLock<regex::Regex> = LazyLock::new(|| {
+    regex::Regex::new(r"(?s)<!--.*?(?:-->|\z)").expect("comment pattern should be valid")
+});
+static HTML_TAG: LazyLock<regex::Regex> = LazyLock::new(|| {
+    // `Vec<String>`のような本文中の山括弧を消さないよう、よく使われるタグに限る
+    regex::Regex::new(
+        r"(?i)</?(?:a|b|i|em|strong|br|hr|p|div|span|sub|sup|kbd|details|summary|table|thead|tbody|tr|th|td|ul|ol|li|h[1-6]|picture|source|video|center|blockquote)\b[^<>]*/?>",
+    )
+    .expect("tag pattern should be valid")
+});
+
+impl StripRules {
+    /// `text`からノイズを取り除く
+    pub fn apply(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        if self.images {
+            text = MARKDOWN_IMAGE
+                .replace_all(&text, |caps: &regex::Captures| image_marker(&caps[1]))
+                .into_owned();
+            text = HTML_IMAGE
+                .replace_all(&text, |caps: &regex::Captures| {
+                    let alt = IMAGE_ALT.captures(&caps[0]);
+                    image_marker(alt.as_ref().map_or("", |alt| &alt[1]))
+                })
+                .into_owned();
+            text = DATA_URI.replace_all(&text, "[base64データ]").into_owned();
+        }
+        if self.html {
+            text = HTML_COMMENT.replace_all(&text, "").into_owned();
+            text = HTML_TAG.replace_all(&text, "").into_owned();
+        }
+        if self.code_blocks {
+            text = collapse_code_blocks(&text, self.max_code_lines);
+        }
+        text
+    }
+}
+
+fn image_marker(alt: &str) -> String {
+    match alt.trim() {
+        "" => "[画像]".to_string(),
+        alt => format!("[画像: {alt}]"),
+    }
+}
+
+// `max_lines`行を超えるフェンス付きコードブロックを、行数だけの目印にする
+fn collapse_code_blocks(text: &str, max_lines: usize) -> String {
+    let mut output: Vec<String> = Vec::new();
+    let mut block: Option<(String, Vec<&str>)> = None;
+    for line in text.lines() {
+        let is_fence = line.trim_start().starts_with("```");
+        match block.take() {
+            None if is_fence => block = Some((line.to_string(), Vec::new())),
+            None => output.push(line.to_string()),
+            Some((fence, lines)) if is_fence => {
+                if lines.len() > max_lines {
+                    output.push(fence);
+                    output.push(format!("（{}行のコードを省略）", lines.len()));
+                } else {
+                    output.push(fence);
+                    output.extend(lines.iter().map(|l| l.to_string()));
+                }
+                output.push(line.to_string());
+            }
+            Some((fence, mut lines)) => {
+                lines.push(line);
+                block = Some((fence, lines));
+            }
+        }
+    }
+    // 閉じられていないコードブロックはそのまま残す
+    if let Some((fence, lines)) = block {
+        output.push(fence);
+        output.extend(lines.iter().map(|l| l.to_string()));
+    }
+    output.join("\n")
+}
+
+/// PR/Issueの本文・コメント・レビューにルールを適用し、短くなった件数を返す
+pub fn strip_activity(rules: &StripRules, prs: &mut [PullRequest], issues: &mut [Issue]) -> usize {
+    let mut changed = 0;
+    let mut strip = |text: &mut String| {
+        let stripped = rules.apply(text);
+        if stripped.len() < text.len() {
+            *text = stripped;
+            changed += 1;
+        }
+    };
+    for pr in prs.iter_mut() {
+        pr.body.iter_mut().for_each(&mut strip);
+        pr.comments.iter_mut().for_each(|c| strip(&mut c.body));
+        pr.reviews.iter_mut().for_each(|r| strip(&mut r.body));
+    }
+    for issue in issues.iter_mut() {
+        issue.body.iter_mut().for_each(&mut strip);
+        issue.comments.iter_mut().for_each(|c| strip(&mut c.body));
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collapse_code_blocks_replaces_long_blocks_with_a_marker() {
+        let text = "before\n```log\n1\n2\n3\n```\nafter";
+        assert_eq!(
+            collapse_code_blocks(text, 2),
+            "before\n```log\n（3行のコードを省略）\n```\nafter"
+        );
+    }
+
+    #[test]
+    fn collapse_code_blocks_keeps_short_and_unclosed_blocks() {
+        let short = "```\n1\n2\n```";
+        assert_eq!(collapse_code_blocks(short, 2), short);
+        let unclosed = "text\n```\n1\n2\n3";
+        assert_eq!(collapse_code_blocks(unclosed, 2), unclosed);
+    }
+
+    #[test]
+    fn apply_replaces_images_and_drops_html_comments() {
+        let rules = StripRules::default();
+        let text = "![screenshot](https://example.com/a.png)\n<!-- hint -->\n<img alt=\"diagram\" src=\"x.png\">\n<b>bold</b>";
+        assert_eq!(
+            rules.apply(text),
+            "[画像: screenshot]\n\n[画像: diagram]\nbold"
+        );
+    }
+
+    #[test]
+    fn html_comment_drops_unclosed_comments_to_the_end() {
+        assert_eq!(HTML_COMMENT.replace_all("keep<!-- open", ""), "keep");
+    }
+}
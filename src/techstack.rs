@@ -0,0 +1,131 @@
+//! 変更ファイルのパスから技術スタックを判定
+
+use crate::github::PullRequest;
+use std::collections::HashMap;
+
+// 拡張子と言語・技術の対応
+const EXTENSIONS: &[(&str, &str)] = &[
+    ("rs", "Rust"),
+    ("go", "Go"),
+    ("py", "Python"),
+    ("rb", "Ruby"),
+    ("erb", "Ruby"),
+    ("java", "Java"),
+    ("kt", "Kotlin"),
+    ("kts", "Kotlin"),
+    ("scala", "Scala"),
+    ("swift", "Swift"),
+    ("m", "Objective-C"),
+    ("c", "C"),
+    ("h", "C"),
+    ("cc", "C++"),
+    ("cpp", "C++"),
+    ("hpp", "C++"),
+    ("cs", "C#"),
+    ("php", "PHP"),
+    ("ex", "Elixir"),
+    ("exs", "Elixir"),
+    ("dart", "Dart"),
+    ("ts", "TypeScript"),
+    ("tsx", "TypeScript"),
+    ("js", "JavaScript"),
+    ("jsx", "JavaScript"),
+    ("mjs", "JavaScript"),
+    ("vue", "Vue"),
+    ("svelte", "Svelte"),
+    ("css", "CSS"),
+    ("scss", "CSS"),
+    ("sass", "CSS"),
+    ("html", "HTML"),
+    ("sql", "SQL"),
+    ("tf", "Terraform"),
+    ("hcl", "Terraform"),
+    ("sh", "Shell"),
+    ("bash", "Shell"),
+    ("proto", "Protocol Buffers"),
+    ("graphql", "GraphQL"),
+    ("md", "Markdown"),
+    ("mdx", "Markdown"),
+    ("yml", "YAML"),
+    ("yaml", "YAML"),
+];
+
+// 技術スタックの判定から除外する生成ファイル
+const IGNORED_FILES: &[&str] = &[
+    "Cargo.lock",
+    "package-lock.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "Gemfile.lock",
+    "poetry.lock",
+    "go.sum",
+    "composer.lock",
+];
+
+/// ファイルパスから言語・技術を判定（判定できない・生成ファイルの場合は`None`）
+pub fn language(path: &str) -> Option<&'static str> {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    if IGNORED_FILES.contains(&name) {
+        return None;
+    }
+    if path.starts_with(".github/workflows/") {
+        return Some("GitHub Actions");
+    }
+    if name == "Dockerfile" || name.starts_with("Dockerfile.") || name.ends_with(".dockerfile") {
+        return Some("Docker");
+    }
+    if name == "Makefile" {
+        return Some("Make");
+    }
+
+    let (_, extension) = name.rsplit_once('.')?;
+    let extension = extension.to_lowercase();
+    EXTENSIONS
+        .iter()
+        .find(|(ext, _)| *ext == extension)
+        .map(|(_, language)| *language)
+}
+
+// ドキュメントとみなす拡張子
+const DOC_EXTENSIONS: &[&str] = &["md", "mdx", "rst", "adoc", "txt"];
+
+/// ドキュメントのファイルか（`docs/`・`doc/`配下、Markdownなどのテキスト）
+pub fn is_documentation(path: &str) -> bool {
+    if path.starts_with("docs/") || path.starts_with("doc/") || path.contains("/docs/") {
+        return true;
+    }
+    let name = path.rsplit('/').next().unwrap_or(path);
+    name.rsplit_once('.')
+        .is_some_and(|(_, ext)| DOC_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+/// 変更行数の過半がドキュメントのPRか（変更ファイル取得時のみ判定できる）
+pub fn is_docs_pr(pr: &PullRequest) -> bool {
+    let (docs, total) = pr.files.iter().fold((0, 0), |(docs, total), file| {
+        let lines = (file.additions + file.deletions).max(1);
+        if is_documentation(&file.path) {
+            (docs + lines, total + lines)
+        } else {
+            (docs, total + lines)
+        }
+    });
+    total > 0 && docs * 2 > total
+}
+
+/// 言語・技術別の変更行数（追加+削除、多い順）
+pub fn breakdown(prs: &[PullRequest]) -> Vec<(String, usize)> {
+    let mut lines: HashMap<&str, usize> = HashMap::new();
+    for file in prs.iter().flat_map(|pr| &pr.files) {
+        if let Some(language) = language(&file.path) {
+            *lines.entry(language).or_insert(0) += file.additions + file.deletions;
+        }
+    }
+
+    let mut sorted: Vec<_> = lines
+        .into_iter()
+        .filter(|(_, count)| *count > 0)
+        .map(|(language, count)| (language.to_string(), count))
+        .collect();
+    sorted.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    sorted
+}
@@ -0,0 +1,117 @@
+//! PR本文のテンプレートの整理（`--template-sections`）
+//!
+//! 「## What」「## Why」「## Testing」のような見出しで区切られた本文から、
+//! 記入されていないセクションや定型のチェックリストを除き、意味のあるセクションだけを残します。
+
+use crate::github::PullRequest;
+use crate::strip::HTML_COMMENT;
+
+// 中身に関わらず除外する見出し（小文字で部分一致）
+const BOILERPLATE_HEADINGS: &[&str] = &["checklist", "check list", "チェックリスト", "確認事項"];
+
+// 未記入とみなす本文
+const PLACEHOLDERS: &[&str] = &["n/a", "na", "none", "tbd", "-", "なし", "特になし", "無し"];
+
+/// テンプレートの見出しで区切られた本文から、意味のあるセクションだけを残す
+///
+/// HTMLコメント（テンプレートの記入例）と未チェックのチェックボックスは常に除きます。
+/// 見出しのない本文は、HTMLコメントなどを除く以外はそのまま返します。
+pub fn extract_sections(body: &str) -> String {
+    let body = HTML_COMMENT.replace_all(body, "");
+
+    let mut kept: Vec<String> = Vec::new();
+    let mut heading: Option<&str> = None;
+    let mut lines: Vec<&str> = Vec::new();
+    let mut in_fence = false;
+    for line in body.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        if !in_fence && is_heading(line) {
+            push_section(&mut kept, heading, &lines);
+            heading = Some(line);
+            lines.clear();
+        } else {
+            lines.push(line);
+        }
+    }
+    push_section(&mut kept, heading, &lines);
+    kept.join("\n\n")
+}
+
+/// `--template-sections`指定時に、すべてのPRの本文を整理し、短くなった件数を返す
+pub fn extract_pr_sections(prs: &mut [PullRequest]) -> usize {
+    let mut changed = 0;
+    for body in prs.iter_mut().filter_map(|pr| pr.body.as_mut()) {
+        let extracted = extract_sections(body);
+        if extracted.len() < body.len() {
+            *body = extracted;
+            changed += 1;
+        }
+    }
+    changed
+}
+
+fn is_heading(line: &str) -> bool {
+    let hashes = line.chars().take_while(|c| *c == '#').count();
+    (1..=6).contains(&hashes) && line[hashes..].starts_with(' ')
+}
+
+// 意味のある行があるセクションだけを`kept`に加える
+fn push_section(kept: &mut Vec<String>, heading: Option<&str>, lines: &[&str]) {
+    if let Some(heading) = heading {
+        let title = heading.trim_start_matches('#').trim().to_lowercase();
+        if BOILERPLATE_HEADINGS.iter().any(|b| title.contains(b)) {
+            return;
+        }
+    }
+    let content: Vec<&str> = lines
+        .iter()
+        .copied()
+        .filter(|line| !is_unchecked_box(line))
+        .collect();
+    let meaningful = content.iter().any(|line| {
+        let line = line.trim();
+        !line.is_empty() && !PLACEHOLDERS.contains(&line.to_lowercase().as_str())
+    });
+    if !meaningful {
+        return;
+    }
+    let text = content.join("\n");
+    kept.push(match heading {
+        Some(heading) => format!("{heading}\n{}", text.trim()),
+        None => text.trim().to_string(),
+    });
+}
+
+fn is_unchecked_box(line: &str) -> bool {
+    let line = line.trim_start();
+    ["- [ ]", "* [ ]", "+ [ ]"]
+        .iter()
+        .any(|prefix| line.starts_with(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_sections_keeps_only_filled_in_sections() {
+        let body = "## What\n<!-- 変更内容を書く -->\nパーサーを追加\n\n## Why\nN/A\n\n## Checklist\n- [x] テストを追加\n\n## Testing\n- [ ] 手動で確認\n";
+        assert_eq!(extract_sections(body), "## What\nパーサーを追加");
+    }
+
+    #[test]
+    fn extract_sections_ignores_headings_in_code_blocks() {
+        let body = "## What\n```sh\n## not a heading\n```";
+        assert_eq!(extract_sections(body), body);
+    }
+
+    #[test]
+    fn extract_sections_returns_body_without_headings_as_is() {
+        assert_eq!(
+            extract_sections("ただの本文<!-- memo -->です"),
+            "ただの本文です"
+        );
+    }
+}
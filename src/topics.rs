@@ -0,0 +1,180 @@
+//! PR/Issueへのトピック（infra, frontend, testing…）の付与
+//!
+//! タイトル・ラベルのキーワードによるルールで付与し、
+//! ルールで判定できなかったものは任意で安価なモデルに分類させます（`--classify-topics`）。
+
+use crate::github::{Issue, Label, PullRequest};
+use crate::llm::{self, LlmClient, OpenAiClient};
+use anyhow::Result;
+use std::collections::HashMap;
+
+/// トピックと判定に使うキーワード（小文字）
+pub const TOPIC_RULES: &[(&str, &[&str])] = &[
+    (
+        "infra",
+        &[
+            "infra",
+            "terraform",
+            "kubernetes",
+            "k8s",
+            "docker",
+            "helm",
+            "aws",
+            "gcp",
+            "インフラ",
+        ],
+    ),
+    ("ci", &["ci", "github actions", "workflow", "pipeline"]),
+    (
+        "frontend",
+        &["frontend", "ui", "css", "react", "vue", "画面", "デザイン"],
+    ),
+    (
+        "backend",
+        &[
+            "backend",
+            "api",
+            "endpoint",
+            "database",
+            "db",
+            "migration",
+            "sql",
+        ],
+    ),
+    ("testing", &["test", "spec", "e2e", "テスト"]),
+    ("docs", &["doc", "readme", "typo", "ドキュメント"]),
+    (
+        "security",
+        &[
+            "security",
+            "vulnerab",
+            "cve",
+            "authentication",
+            "authorization",
+            "oauth",
+            "脆弱性",
+            "セキュリティ",
+        ],
+    ),
+    (
+        "performance",
+        &[
+            "performance",
+            "optimi",
+            "latency",
+            "cache",
+            "speed up",
+            "高速化",
+        ],
+    ),
+    (
+        "refactor",
+        &["refactor", "cleanup", "clean up", "リファクタ"],
+    ),
+    ("bugfix", &["fix", "bug", "hotfix", "不具合", "修正"]),
+    (
+        "dependencies",
+        &["bump", "upgrade", "dependabot", "renovate", "dependenc"],
+    ),
+];
+
+/// タイトルとラベルからルールでトピックを判定
+///
+/// 英字のキーワードは単語の先頭との一致（2文字以下は単語全体の一致）、
+/// 空白を含むものや日本語は部分一致で判定します。
+pub fn tag_by_rules(title: &str, labels: &[Label]) -> Vec<String> {
+    let text = std::iter::once(title)
+        .chain(labels.iter().map(|l| l.name.as_str()))
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    let words: Vec<&str> = text
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    let matches = |keyword: &str| {
+        if !keyword.is_ascii() || keyword.contains(' ') {
+            text.contains(keyword)
+        } else if keyword.len() <= 2 {
+            words.contains(&keyword)
+        } else {
+            words.iter().any(|w| w.starts_with(keyword))
+        }
+    };
+
+    TOPIC_RULES
+        .iter()
+        .filter(|(_, keywords)| keywords.iter().any(|k| matches(k)))
+        .map(|(topic, _)| topic.to_string())
+        .collect()
+}
+
+/// すべてのPR/Issueにルールでトピックを付与
+pub fn tag_activity(prs: &mut [PullRequest], issues: &mut [Issue]) {
+    for pr in prs.iter_mut() {
+        pr.topics = tag_by_rules(&pr.title, &pr.labels);
+    }
+    for issue in issues.iter_mut() {
+        issue.topics = tag_by_rules(&issue.title, &issue.labels);
+    }
+}
+
+/// ルールでトピックが付かなかったPR/Issueを安価なモデルで分類
+///
+/// 分類できなかったもの（応答の形式が不正など）はトピックなしのままにします。
+pub async fn classify_untagged(
+    client: &OpenAiClient,
+    prs: &mut [PullRequest],
+    issues: &mut [Issue],
+) -> Result<()> {
+    let untagged: Vec<(&str, &str)> = prs
+        .iter()
+        .filter(|pr| pr.topics.is_empty())
+        .map(|pr| (pr.url.as_str(), pr.title.as_str()))
+        .chain(
+            issues
+                .iter()
+                .filter(|issue| issue.topics.is_empty())
+                .map(|issue| (issue.url.as_str(), issue.title.as_str())),
+        )
+        .collect();
+    if untagged.is_empty() {
+        return Ok(());
+    }
+
+    let topics: Vec<&str> = TOPIC_RULES.iter().map(|(topic, _)| *topic).collect();
+    let mut prompt = format!(
+        "以下のGitHubのPR/Issueを、次のトピックから該当するもの（0〜2個）に分類してください。\nトピック: {}\n\n",
+        topics.join(", ")
+    );
+    for (url, title) in &untagged {
+        prompt.push_str(&format!("- {url} {title}\n"));
+    }
+    prompt.push_str("\n出力はURLをキー、トピックの配列を値とするJSONオブジェクトのみとしてください（例: {\"https://github.com/...\": [\"infra\"]}）。\n");
+
+    let classifier = client.clone().with_model(llm::LIGHT_MODEL);
+    let response = classifier
+        .chat("あなたはPR/Issueの分類を手伝うアシスタントです。", &prompt)
+        .await?;
+    let classified: HashMap<String, Vec<String>> =
+        serde_json::from_str(llm::strip_code_fence(&response)).unwrap_or_default();
+
+    // 定義済みのトピック以外は捨てる
+    let lookup = |url: &str| -> Vec<String> {
+        classified
+            .get(url)
+            .into_iter()
+            .flatten()
+            .filter(|topic| topics.contains(&topic.as_str()))
+            .cloned()
+            .collect()
+    };
+    for pr in prs.iter_mut().filter(|pr| pr.topics.is_empty()) {
+        pr.topics = lookup(&pr.url);
+    }
+    for issue in issues.iter_mut().filter(|issue| issue.topics.is_empty()) {
+        issue.topics = lookup(&issue.url);
+    }
+    Ok(())
+}
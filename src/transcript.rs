@@ -0,0 +1,88 @@
+//! LLM呼び出しの記録（`--save-transcript`）
+//!
+//! 評価資料の出どころを後から確認できるよう、呼び出しごとにシステムプロンプト・ユーザープロンプト・
+//! APIへのリクエストと応答のJSON・トークン使用量を1ファイルのJSONとして保存します。
+//! ストリーミングの応答は組み立てた本文を、キャッシュ済みの応答はその旨とキャッシュの本文を記録します。
+
+use crate::llm::Message;
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+// 同じミリ秒の呼び出しでもファイル名が重ならないよう、プロセス内で通し番号を振る
+static SEQUENCE: AtomicUsize = AtomicUsize::new(0);
+
+/// 記録の保存先
+#[derive(Debug, Clone)]
+pub struct Transcript {
+    dir: PathBuf,
+}
+
+/// 1回分の呼び出し
+#[derive(Debug, Clone, Copy)]
+pub struct Call<'a> {
+    pub provider: &'a str,
+    pub model: &'a str,
+    pub messages: &'a [Message],
+    /// APIに送ったリクエスト（キャッシュ済みの応答を使った場合は`None`）
+    pub request: Option<&'a Value>,
+    /// APIの応答（ストリーミング・キャッシュの場合は`{"content": 本文}`）
+    pub response: &'a Value,
+    /// リクエストの送信時刻（キャッシュの場合は`None`）
+    pub started: Option<Instant>,
+}
+
+impl Transcript {
+    /// `dir`に保存する（ディレクトリは最初の記録時に作成）
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn record(&self, call: Call<'_>) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("記録の保存先を作成できません: {}", self.dir.display()))?;
+        let now = chrono::Local::now();
+        let sequence = SEQUENCE.fetch_add(1, Ordering::Relaxed) + 1;
+        let model: String = call
+            .model
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let path = self.dir.join(format!(
+            "{}-{sequence:04}-{model}.json",
+            now.format("%Y%m%dT%H%M%S%.3f")
+        ));
+
+        let prompt = |role: &str| {
+            call.messages
+                .iter()
+                .filter(|m| m.role == role)
+                .map(|m| m.content.as_str())
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        };
+        let record = serde_json::json!({
+            "timestamp": now.to_rfc3339(),
+            "provider": call.provider,
+            "model": call.model,
+            "cached": call.request.is_none(),
+            "elapsed_ms": call.started.map(|s| s.elapsed().as_millis() as u64),
+            "system_prompt": prompt("system"),
+            "user_prompt": prompt("user"),
+            "messages": call.messages,
+            "request": call.request,
+            "response": call.response,
+            "usage": call.response.get("usage"),
+        });
+        std::fs::write(&path, serde_json::to_string_pretty(&record)?)
+            .with_context(|| format!("記録を保存できません: {}", path.display()))
+    }
+}
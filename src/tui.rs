@@ -0,0 +1,345 @@
+//! `--tui` モード: 取得したPR/Issueを一覧で選択し、プロンプト確認・サマリー生成を行う
+
+use anyhow::Result;
+use easy_hyoka::github::{Issue, PullRequest, SearchQuery};
+use easy_hyoka::llm::{self, ProviderClient};
+use easy_hyoka::report::{self, PromptOptions};
+use ratatui::DefaultTerminal;
+use ratatui::Frame;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum View {
+    List,
+    Prompt,
+    Summary,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ItemRef {
+    Pr(usize),
+    Issue(usize),
+}
+
+struct Item {
+    item: ItemRef,
+    included: bool,
+}
+
+struct App {
+    query: SearchQuery,
+    prs: Vec<PullRequest>,
+    issues: Vec<Issue>,
+    items: Vec<Item>,
+    list_state: ListState,
+    view: View,
+    scroll: u16,
+    prompt: String,
+    summary: Option<String>,
+    status: String,
+    client: Option<ProviderClient>,
+    per_repo: bool,
+    options: PromptOptions,
+    generation: Option<JoinHandle<Result<String>>>,
+}
+
+impl App {
+    fn new(
+        query: SearchQuery,
+        prs: Vec<PullRequest>,
+        issues: Vec<Issue>,
+        client: Option<ProviderClient>,
+        per_repo: bool,
+        options: PromptOptions,
+    ) -> Self {
+        let items = (0..prs.len())
+            .map(ItemRef::Pr)
+            .chain((0..issues.len()).map(ItemRef::Issue))
+            .map(|item| Item {
+                item,
+                included: true,
+            })
+            .collect::<Vec<_>>();
+
+        let mut list_state = ListState::default();
+        if !items.is_empty() {
+            list_state.select(Some(0));
+        }
+
+        Self {
+            query,
+            prs,
+            issues,
+            items,
+            list_state,
+            view: View::List,
+            scroll: 0,
+            prompt: String::new(),
+            summary: None,
+            status: String::new(),
+            client,
+            per_repo,
+            options,
+            generation: None,
+        }
+    }
+
+    // チェックの入ったPR/Issueだけを取り出す
+    fn selected_data(&self) -> (Vec<PullRequest>, Vec<Issue>) {
+        let mut prs = Vec::new();
+        let mut issues = Vec::new();
+        for item in self.items.iter().filter(|i| i.included) {
+            match item.item {
+                ItemRef::Pr(i) => prs.push(self.prs[i].clone()),
+                ItemRef::Issue(i) => issues.push(self.issues[i].clone()),
+            }
+        }
+        (prs, issues)
+    }
+
+    fn included_count(&self) -> usize {
+        self.items.iter().filter(|i| i.included).count()
+    }
+
+    fn toggle_current(&mut self) {
+        if let Some(i) = self.list_state.selected() {
+            self.items[i].included = !self.items[i].included;
+        }
+    }
+
+    fn toggle_all(&mut self) {
+        let include = self.items.iter().any(|i| !i.included);
+        for item in &mut self.items {
+            item.included = include;
+        }
+    }
+
+    fn show_prompt(&mut self) {
+        let (prs, issues) = self.selected_data();
+        self.prompt = if self.per_repo {
+            let mut prompt = String::new();
+            for repo in report::group_by_repository(&prs, &issues) {
+                prompt.push_str(&format!("【ユーザープロンプト: {}】\n", repo.name));
+                match report::build_repository_prompt(&self.query, &repo, &self.options) {
+                    Ok(p) => prompt.push_str(&p),
+                    Err(e) => prompt.push_str(&format!("プロンプトの構築に失敗しました: {e}")),
+                }
+                prompt.push('\n');
+            }
+            prompt
+        } else {
+            report::build_prompt(&self.query, &prs, &issues, &self.options)
+                .unwrap_or_else(|e| format!("プロンプトの構築に失敗しました: {e}"))
+        };
+        self.view = View::Prompt;
+        self.scroll = 0;
+    }
+
+    fn start_generation(&mut self) {
+        if self.generation.is_some() {
+            return;
+        }
+        let Some(client) = self.client.clone() else {
+            self.status =
+                "APIキーの環境変数（OPENAI_API_KEYなど）が設定されていないため生成できません"
+                    .to_string();
+            return;
+        };
+        if self.included_count() == 0 {
+            self.status = "対象のPR/Issueが選択されていません".to_string();
+            return;
+        }
+
+        let (prs, issues) = self.selected_data();
+        let query = self.query.clone();
+        let per_repo = self.per_repo;
+        let options = self.options.clone();
+        self.generation = Some(tokio::spawn(async move {
+            if per_repo {
+                llm::generate_per_repo_summary(&client, &query, &prs, &issues, &options, |_| {})
+                    .await
+            } else {
+                llm::generate_summary(&client, &query, &prs, &issues, &options).await
+            }
+        }));
+        self.status = "実績サマリーを生成中...".to_string();
+    }
+
+    async fn poll_generation(&mut self) {
+        if !self.generation.as_ref().is_some_and(|h| h.is_finished()) {
+            return;
+        }
+        let Some(handle) = self.generation.take() else {
+            return;
+        };
+        match handle.await {
+            Ok(Ok(summary)) => {
+                self.summary = Some(summary);
+                self.status = "サマリーを生成しました".to_string();
+                self.view = View::Summary;
+                self.scroll = 0;
+            }
+            Ok(Err(e)) => self.status = format!("生成に失敗しました: {e}"),
+            Err(e) => self.status = format!("生成に失敗しました: {e}"),
+        }
+    }
+
+    fn item_label(&self, item: &Item) -> String {
+        let check = if item.included { "[x]" } else { "[ ]" };
+        match item.item {
+            ItemRef::Pr(i) => {
+                let pr = &self.prs[i];
+                format!(
+                    "{check} PR    {}#{} {} ({})",
+                    pr.repository.name_with_owner, pr.number, pr.title, pr.state
+                )
+            }
+            ItemRef::Issue(i) => {
+                let issue = &self.issues[i];
+                format!(
+                    "{check} Issue {}#{} {} ({})",
+                    issue.repository.name_with_owner, issue.number, issue.title, issue.state
+                )
+            }
+        }
+    }
+}
+
+/// TUIを起動し、終了するまでイベントループを回す
+pub async fn run(
+    query: SearchQuery,
+    prs: Vec<PullRequest>,
+    issues: Vec<Issue>,
+    client: Option<ProviderClient>,
+    per_repo: bool,
+    options: PromptOptions,
+) -> Result<()> {
+    let mut app = App::new(query, prs, issues, client, per_repo, options);
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut app).await;
+    ratatui::restore();
+
+    // 終了後も結果を参照できるように、生成済みのサマリーを出力
+    if let Some(summary) = &app.summary {
+        println!("\n実績サマリー");
+        println!("=====================================");
+        println!("{summary}");
+    }
+
+    result
+}
+
+async fn event_loop(terminal: &mut DefaultTerminal, app: &mut App) -> Result<()> {
+    loop {
+        app.poll_generation().await;
+        terminal.draw(|frame| draw(frame, app))?;
+
+        if !event::poll(Duration::from_millis(200))? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match (app.view, key.code) {
+            (_, KeyCode::Char('q')) => return Ok(()),
+            (View::List, KeyCode::Down | KeyCode::Char('j')) => app.list_state.select_next(),
+            (View::List, KeyCode::Up | KeyCode::Char('k')) => app.list_state.select_previous(),
+            (View::List, KeyCode::Char(' ')) => app.toggle_current(),
+            (View::List, KeyCode::Char('a')) => app.toggle_all(),
+            (_, KeyCode::Char('p')) => app.show_prompt(),
+            (_, KeyCode::Char('g')) => app.start_generation(),
+            (_, KeyCode::Char('s')) if app.summary.is_some() => {
+                app.view = View::Summary;
+                app.scroll = 0;
+            }
+            (View::Prompt | View::Summary, KeyCode::Esc | KeyCode::Char('l')) => {
+                app.view = View::List;
+            }
+            (View::Prompt | View::Summary, KeyCode::Down | KeyCode::Char('j')) => {
+                app.scroll = app.scroll.saturating_add(1);
+            }
+            (View::Prompt | View::Summary, KeyCode::Up | KeyCode::Char('k')) => {
+                app.scroll = app.scroll.saturating_sub(1);
+            }
+            (View::Prompt | View::Summary, KeyCode::PageDown) => {
+                app.scroll = app.scroll.saturating_add(20);
+            }
+            (View::Prompt | View::Summary, KeyCode::PageUp) => {
+                app.scroll = app.scroll.saturating_sub(20);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, app: &mut App) {
+    let [header, body, footer] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(0),
+        Constraint::Length(2),
+    ])
+    .areas(frame.area());
+
+    frame.render_widget(
+        Line::from(format!(
+            "easyhyoka - {} ({})  選択中: {}/{}件",
+            app.query.author,
+            app.query.period(),
+            app.included_count(),
+            app.items.len()
+        ))
+        .bold(),
+        header,
+    );
+
+    match app.view {
+        View::List => {
+            let items: Vec<ListItem> = app
+                .items
+                .iter()
+                .map(|item| ListItem::new(app.item_label(item)))
+                .collect();
+            let list = List::new(items)
+                .block(Block::bordered().title("PR/Issue一覧"))
+                .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+            frame.render_stateful_widget(list, body, &mut app.list_state);
+        }
+        View::Prompt => {
+            let paragraph = Paragraph::new(app.prompt.as_str())
+                .block(Block::bordered().title("プロンプトプレビュー"))
+                .wrap(Wrap { trim: false })
+                .scroll((app.scroll, 0));
+            frame.render_widget(paragraph, body);
+        }
+        View::Summary => {
+            let paragraph = Paragraph::new(app.summary.as_deref().unwrap_or(""))
+                .block(Block::bordered().title("実績サマリー"))
+                .wrap(Wrap { trim: false })
+                .scroll((app.scroll, 0));
+            frame.render_widget(paragraph, body);
+        }
+    }
+
+    let help = match app.view {
+        View::List => {
+            "↑↓/jk: 移動  Space: 選択切替  a: 全選択切替  p: プロンプト  g: 生成  s: サマリー  q: 終了"
+        }
+        View::Prompt | View::Summary => {
+            "↑↓/jk/PgUp/PgDn: スクロール  Esc/l: 一覧  p: プロンプト  g: 生成  q: 終了"
+        }
+    };
+    frame.render_widget(
+        Paragraph::new(vec![Line::from(app.status.as_str()), Line::from(help)]),
+        footer,
+    );
+}
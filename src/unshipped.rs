@@ -0,0 +1,106 @@
+//! 出荷されなかった作業（`--unshipped`）
+//!
+//! 長くオープンのままのPR、動きのないIssue、マージされずにクローズしたPRを集め、
+//! 成果だけでなくリスクと次の期間のフォローアップもレポートに含められるようにします。
+
+use crate::github::{Issue, PullRequest};
+use chrono::NaiveDate;
+use serde::Serialize;
+
+/// 長期間オープンとみなすPRの作成からの日数
+pub const LONG_OPEN_DAYS: i64 = 30;
+
+/// 動きがないとみなすIssueの最後の活動（作成・コメント）からの日数
+pub const STALE_ISSUE_DAYS: i64 = 60;
+
+/// 出荷されなかった作業の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnshippedKind {
+    /// 作成から[`LONG_OPEN_DAYS`]日以上オープンのままのPR
+    LongOpenPr,
+    /// 最後の活動から[`STALE_ISSUE_DAYS`]日以上動きのないオープンなIssue
+    StaleIssue,
+    /// マージされずにクローズしたPR
+    ClosedUnmerged,
+}
+
+/// 出荷されなかった作業1件
+#[derive(Debug, Clone, Serialize)]
+pub struct UnshippedItem {
+    pub kind: UnshippedKind,
+    pub url: String,
+    pub title: String,
+    pub repository: String,
+    /// 長期間オープンのPRは作成から、動きのないIssueは最後の活動から期間の終わりまで、
+    /// クローズしたPRは作成からクローズまでの日数
+    pub days: i64,
+    pub is_draft: bool,
+}
+
+/// 期間の終わり（`until`）の時点で出荷されていない作業（種類ごとに日数の長い順）
+pub fn collect(prs: &[PullRequest], issues: &[Issue], until: &str) -> Vec<UnshippedItem> {
+    let Some(until) = date(until) else {
+        return Vec::new();
+    };
+    let days_until = |time: &str| date(time).map(|d| (until - d).num_days());
+
+    let mut items: Vec<UnshippedItem> = Vec::new();
+    for pr in prs {
+        let (kind, days) = match pr.state.as_str() {
+            "open" => match days_until(&pr.created_at) {
+                Some(days) if days >= LONG_OPEN_DAYS => (UnshippedKind::LongOpenPr, days),
+                _ => continue,
+            },
+            "closed" => {
+                let closed = pr.closed_at.as_deref().and_then(date);
+                let days = closed
+                    .zip(date(&pr.created_at))
+                    .map_or(0, |(closed, created)| (closed - created).num_days());
+                (UnshippedKind::ClosedUnmerged, days)
+            }
+            _ => continue,
+        };
+        items.push(UnshippedItem {
+            kind,
+            url: pr.url.clone(),
+            title: pr.title.clone(),
+            repository: pr.repository.name_with_owner.clone(),
+            days,
+            is_draft: pr.is_draft,
+        });
+    }
+    for issue in issues.iter().filter(|issue| issue.state == "open") {
+        let last_activity = issue
+            .comments
+            .iter()
+            .map(|c| c.created_at.as_str())
+            .chain([issue.created_at.as_str()])
+            .max()
+            .unwrap_or_default();
+        match days_until(last_activity) {
+            Some(days) if days >= STALE_ISSUE_DAYS => items.push(UnshippedItem {
+                kind: UnshippedKind::StaleIssue,
+                url: issue.url.clone(),
+                title: issue.title.clone(),
+                repository: issue.repository.name_with_owner.clone(),
+                days,
+                is_draft: false,
+            }),
+            _ => {}
+        }
+    }
+
+    items.sort_by(|a, b| a.kind.cmp(&b.kind).then_with(|| b.days.cmp(&a.days)));
+    items
+}
+
+/// 種類ごとの件数
+pub fn count(items: &[UnshippedItem], kind: UnshippedKind) -> usize {
+    items.iter().filter(|item| item.kind == kind).count()
+}
+
+fn date(time: &str) -> Option<NaiveDate> {
+    time.get(..10)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+}
@@ -0,0 +1,80 @@
+//! `--replay`で記録済みの`gh`とLLMの応答を再生する結合テスト
+//!
+//! `tests/fixtures/replay`は`--record`で記録したものです。プロンプトの構築を変えると
+//! LLMの応答のキー（`llm/`のファイル名）が変わるため、記録し直してください。
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/replay");
+
+// 記録したときと同じ対象・期間で実行する（ユーザーの設定・キャッシュ・履歴は使わない）
+fn replay(name: &str, extra: &[&str]) -> Output {
+    let home = std::env::temp_dir().join(format!("easyhyoka-replay-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&home);
+    let output = Command::new(env!("CARGO_BIN_EXE_easy-hyoka"))
+        .args([
+            "--owner",
+            "acme",
+            "--author",
+            "alice",
+            "--since",
+            "2025-01-01",
+            "--until",
+            "2025-01-31",
+            "--no-resume",
+            "--no-history",
+            "--replay",
+            FIXTURE,
+        ])
+        .args(extra)
+        .env("HOME", &home)
+        .env("XDG_CONFIG_HOME", home.join("config"))
+        .env("XDG_CACHE_HOME", home.join("cache"))
+        .env("XDG_DATA_HOME", home.join("data"))
+        .env_remove("OPENAI_API_KEY")
+        .env_remove("ANTHROPIC_API_KEY")
+        // ghを呼び出した場合は失敗させる
+        .env("PATH", PathBuf::from("/nonexistent"))
+        .output()
+        .expect("easyhyoka should start");
+    let _ = std::fs::remove_dir_all(&home);
+    output
+}
+
+#[test]
+fn replay_prints_the_recorded_summary() {
+    let output = replay("summary", &["--quiet"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("検索結果のパーサーを追加し"), "{stdout}");
+    assert!(
+        stdout.contains("https://github.com/acme/x/pull/4"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn replay_builds_the_prompt_from_recorded_activity() {
+    let output = replay("prompt", &["--show-prompts"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("Add search result parser"), "{stdout}");
+    // コメントの多いPRには記録したコメントが付く
+    assert!(stdout.contains("LGTMです"), "{stdout}");
+}
+
+#[test]
+fn replay_refuses_to_publish() {
+    let output = replay("publish", &["--gist"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--gist"));
+}